@@ -60,6 +60,52 @@ from = From:
 to = To:
 confirm = Confirm
 
+# Loans
+account-type = Account type
+account-type-checking = Checking
+account-type-loan = Loan
+principal = Principal
+interest = Interest
+interest-rate = Annual interest rate (%)
+loan-term = Number of payments
+payment-frequency = Payment frequency
+frequency-weekly = Weekly
+frequency-biweekly = Every two weeks
+frequency-monthly = Monthly
+frequency-quarterly = Quarterly
+frequency-yearly = Yearly
+start-date = Start date
+remaining-balance = Remaining balance: {$balance} {$currency}
+loan-installment = Installment: {$amount} {$currency} ({$frequency})
+loan-payment = Payment
+loan-payment-split = Principal: {$principal} {$currency}, interest: {$interest} {$currency}
+payoff-date = Payoff date: {$date}
+payoff-never = The installment does not cover the interest
+loan-paid-off = Loan paid off
+show-schedule = Show amortization schedule
+hide-schedule = Hide amortization schedule
+
+# Investments
+account-type-investment = Investment
+market-value = Market value: {$value} {$currency}
+unrealized-gain = Unrealized gain: {$value} {$currency}
+no-holdings = No securities held
+symbol = Symbol
+quantity = Quantity
+cost-basis = Cost basis
+price = Price
+value = Value
+gain = Gain
+operation = Operation
+buy = Buy
+sell = Sell
+dividend = Dividend
+add-investment-operation = Add operation
+update-price = Update price
+import-prices = Import prices
+prices-import-success = {$count} prices imported
+prices-import-error = Error happened during price import
+
 view-settings = View settings
 add-category = Add category
 new-category = New category
@@ -71,6 +117,162 @@ expense-categories = Expense categories
 expenses-period = Expenses by period
 income-period = Income by period
 
+# Account groups
+account-type-cash = Cash
+account-type-savings = Savings
+account-type-asset = Asset
+account-groups = Groups
+account-group = Group
+group-name = Group name
+no-group = Other accounts
+close = Close
+exclude-from-total = Exclude from total balance
+exclude-from-statistics = Exclude from statistics
+
+# Credit cards
+account-type-credit = Credit card
+closing-day = Statement closing day
+due-day = Payment due day
+next-closing-date = Next statement closes on {$date}
+closing-date = Closing date
+due-date = Due date
+paid = Paid
+pay-from = Pay from
+pay-statement = Pay statement
+statement-payment = Credit card statement of {$date}
+statement-due = Statement payment of {$amount} {$currency} due on {$date} ({$days} days left)
+statement-overdue = Statement payment of {$amount} {$currency} was due on {$date}
+
+# Reconciliation
+reconcile = Reconcile
+statement-end-date = Statement end date
+statement-balance = Statement balance
+cleared-balance = Cleared balance: {$balance} {$currency}
+reconcile-difference = Difference: {$difference} {$currency}
+finish-reconcile = Finish reconciliation
+nothing-to-reconcile = No transactions to reconcile up to this date
+status-uncleared = Uncleared
+status-cleared = Cleared
+status-reconciled = Reconciled
+transaction-reconciled-error = Reconciled transactions can't be changed or deleted
+transaction-change-error = Could not save the changes to the transaction
+status = Status
+from-account = From account
+to-account = To account
+from-account-status = Status in the from account
+to-account-status = Status in the to account
+
+# Projections
+current-balance = Current balance: {$balance} {$currency}
+projected-balance = Projected balance in {$days} days: {$balance} {$currency}
+negative-balance-warning = Warning: balance falls to {$balance} {$currency} on {$date}
+scheduled = Scheduled
+projection-horizon = Projection horizon
+projection-horizon-desc = Number of days ahead included in projected balances and negative balance warnings.
+
+# Search
+page-search = Search
+search-placeholder = Search transactions
+search-hint = Type in the header search field to look through descriptions, payees, categories and accounts.
+search-results = {$count} matching transactions
+no-search-results = No transactions match the search
+min-amount = Minimum amount
+max-amount = Maximum amount
+filter-by-date = Only within a date range
+
+# Duplicates
+page-duplicates = Duplicates
+duplicates-desc = These transactions have the same account, close amounts and dates and similar descriptions. Keep one of them to merge the pair, or keep both if they are distinct.
+no-duplicates = No possible duplicates found
+keep-first = Keep first
+keep-second = Keep second
+keep-both = Keep both
+merge-duplicate-error = Could not merge the transactions, reconciled transactions can't be removed
+duplicates-found = {$count} possible duplicate transactions found, review them in Duplicates
+duplicate-window = Duplicate detection window
+duplicate-window-desc = Maximum number of days between two transactions that can be flagged as duplicates.
+
+# Rules
+page-rules = Rules
+rules-desc = Rules fill in transactions as they are entered or imported. The first matching rule applies; empty conditions match every transaction.
+no-rules = No rules yet
+add-rule = Add rule
+new-rule = New rule
+rule-name = Rule name
+rule-when = When
+rule-then = Then set
+description-contains = Description contains
+regular-expression = Regular expression
+keep-category = Keep category
+any-transaction = Any transaction
+tag = Tag
+tags = Tags
+preview = Preview
+apply-rule = Apply to existing transactions
+rule-preview = {$count} transactions match this rule
+rule-applied = Rule applied to {$count} transactions
+rule-apply-error = Could not apply the rule
+rule-save-error = Could not save the rule
+invalid-pattern = Invalid regular expression
+
+# CSV import
+page-csv-import = Import CSV
+import-csv = Import CSV
+csv-import-desc = Import the transactions of a bank statement exported as CSV. Choose a file to start; its format is detected and can be corrected before importing.
+choose-file = Choose file
+next = Next
+back = Back
+csv-format = File format
+csv-profile = Bank profile
+csv-detect = Detect automatically
+csv-encoding = Encoding
+csv-delimiter = Delimiter
+csv-tab = Tab
+csv-date-format = Date format
+csv-has-header = First line is a header
+csv-decimal-comma = Amounts use a decimal comma
+csv-sample = First lines of {$file}
+csv-mapping = Columns
+csv-mapping-desc = Choose the columns holding each value. Use either a signed amount or separate debit and credit columns.
+csv-no-column = Not in the file
+csv-column = Column {$number}
+csv-debit = Debit
+csv-credit = Credit
+csv-target = Import into
+csv-expense-category = Category for expenses
+csv-income-category = Category for income
+csv-save-profile = Remember as bank profile
+csv-profile-name = Bank name
+csv-profile-saved = Bank profile saved
+csv-profile-save-error = Could not save the bank profile
+csv-preview = Preview
+csv-preview-counts = {$count} transactions will be imported into {$account}
+csv-preview-problems = {$count} lines cannot be read and will be skipped
+csv-preview-more = and {$count} more
+csv-read-error = Could not read the selected file
+csv-import-success = {$count} transactions imported
+
+# Statement import
+page-statement-import = Statement import
+import-statement = Import statement
+statement-import-desc = Import a statement file from your bank, such as OFX, QFX, CAMT.053/CAMT.052 XML or MT940. Transactions already imported from an earlier statement are skipped.
+statement-file = Statements in {$file}
+statement-account = Account {$account} {$currency}
+statement-unknown-account = without number
+statement-counts = {$count} transactions, {$new} not imported yet
+statement-ledger-balance = Balance at the bank: {$amount} {$currency} on {$date}
+statement-categories-desc = Categories given to the imported transactions that no rule categorizes
+statement-reconcile = Reconcile
+statement-reconcile-desc = Compare the imported accounts with the balance reported by the bank.
+statement-balance-on = {$amount} on {$date}
+statement-import-success = {$added} transactions imported, {$skipped} already present
+statement-read-error = Could not read the statement: {$error}
+statement-unknown-format = The file is not in a supported statement format
+statement-empty = The file holds no bank or credit card statement
+statement-invalid-entry = Transaction {$entry} has no valid {$field}
+statement-invalid-field = Field {$field} has an invalid value: {$value}
+statement-balance-mismatch = In statement {$statement} the opening balance {$opening} plus the entries {$total} does not make the closing balance {$closing}
+
 # Transactios page
 transactions = Transactions
 transfers = Transfers
@@ -80,11 +282,57 @@ amount = Amount
 income = Income
 expense = Expense
 note = Note
+payee = Payee
+suggested-category = Suggested: {$category} ({$confidence}%)
 not-found = Not found
 date = Date
 edit-transaction = Edit transaction
 delete-transaction = Delete transaction
 
+filter = Filter
+hide-filters = Hide filters
+clear-filter = Clear filter
+all-accounts = All accounts
+all-categories = All categories
+all-types = Income and expenses
+transaction-type = Type
+text = Text
+net = Net
+quick-add = Quick add
+quick-add-desc = Add a transaction from a template, dated today.
+template-name = Template name
+save-as-template = Save as template
+template-saved = Template saved
+template-save-error = Could not save the template, template names must be unique
+select = Select
+done = Done
+select-all = Select all
+selected-transactions = {$count} transactions selected
+apply = Apply
+add-tag = Add tag
+shift-days = Shift by days
+shift-date = Shift date
+delete-selected = Delete selected
+undo = Undo
+bulk-edit-done = {$count} transactions changed
+bulk-edit-error = Could not change the selected transactions
+bulk-undo-outdated = The transactions changed after the edit, it can no longer be undone
+dates = Dates
+viewed-month = Month shown
+this-month = This month
+last-month = Last month
+this-quarter = This quarter
+this-year = This year
+last-30-days = Last 30 days
+custom-range = Custom range
+filter-name = Filter name
+save-filter = Save filter
+filter-saved = Filter saved to the sidebar
+filter-save-error = Could not save the filter, is the name already used?
+saved-filter = Saved filter
+saved-filter-invalid = This filter can't be read, delete it and save it again: {$error}
+delete-filter = Delete filter
+
 month-1 = January
 month-2 = February
 month-3 = March
@@ -106,13 +354,30 @@ minutes = Minutes:
 
 currency = Currency
 import-export = Import/Export data
-import-export-desc = Here you can import or export your data.
+import-export-desc = Here you can import or export your data. Import replaces everything with the file, merge import only adds what is missing and updates what changed.
 import = Import
+merge-import = Merge import
 export = Export
 export-completed = Export to file completed
 export-error = Error happened during file export
 import-error = Error happened during file import
 import-success = Import from file completed
+merge-import-success = Merge completed: {$added} added, {$updated} updated, {$skipped} unchanged
+import-error-detail = Import failed, nothing was changed: {$error}
+import-preview = Import preview
+import-preview-counts = {$accounts} accounts, {$categories} categories, {$transactions} transactions and {$transfers} transfers
+import-preview-dates = From {$first} to {$last}
+import-preview-replace = Importing replaces all the data currently in the app.
+import-preview-merge = Importing adds the missing records and updates the changed ones.
+import-preview-problems = The file has {$count} problems and cannot be imported
+csv-export = CSV export
+csv-export-desc = Exports transactions and transfers to two CSV files that spreadsheets can open.
+export-csv = Export CSV
+csv-export-completed = Exported {$transactions} transactions and {$transfers} transfers
+import-qif = Import QIF
+export-qif = Export QIF
+qif-export-completed = Exported {$accounts} accounts to QIF
+qif-invalid-line = Line {$line} of the QIF file has an invalid value: {$value}
 operation-cancelled = Operation cancelled
 
 statistics = Statistics
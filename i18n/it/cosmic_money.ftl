@@ -60,6 +60,52 @@ from = Da:
 to = A:
 confirm = Conferma
 
+# Prestiti
+account-type = Tipo di conto
+account-type-checking = Conto corrente
+account-type-loan = Prestito
+principal = Capitale
+interest = Interessi
+interest-rate = Tasso di interesse annuo (%)
+loan-term = Numero di rate
+payment-frequency = Frequenza delle rate
+frequency-weekly = Settimanale
+frequency-biweekly = Ogni due settimane
+frequency-monthly = Mensile
+frequency-quarterly = Trimestrale
+frequency-yearly = Annuale
+start-date = Data di inizio
+remaining-balance = Debito residuo: {$balance} {$currency}
+loan-installment = Rata: {$amount} {$currency} ({$frequency})
+loan-payment = Rata
+loan-payment-split = Capitale: {$principal} {$currency}, interessi: {$interest} {$currency}
+payoff-date = Data di estinzione: {$date}
+payoff-never = La rata non copre gli interessi
+loan-paid-off = Prestito estinto
+show-schedule = Mostra piano di ammortamento
+hide-schedule = Nascondi piano di ammortamento
+
+# Investimenti
+account-type-investment = Investimento
+market-value = Valore di mercato: {$value} {$currency}
+unrealized-gain = Plusvalenza latente: {$value} {$currency}
+no-holdings = Nessun titolo in portafoglio
+symbol = Simbolo
+quantity = Quantità
+cost-basis = Prezzo di carico
+price = Prezzo
+value = Valore
+gain = Guadagno
+operation = Operazione
+buy = Acquisto
+sell = Vendita
+dividend = Dividendo
+add-investment-operation = Aggiungi operazione
+update-price = Aggiorna prezzo
+import-prices = Importa prezzi
+prices-import-success = {$count} prezzi importati
+prices-import-error = Errore durante l'importazione dei prezzi
+
 view-settings = Impostazioni di visualizzazione
 add-category = Aggiungi categoria
 new-category = Nuova categoria
@@ -71,6 +117,162 @@ expense-categories = Categorie di spese
 expenses-period = Spese per periodo
 income-period = Entrate per periodo
 
+# Gruppi di conti
+account-type-cash = Contanti
+account-type-savings = Risparmi
+account-type-asset = Beni
+account-groups = Gruppi
+account-group = Gruppo
+group-name = Nome del gruppo
+no-group = Altri conti
+close = Chiudi
+exclude-from-total = Escludi dal saldo totale
+exclude-from-statistics = Escludi dalle statistiche
+
+# Carte di credito
+account-type-credit = Carta di credito
+closing-day = Giorno di chiusura dell'estratto conto
+due-day = Giorno di scadenza del pagamento
+next-closing-date = Il prossimo estratto conto chiude il {$date}
+closing-date = Data di chiusura
+due-date = Scadenza
+paid = Pagato
+pay-from = Paga da
+pay-statement = Paga estratto conto
+statement-payment = Estratto conto della carta di credito del {$date}
+statement-due = Pagamento di {$amount} {$currency} in scadenza il {$date} (mancano {$days} giorni)
+statement-overdue = Il pagamento di {$amount} {$currency} era in scadenza il {$date}
+
+# Riconciliazione
+reconcile = Riconcilia
+statement-end-date = Data di fine estratto conto
+statement-balance = Saldo dell'estratto conto
+cleared-balance = Saldo verificato: {$balance} {$currency}
+reconcile-difference = Differenza: {$difference} {$currency}
+finish-reconcile = Concludi riconciliazione
+nothing-to-reconcile = Nessuna transazione da riconciliare fino a questa data
+status-uncleared = Non verificata
+status-cleared = Verificata
+status-reconciled = Riconciliata
+transaction-reconciled-error = Le transazioni riconciliate non possono essere modificate o eliminate
+transaction-change-error = Impossibile salvare le modifiche alla transazione
+status = Stato
+from-account = Dal conto
+to-account = Al conto
+from-account-status = Stato nel conto di partenza
+to-account-status = Stato nel conto di arrivo
+
+# Proiezioni
+current-balance = Saldo attuale: {$balance} {$currency}
+projected-balance = Saldo previsto tra {$days} giorni: {$balance} {$currency}
+negative-balance-warning = Attenzione: il saldo scende a {$balance} {$currency} il {$date}
+scheduled = Programmata
+projection-horizon = Orizzonte di previsione
+projection-horizon-desc = Numero di giorni futuri inclusi nel saldo previsto e negli avvisi di saldo negativo.
+
+# Ricerca
+page-search = Ricerca
+search-placeholder = Cerca transazioni
+search-hint = Scrivi nel campo di ricerca in alto per cercare tra note, beneficiari, categorie e conti.
+search-results = {$count} transazioni trovate
+no-search-results = Nessuna transazione corrisponde alla ricerca
+min-amount = Importo minimo
+max-amount = Importo massimo
+filter-by-date = Solo in un intervallo di date
+
+# Duplicati
+page-duplicates = Duplicati
+duplicates-desc = Queste transazioni hanno lo stesso conto, importi e date vicini e descrizioni simili. Mantienine una per unire la coppia, oppure mantienile entrambe se sono distinte.
+no-duplicates = Nessun possibile duplicato trovato
+keep-first = Mantieni la prima
+keep-second = Mantieni la seconda
+keep-both = Mantieni entrambe
+merge-duplicate-error = Impossibile unire le transazioni, le transazioni riconciliate non possono essere rimosse
+duplicates-found = Trovate {$count} possibili transazioni duplicate, controllale in Duplicati
+duplicate-window = Intervallo per i duplicati
+duplicate-window-desc = Numero massimo di giorni tra due transazioni che possono essere segnalate come duplicate.
+
+# Regole
+page-rules = Regole
+rules-desc = Le regole compilano le transazioni quando vengono inserite o importate. Si applica la prima regola corrispondente; le condizioni vuote corrispondono a ogni transazione.
+no-rules = Ancora nessuna regola
+add-rule = Aggiungi regola
+new-rule = Nuova regola
+rule-name = Nome della regola
+rule-when = Quando
+rule-then = Allora imposta
+description-contains = La descrizione contiene
+regular-expression = Espressione regolare
+keep-category = Mantieni la categoria
+any-transaction = Qualsiasi transazione
+tag = Etichetta
+tags = Etichette
+preview = Anteprima
+apply-rule = Applica alle transazioni esistenti
+rule-preview = {$count} transazioni corrispondono a questa regola
+rule-applied = Regola applicata a {$count} transazioni
+rule-apply-error = Impossibile applicare la regola
+rule-save-error = Impossibile salvare la regola
+invalid-pattern = Espressione regolare non valida
+
+# Importazione CSV
+page-csv-import = Importa CSV
+import-csv = Importa CSV
+csv-import-desc = Importa le transazioni di un estratto conto esportato in CSV. Scegli un file per iniziare; il formato viene rilevato e può essere corretto prima dell'importazione.
+choose-file = Scegli file
+next = Avanti
+back = Indietro
+csv-format = Formato del file
+csv-profile = Profilo banca
+csv-detect = Rileva automaticamente
+csv-encoding = Codifica
+csv-delimiter = Separatore
+csv-tab = Tabulazione
+csv-date-format = Formato data
+csv-has-header = La prima riga è un'intestazione
+csv-decimal-comma = Gli importi usano la virgola decimale
+csv-sample = Prime righe di {$file}
+csv-mapping = Colonne
+csv-mapping-desc = Scegli le colonne che contengono ciascun valore. Usa un importo con segno oppure colonne separate per addebiti e accrediti.
+csv-no-column = Non presente nel file
+csv-column = Colonna {$number}
+csv-debit = Addebito
+csv-credit = Accredito
+csv-target = Importa in
+csv-expense-category = Categoria per le uscite
+csv-income-category = Categoria per le entrate
+csv-save-profile = Ricorda come profilo banca
+csv-profile-name = Nome della banca
+csv-profile-saved = Profilo banca salvato
+csv-profile-save-error = Impossibile salvare il profilo banca
+csv-preview = Anteprima
+csv-preview-counts = {$count} transazioni verranno importate in {$account}
+csv-preview-problems = {$count} righe non possono essere lette e verranno saltate
+csv-preview-more = e altre {$count}
+csv-read-error = Impossibile leggere il file selezionato
+csv-import-success = {$count} transazioni importate
+
+# Importazione estratti conto
+page-statement-import = Importa estratto conto
+import-statement = Importa estratto conto
+statement-import-desc = Importa un estratto conto della tua banca, per esempio OFX, QFX, XML CAMT.053/CAMT.052 o MT940. Le transazioni già importate da un estratto precedente vengono saltate.
+statement-file = Estratti conto in {$file}
+statement-account = Conto {$account} {$currency}
+statement-unknown-account = senza numero
+statement-counts = {$count} transazioni, {$new} non ancora importate
+statement-ledger-balance = Saldo in banca: {$amount} {$currency} al {$date}
+statement-categories-desc = Categorie date alle transazioni importate che nessuna regola categorizza
+statement-reconcile = Riconcilia
+statement-reconcile-desc = Confronta i conti importati con il saldo indicato dalla banca.
+statement-balance-on = {$amount} al {$date}
+statement-import-success = {$added} transazioni importate, {$skipped} già presenti
+statement-read-error = Impossibile leggere l'estratto conto: {$error}
+statement-unknown-format = Il file non è in un formato di estratto conto supportato
+statement-empty = Il file non contiene estratti conto bancari o di carte di credito
+statement-invalid-entry = La transazione {$entry} non ha un {$field} valido
+statement-invalid-field = Il campo {$field} ha un valore non valido: {$value}
+statement-balance-mismatch = Nell'estratto {$statement} il saldo iniziale {$opening} più i movimenti {$total} non dà il saldo finale {$closing}
+
 # Pagina transazioni
 transactions = Transazioni
 transfers = Trasferimenti
@@ -80,11 +282,57 @@ amount = Importo
 income = Entrata
 expense = Spesa
 note = Nota
+payee = Beneficiario
+suggested-category = Suggerita: {$category} ({$confidence}%)
 not-found = Non trovato
 date = Data
 edit-transaction = Modifica transazione
 delete-transaction = Elimina transazione
 
+filter = Filtra
+hide-filters = Nascondi filtri
+clear-filter = Rimuovi filtro
+all-accounts = Tutti i conti
+all-categories = Tutte le categorie
+all-types = Entrate e uscite
+transaction-type = Tipo
+text = Testo
+net = Netto
+quick-add = Aggiunta rapida
+quick-add-desc = Aggiungi una transazione da un modello, con la data di oggi.
+template-name = Nome del modello
+save-as-template = Salva come modello
+template-saved = Modello salvato
+template-save-error = Impossibile salvare il modello, i nomi dei modelli devono essere unici
+select = Seleziona
+done = Fatto
+select-all = Seleziona tutto
+selected-transactions = {$count} transazioni selezionate
+apply = Applica
+add-tag = Aggiungi etichetta
+shift-days = Sposta di giorni
+shift-date = Sposta data
+delete-selected = Elimina selezionate
+undo = Annulla modifica
+bulk-edit-done = {$count} transazioni modificate
+bulk-edit-error = Impossibile modificare le transazioni selezionate
+bulk-undo-outdated = Le transazioni sono cambiate dopo la modifica, non è più possibile annullarla
+dates = Date
+viewed-month = Mese visualizzato
+this-month = Questo mese
+last-month = Mese scorso
+this-quarter = Questo trimestre
+this-year = Quest'anno
+last-30-days = Ultimi 30 giorni
+custom-range = Intervallo personalizzato
+filter-name = Nome del filtro
+save-filter = Salva filtro
+filter-saved = Filtro salvato nella barra laterale
+filter-save-error = Impossibile salvare il filtro, il nome è già in uso?
+saved-filter = Filtro salvato
+saved-filter-invalid = Impossibile leggere questo filtro, eliminalo e salvalo di nuovo: {$error}
+delete-filter = Elimina filtro
+
 month-1 = Gennaio
 month-2 = Febbraio
 month-3 = Marzo
@@ -106,13 +354,30 @@ minutes = Minuti:
 
 currency = Valuta
 import-export = Importa/Esporta dati
-import-export-desc = Qui puoi importare o esportare i tuoi dati.
+import-export-desc = Qui puoi importare o esportare i tuoi dati. L'importazione sostituisce tutto con il file, l'importazione con unione aggiunge solo ciò che manca e aggiorna ciò che è cambiato.
 import = Importa
+merge-import = Importa con unione
 export = Esporta
 export-completed = Esportazione su file completata
 export-error = Si è verificato un errore durante l'esportazione del file
 import-error = Si è verificato un errore durante l'importazione del file
 import-success = Importazione da file completata
+merge-import-success = Unione completata: {$added} aggiunti, {$updated} aggiornati, {$skipped} invariati
+import-error-detail = Importazione non riuscita, nessuna modifica effettuata: {$error}
+import-preview = Anteprima importazione
+import-preview-counts = {$accounts} conti, {$categories} categorie, {$transactions} transazioni e {$transfers} trasferimenti
+import-preview-dates = Dal {$first} al {$last}
+import-preview-replace = L'importazione sostituisce tutti i dati attualmente nell'app.
+import-preview-merge = L'importazione aggiunge i record mancanti e aggiorna quelli modificati.
+import-preview-problems = Il file ha {$count} problemi e non può essere importato
+csv-export = Esportazione CSV
+csv-export-desc = Esporta transazioni e trasferimenti in due file CSV apribili con un foglio di calcolo.
+export-csv = Esporta CSV
+csv-export-completed = Esportate {$transactions} transazioni e {$transfers} trasferimenti
+import-qif = Importa QIF
+export-qif = Esporta QIF
+qif-export-completed = Esportati {$accounts} conti in QIF
+qif-invalid-line = La riga {$line} del file QIF ha un valore non valido: {$value}
 operation-cancelled = Operazione annullata
 
 statistics = Statistiche
@@ -0,0 +1,3 @@
+DROP TABLE loan_payment;
+DROP TABLE loan;
+ALTER TABLE account DROP COLUMN account_type;
@@ -0,0 +1,22 @@
+ALTER TABLE account ADD COLUMN account_type INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE loan (
+  id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+  account_id INTEGER NOT NULL UNIQUE,
+  principal REAL NOT NULL,
+  interest_rate REAL NOT NULL DEFAULT 0,
+  term INTEGER NOT NULL,
+  payment_frequency INTEGER NOT NULL DEFAULT 12,
+  start_date DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+  FOREIGN KEY (account_id) REFERENCES account(id)
+);
+
+CREATE TABLE loan_payment (
+  id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+  loan_id INTEGER NOT NULL,
+  transfer_id INTEGER NOT NULL UNIQUE,
+  principal_amount REAL NOT NULL,
+  interest_amount REAL NOT NULL,
+  FOREIGN KEY (loan_id) REFERENCES loan(id),
+  FOREIGN KEY (transfer_id) REFERENCES account_transfer(id)
+);
@@ -0,0 +1,3 @@
+DROP TABLE investment_transaction;
+DROP TABLE security_price;
+DROP TABLE security;
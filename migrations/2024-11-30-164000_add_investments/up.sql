@@ -0,0 +1,27 @@
+CREATE TABLE security (
+  id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+  symbol VARCHAR NOT NULL UNIQUE,
+  security_name VARCHAR NOT NULL DEFAULT ''
+);
+
+CREATE TABLE security_price (
+  id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+  security_id INTEGER NOT NULL,
+  price_date DATETIME NOT NULL,
+  price REAL NOT NULL,
+  UNIQUE (security_id, price_date),
+  FOREIGN KEY (security_id) REFERENCES security(id)
+);
+
+CREATE TABLE investment_transaction (
+  id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+  account_id INTEGER NOT NULL,
+  security_id INTEGER NOT NULL,
+  kind INTEGER NOT NULL,
+  quantity REAL NOT NULL DEFAULT 0,
+  price REAL NOT NULL DEFAULT 0,
+  amount REAL NOT NULL,
+  transaction_date DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+  FOREIGN KEY (account_id) REFERENCES account(id),
+  FOREIGN KEY (security_id) REFERENCES security(id)
+);
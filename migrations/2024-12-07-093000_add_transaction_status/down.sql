@@ -0,0 +1,2 @@
+ALTER TABLE account_transfer DROP COLUMN status;
+ALTER TABLE money_transaction DROP COLUMN status;
@@ -0,0 +1,2 @@
+ALTER TABLE money_transaction ADD COLUMN status INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE account_transfer ADD COLUMN status INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,7 @@
+CREATE TABLE credit_card (
+  id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+  account_id INTEGER NOT NULL UNIQUE,
+  closing_day INTEGER NOT NULL,
+  due_day INTEGER NOT NULL,
+  FOREIGN KEY (account_id) REFERENCES account(id)
+);
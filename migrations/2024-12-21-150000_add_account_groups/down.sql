@@ -0,0 +1,4 @@
+ALTER TABLE account DROP COLUMN exclude_from_statistics;
+ALTER TABLE account DROP COLUMN exclude_from_total;
+ALTER TABLE account DROP COLUMN group_id;
+DROP TABLE account_group;
@@ -0,0 +1,8 @@
+CREATE TABLE account_group (
+  id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+  group_name TEXT NOT NULL UNIQUE
+);
+
+ALTER TABLE account ADD COLUMN group_id INTEGER REFERENCES account_group(id);
+ALTER TABLE account ADD COLUMN exclude_from_total BOOLEAN NOT NULL DEFAULT 0;
+ALTER TABLE account ADD COLUMN exclude_from_statistics BOOLEAN NOT NULL DEFAULT 0;
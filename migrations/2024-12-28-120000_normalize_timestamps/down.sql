@@ -0,0 +1 @@
+-- Normalized timestamps are equivalent to the original ones, nothing to undo.
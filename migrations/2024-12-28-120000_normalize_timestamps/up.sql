@@ -0,0 +1,23 @@
+-- Timestamps are stored as UTC text and compared as strings: rewrite every
+-- value in the canonical 'YYYY-MM-DD HH:MM:SS' form, so rows written with a
+-- 'T' separator or fractional seconds sort and filter like the others.
+UPDATE money_transaction SET transaction_date = datetime(transaction_date)
+  WHERE datetime(transaction_date) IS NOT NULL;
+UPDATE account_transfer SET transfer_date = datetime(transfer_date)
+  WHERE datetime(transfer_date) IS NOT NULL;
+UPDATE investment_transaction SET transaction_date = datetime(transaction_date)
+  WHERE datetime(transaction_date) IS NOT NULL;
+-- Prices are unique per security and day: when two rows normalize to the
+-- same timestamp, keep the one written last.
+DELETE FROM security_price
+  WHERE EXISTS (
+    SELECT 1 FROM security_price AS newer
+    WHERE newer.security_id = security_price.security_id
+      AND newer.id > security_price.id
+      AND coalesce(datetime(newer.price_date), newer.price_date)
+        = coalesce(datetime(security_price.price_date), security_price.price_date)
+  );
+UPDATE security_price SET price_date = datetime(price_date)
+  WHERE datetime(price_date) IS NOT NULL;
+UPDATE loan SET start_date = datetime(start_date)
+  WHERE datetime(start_date) IS NOT NULL;
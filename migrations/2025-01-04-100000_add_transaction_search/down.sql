@@ -0,0 +1,7 @@
+DROP TRIGGER transaction_search_account;
+DROP TRIGGER transaction_search_category;
+DROP TRIGGER transaction_search_delete;
+DROP TRIGGER transaction_search_update;
+DROP TRIGGER transaction_search_insert;
+DROP TABLE transaction_search;
+ALTER TABLE money_transaction DROP COLUMN payee;
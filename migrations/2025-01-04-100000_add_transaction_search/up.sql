@@ -0,0 +1,53 @@
+ALTER TABLE money_transaction ADD COLUMN payee TEXT;
+
+-- Search index over the text of every transaction, keyed by the transaction id.
+CREATE VIRTUAL TABLE transaction_search USING fts5(
+  description,
+  payee,
+  category_name,
+  account_name,
+  tokenize = 'unicode61 remove_diacritics 2'
+);
+
+INSERT INTO transaction_search (rowid, description, payee, category_name, account_name)
+SELECT t.id, t.description, COALESCE(t.payee, ''), COALESCE(c.name, ''), COALESCE(a.name, '')
+FROM money_transaction t
+LEFT JOIN category c ON c.id = t.transaction_category
+LEFT JOIN account a ON a.id = t.bank_account;
+
+CREATE TRIGGER transaction_search_insert AFTER INSERT ON money_transaction BEGIN
+  INSERT INTO transaction_search (rowid, description, payee, category_name, account_name)
+  VALUES (
+    new.id,
+    new.description,
+    COALESCE(new.payee, ''),
+    COALESCE((SELECT name FROM category WHERE id = new.transaction_category), ''),
+    COALESCE((SELECT name FROM account WHERE id = new.bank_account), '')
+  );
+END;
+
+CREATE TRIGGER transaction_search_update AFTER UPDATE ON money_transaction BEGIN
+  DELETE FROM transaction_search WHERE rowid = old.id;
+  INSERT INTO transaction_search (rowid, description, payee, category_name, account_name)
+  VALUES (
+    new.id,
+    new.description,
+    COALESCE(new.payee, ''),
+    COALESCE((SELECT name FROM category WHERE id = new.transaction_category), ''),
+    COALESCE((SELECT name FROM account WHERE id = new.bank_account), '')
+  );
+END;
+
+CREATE TRIGGER transaction_search_delete AFTER DELETE ON money_transaction BEGIN
+  DELETE FROM transaction_search WHERE rowid = old.id;
+END;
+
+CREATE TRIGGER transaction_search_category AFTER UPDATE OF name ON category BEGIN
+  UPDATE transaction_search SET category_name = new.name
+  WHERE rowid IN (SELECT id FROM money_transaction WHERE transaction_category = new.id);
+END;
+
+CREATE TRIGGER transaction_search_account AFTER UPDATE OF name ON account BEGIN
+  UPDATE transaction_search SET account_name = new.name
+  WHERE rowid IN (SELECT id FROM money_transaction WHERE bank_account = new.id);
+END;
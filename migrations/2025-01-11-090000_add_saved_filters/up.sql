@@ -0,0 +1,6 @@
+CREATE TABLE saved_filter (
+  id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+  filter_name TEXT NOT NULL UNIQUE,
+  -- TransactionFilter serialized as JSON
+  definition TEXT NOT NULL
+);
@@ -0,0 +1 @@
+DROP INDEX money_transaction_date_id;
@@ -0,0 +1,2 @@
+-- Backs the (date, id) keyset used to page through transactions
+CREATE INDEX money_transaction_date_id ON money_transaction (transaction_date DESC, id DESC);
@@ -0,0 +1,8 @@
+-- Transaction pairs the user marked as not being duplicates, oldest first.
+-- Transaction ids are never reused, so rows left by deleted transactions are
+-- harmless.
+CREATE TABLE dismissed_duplicate (
+  first_id INTEGER NOT NULL,
+  second_id INTEGER NOT NULL,
+  PRIMARY KEY (first_id, second_id)
+);
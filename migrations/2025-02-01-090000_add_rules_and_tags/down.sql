@@ -0,0 +1,3 @@
+DROP TABLE rule;
+DROP TABLE transaction_tag;
+DROP TABLE tag;
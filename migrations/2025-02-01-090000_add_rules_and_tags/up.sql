@@ -0,0 +1,26 @@
+CREATE TABLE tag (
+  id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+  tag_name TEXT NOT NULL UNIQUE
+);
+
+CREATE TABLE transaction_tag (
+  transaction_id INTEGER NOT NULL REFERENCES money_transaction(id),
+  tag_id INTEGER NOT NULL REFERENCES tag(id),
+  PRIMARY KEY (transaction_id, tag_id)
+);
+
+-- Rules are checked in id order and the first matching one applies. Empty
+-- match columns match everything, empty set columns leave the field as is.
+CREATE TABLE rule (
+  id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+  rule_name TEXT NOT NULL,
+  match_text TEXT,
+  match_regex BOOLEAN NOT NULL DEFAULT 0,
+  match_min_amount REAL,
+  match_max_amount REAL,
+  match_account INTEGER REFERENCES account(id),
+  set_category INTEGER REFERENCES category(id),
+  set_payee TEXT,
+  set_tag INTEGER REFERENCES tag(id),
+  set_note TEXT
+);
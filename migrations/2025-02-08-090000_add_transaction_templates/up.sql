@@ -0,0 +1,10 @@
+CREATE TABLE transaction_template (
+  id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+  template_name TEXT NOT NULL UNIQUE,
+  bank_account INTEGER NOT NULL REFERENCES account(id),
+  transaction_category INTEGER NOT NULL REFERENCES category(id),
+  description TEXT NOT NULL,
+  amount REAL NOT NULL,
+  is_expense BOOLEAN NOT NULL,
+  payee TEXT
+);
@@ -0,0 +1,6 @@
+CREATE TABLE csv_profile (
+  id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+  profile_name TEXT NOT NULL UNIQUE,
+  -- CsvProfile serialized as JSON
+  definition TEXT NOT NULL
+);
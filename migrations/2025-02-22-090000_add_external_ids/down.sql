@@ -0,0 +1,3 @@
+DROP TABLE statement_account;
+DROP INDEX money_transaction_external_id;
+ALTER TABLE money_transaction DROP COLUMN external_id;
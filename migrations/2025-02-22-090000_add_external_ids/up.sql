@@ -0,0 +1,11 @@
+-- Id a bank gives a transaction in its statements, like the OFX FITID,
+-- so that importing a statement again skips what is already there.
+ALTER TABLE money_transaction ADD COLUMN external_id TEXT;
+CREATE INDEX money_transaction_external_id ON money_transaction (bank_account, external_id);
+
+-- Account ids found in statement files, bound to the app account they were
+-- imported into.
+CREATE TABLE statement_account (
+  external_account TEXT NOT NULL PRIMARY KEY,
+  account_id INTEGER NOT NULL REFERENCES account (id) ON DELETE CASCADE
+);
@@ -0,0 +1 @@
+ALTER TABLE account_transfer DROP COLUMN to_status;
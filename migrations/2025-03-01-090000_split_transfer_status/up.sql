@@ -0,0 +1,5 @@
+-- A transfer is cleared and reconciled separately in each of its accounts:
+-- status is kept for the account the money leaves, to_status for the one it
+-- reaches.
+ALTER TABLE account_transfer ADD COLUMN to_status INTEGER NOT NULL DEFAULT 0;
+UPDATE account_transfer SET to_status = status;
@@ -5,9 +5,13 @@ use std::collections::HashMap;
 
 use crate::config::{Config, CONFIG_VERSION};
 use crate::core::nav::NavPage;
-use crate::synchronization::export::export_to_folder;
-use crate::synchronization::import::import_from_json;
-use crate::{fl, pages};
+use crate::synchronization::export::{
+    export_csv_to_folder, export_qif_to_folder, export_to_folder, CsvExportOptions,
+};
+use crate::synchronization::import::{import_data, read_import_file, read_qif_file, ImportMode};
+use crate::synchronization::model::{ImportPreview, SyncModel};
+use crate::synchronization::prices::import_prices_from_csv;
+use crate::{fl, pages, STORE};
 use cosmic::app::{self, Core, Task};
 use cosmic::cosmic_config::Update;
 use cosmic::cosmic_theme::ThemeMode;
@@ -39,9 +43,23 @@ pub struct MoneyManager {
     pub categories: pages::categories::Categories,
     pub settings: pages::settings::Settings,
     pub transactions: pages::transactions::Transactions,
+    pub search: pages::search::Search,
+    pub saved_filter: pages::saved_filter::SavedFilterView,
+    pub duplicates: pages::duplicates::Duplicates,
+    pub rules: pages::rules::Rules,
+    pub csv_import: pages::csv_import::CsvImport,
+    pub statement_import: pages::statement_import::StatementImport,
     pub statistics: pages::statistics::Statistics,
     pub welcome: pages::welcome::Welcome,
     pub toasts: widget::toaster::Toasts<AppMessage>,
+    /// File read for import, waiting for the user to confirm its preview.
+    pending_import: Option<PendingImport>,
+}
+
+struct PendingImport {
+    data: SyncModel,
+    mode: ImportMode,
+    preview: ImportPreview,
 }
 
 #[derive(Debug, Clone)]
@@ -53,19 +71,42 @@ pub enum AppMessage {
     Accounts(pages::accounts::AccountsMessage),
     Categories(pages::categories::CategoriesMessage),
     Transactions(pages::transactions::TransactionMessage),
+    Search(pages::search::SearchMessage),
+    SavedFilter(pages::saved_filter::SavedFilterMessage),
+    Duplicates(pages::duplicates::DuplicatesMessage),
+    Rules(pages::rules::RulesMessage),
+    CsvImport(pages::csv_import::CsvImportMessage),
+    StatementImport(pages::statement_import::StatementImportMessage),
     Settings(pages::settings::SettingsMessage),
     Statistics(pages::statistics::StatisticsMessage),
     Welcome(pages::welcome::WelcomeMessage),
 
     GoToAccounts,
+    OpenSearch,
+    SavedFiltersChanged,
+    CheckDuplicates,
     ShowToast(String),
     CloseToast(ToastId),
     UpdateAllPages,
 
-    Import,
+    Import(ImportMode),
     Export,
-    ImportFromJsonFile(Url),
+    ImportFromJsonFile(Url, ImportMode),
+    ConfirmImport,
+    CancelImport,
     ExportToFolder(Url),
+    ImportPrices,
+    ImportPricesFromFile(Url),
+    ImportCsv,
+    ImportCsvFile(Url),
+    ImportStatement,
+    ImportStatementFile(Url),
+    ImportQif,
+    ImportQifFile(Url),
+    ExportQif,
+    ExportQifToFolder(Url),
+    ExportCsv(CsvExportOptions),
+    ExportCsvToFolder(Url, CsvExportOptions),
 }
 
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
@@ -130,6 +171,7 @@ impl Application for MoneyManager {
                 nav.activate(id);
             }
         }
+        insert_saved_filters(&mut nav);
 
         let config = Config::load();
         if !config.1.is_user_initialized {
@@ -164,9 +206,16 @@ impl Application for MoneyManager {
             categories: pages::categories::Categories::default(),
             settings: pages::settings::Settings::default(),
             transactions: pages::transactions::Transactions::default(),
+            search: pages::search::Search::default(),
+            saved_filter: pages::saved_filter::SavedFilterView::default(),
+            duplicates: pages::duplicates::Duplicates::default(),
+            rules: pages::rules::Rules::default(),
+            csv_import: pages::csv_import::CsvImport::default(),
+            statement_import: pages::statement_import::StatementImport::default(),
             statistics: pages::statistics::Statistics::default(),
             welcome: pages::welcome::Welcome::default(),
             toasts: widget::toaster::Toasts::new(AppMessage::CloseToast),
+            pending_import: None,
         };
 
         let command = app.update_title();
@@ -186,6 +235,24 @@ impl Application for MoneyManager {
         vec![menu_bar.into()]
     }
 
+    fn header_end(&self) -> Vec<Element<Self::Message>> {
+        let config = Config::load();
+        if !config.1.is_user_initialized {
+            return vec![];
+        }
+
+        vec![
+            widget::search_input(fl!("search-placeholder"), self.search.query())
+                .on_input(|query| {
+                    AppMessage::Search(pages::search::SearchMessage::QueryChanged(query))
+                })
+                .on_clear(AppMessage::Search(pages::search::SearchMessage::ClearQuery))
+                .on_submit(AppMessage::OpenSearch)
+                .width(Length::Fixed(240.))
+                .into(),
+        ]
+    }
+
     fn view(&self) -> Element<Self::Message> {
         let spacing = cosmic::theme::active().cosmic().spacing;
         let entity = self.nav.active();
@@ -243,6 +310,48 @@ impl Application for MoneyManager {
                     .update(message)
                     .map(cosmic::app::Message::App),
             ),
+            AppMessage::Search(message) => {
+                commands.push(self.search.update(message).map(cosmic::app::Message::App))
+            }
+            AppMessage::Duplicates(message) => commands.push(
+                self.duplicates
+                    .update(message)
+                    .map(cosmic::app::Message::App),
+            ),
+            AppMessage::Rules(message) => {
+                commands.push(self.rules.update(message).map(cosmic::app::Message::App))
+            }
+            AppMessage::CsvImport(message) => commands.push(
+                self.csv_import
+                    .update(message)
+                    .map(cosmic::app::Message::App),
+            ),
+            AppMessage::StatementImport(message) => commands.push(
+                self.statement_import
+                    .update(message)
+                    .map(cosmic::app::Message::App),
+            ),
+            AppMessage::CheckDuplicates => {
+                commands.push(
+                    self.duplicates
+                        .update(pages::duplicates::DuplicatesMessage::Update)
+                        .map(cosmic::app::Message::App),
+                );
+                let count = self.duplicates.count();
+                if count > 0 {
+                    commands.push(Task::perform(async {}, move |_| {
+                        cosmic::app::Message::App(AppMessage::ShowToast(fl!(
+                            "duplicates-found",
+                            count = count
+                        )))
+                    }));
+                }
+            }
+            AppMessage::SavedFilter(message) => commands.push(
+                self.saved_filter
+                    .update(message)
+                    .map(cosmic::app::Message::App),
+            ),
             AppMessage::Statistics(message) => commands.push(
                 self.statistics
                     .update(message)
@@ -259,6 +368,23 @@ impl Application for MoneyManager {
                 self.nav.activate_position(0);
                 self.core.nav_bar_set_toggled(true);
             }
+            AppMessage::OpenSearch => {
+                let search_entity = self
+                    .nav
+                    .iter()
+                    .find(|&entity| self.nav.data::<NavPage>(entity) == Some(&NavPage::Search));
+                if let Some(entity) = search_entity {
+                    self.nav.activate(entity);
+                    commands.push(self.update_title());
+                }
+            }
+            AppMessage::SavedFiltersChanged => {
+                insert_saved_filters(&mut self.nav);
+                if self.nav.data::<NavPage>(self.nav.active()).is_none() {
+                    self.nav.activate_position(0);
+                    commands.push(self.update_title());
+                }
+            }
             AppMessage::ShowToast(message) => {
                 commands.push(
                     self.toasts
@@ -290,13 +416,23 @@ impl Application for MoneyManager {
                         .update(pages::transactions::TransactionMessage::UpdatePage)
                         .map(cosmic::app::Message::App),
                 );
+                commands.push(
+                    self.search
+                        .update(pages::search::SearchMessage::Update)
+                        .map(cosmic::app::Message::App),
+                );
+                commands.push(
+                    self.saved_filter
+                        .update(pages::saved_filter::SavedFilterMessage::Update)
+                        .map(cosmic::app::Message::App),
+                );
                 commands.push(
                     self.statistics
                         .update(pages::statistics::StatisticsMessage::Update)
                         .map(cosmic::app::Message::App),
                 );
             }
-            AppMessage::Import => {
+            AppMessage::Import(mode) => {
                 commands.push(cosmic::command::future(
                     async move {
                         let filter = FileFilter::new("json files").glob("*.json");
@@ -305,7 +441,7 @@ impl Application for MoneyManager {
                             .filter(filter);
                         match dialog.open_file().await {
                             Ok(selected_file) => {
-                                AppMessage::ImportFromJsonFile(selected_file.url().clone())
+                                AppMessage::ImportFromJsonFile(selected_file.url().clone(), mode)
                             }
                             Err(file_chooser::Error::Cancelled) => {
                                 AppMessage::ShowToast(fl!("operation-cancelled"))
@@ -316,26 +452,70 @@ impl Application for MoneyManager {
                     .map(cosmic::app::Message::App),
                 ));
             }
-            AppMessage::ImportFromJsonFile(url) => {
-                match import_from_json(&url) {
-                    Ok(_) => {
-                        commands.push(Task::perform(async {}, |_| {
-                            cosmic::app::Message::App(AppMessage::ShowToast(fl!("import-success")))
-                        }));
-                    }
-                    Err(_) => {
-                        commands.push(Task::perform(async {}, |_| {
-                            cosmic::app::Message::App(AppMessage::ShowToast(fl!("import-error")))
-                        }));
+            AppMessage::ImportFromJsonFile(url, mode) => match read_import_file(&url) {
+                Ok(data) => {
+                    let preview = data.preview();
+                    self.pending_import = Some(PendingImport {
+                        data,
+                        mode,
+                        preview,
+                    });
+                }
+                Err(e) => {
+                    commands.push(Task::perform(async {}, move |_| {
+                        cosmic::app::Message::App(AppMessage::ShowToast(fl!(
+                            "import-error-detail",
+                            error = e
+                        )))
+                    }));
+                }
+            },
+            AppMessage::ConfirmImport => {
+                if let Some(pending) = self.pending_import.take() {
+                    match import_data(&pending.data, pending.mode) {
+                        Ok(report) => {
+                            let message = match pending.mode {
+                                ImportMode::Replace => fl!("import-success"),
+                                ImportMode::Merge => {
+                                    let total = report.total();
+                                    fl!(
+                                        "merge-import-success",
+                                        added = total.added,
+                                        updated = total.updated,
+                                        skipped = total.skipped
+                                    )
+                                }
+                            };
+                            commands.push(Task::perform(async {}, move |_| {
+                                cosmic::app::Message::App(AppMessage::ShowToast(message))
+                            }));
+                            commands.push(Task::perform(async {}, |_| {
+                                cosmic::app::Message::App(AppMessage::CheckDuplicates)
+                            }));
+                            commands.push(
+                                self.welcome
+                                    .update(pages::welcome::WelcomeMessage::ImportCompleted)
+                                    .map(cosmic::app::Message::App),
+                            );
+                        }
+                        Err(e) => {
+                            commands.push(Task::perform(async {}, move |_| {
+                                cosmic::app::Message::App(AppMessage::ShowToast(fl!(
+                                    "import-error-detail",
+                                    error = e
+                                )))
+                            }));
+                        }
                     }
+                    commands.push(Task::perform(async {}, |_| {
+                        cosmic::app::Message::App(AppMessage::UpdateAllPages)
+                    }));
                 }
-                commands.push(
-                    self.welcome
-                        .update(pages::welcome::WelcomeMessage::ImportCompleted)
-                        .map(cosmic::app::Message::App),
-                );
+            }
+            AppMessage::CancelImport => {
+                self.pending_import = None;
                 commands.push(Task::perform(async {}, |_| {
-                    cosmic::app::Message::App(AppMessage::UpdateAllPages)
+                    cosmic::app::Message::App(AppMessage::ShowToast(fl!("operation-cancelled")))
                 }));
             }
             AppMessage::Export => {
@@ -370,6 +550,207 @@ impl Application for MoneyManager {
                     }
                 }
             }
+            AppMessage::ExportCsv(options) => {
+                commands.push(cosmic::command::future(async move {
+                    let dialog =
+                        file_chooser::open::Dialog::new().title("Choose a destination folder");
+                    match dialog.open_folder().await {
+                        Ok(selected_folder) => {
+                            AppMessage::ExportCsvToFolder(selected_folder.url().clone(), options)
+                        }
+                        Err(file_chooser::Error::Cancelled) => {
+                            AppMessage::ShowToast(fl!("operation-cancelled"))
+                        }
+                        Err(_why) => AppMessage::ShowToast(fl!("operation-cancelled")),
+                    }
+                }));
+            }
+            AppMessage::ExportCsvToFolder(url, options) => {
+                let toast = match export_csv_to_folder(url, &options) {
+                    Ok((transactions, transfers)) => fl!(
+                        "csv-export-completed",
+                        transactions = transactions,
+                        transfers = transfers
+                    ),
+                    Err(e) => {
+                        log::error!("csv export failed: {}", e);
+                        fl!("export-error")
+                    }
+                };
+                commands.push(Task::perform(async {}, |_| {
+                    cosmic::app::Message::App(AppMessage::ShowToast(toast))
+                }));
+            }
+            AppMessage::ImportPrices => {
+                commands.push(cosmic::command::future(async move {
+                    let filter = FileFilter::new("csv files").glob("*.csv");
+                    let dialog = file_chooser::open::Dialog::new()
+                        .title("Choose a price history file")
+                        .filter(filter);
+                    match dialog.open_file().await {
+                        Ok(selected_file) => {
+                            AppMessage::ImportPricesFromFile(selected_file.url().clone())
+                        }
+                        Err(file_chooser::Error::Cancelled) => {
+                            AppMessage::ShowToast(fl!("operation-cancelled"))
+                        }
+                        Err(_why) => AppMessage::ShowToast(fl!("operation-cancelled")),
+                    }
+                }));
+            }
+            AppMessage::ImportPricesFromFile(url) => {
+                let toast = match import_prices_from_csv(&url) {
+                    Ok(count) => fl!("prices-import-success", count = count),
+                    Err(e) => {
+                        log::error!("price import failed: {}", e);
+                        fl!("prices-import-error")
+                    }
+                };
+                commands.push(Task::perform(async {}, |_| {
+                    cosmic::app::Message::App(AppMessage::ShowToast(toast))
+                }));
+                commands.push(Task::perform(async {}, |_| {
+                    cosmic::app::Message::App(AppMessage::UpdateAllPages)
+                }));
+            }
+            AppMessage::ImportCsv => {
+                commands.push(cosmic::command::future(async move {
+                    let filter = FileFilter::new("csv files").glob("*.csv");
+                    let dialog = file_chooser::open::Dialog::new()
+                        .title("Choose a bank statement")
+                        .filter(filter);
+                    match dialog.open_file().await {
+                        Ok(selected_file) => AppMessage::ImportCsvFile(selected_file.url().clone()),
+                        Err(file_chooser::Error::Cancelled) => {
+                            AppMessage::ShowToast(fl!("operation-cancelled"))
+                        }
+                        Err(_why) => AppMessage::ShowToast(fl!("operation-cancelled")),
+                    }
+                }));
+            }
+            AppMessage::ImportCsvFile(url) => {
+                let csv_entity = self
+                    .nav
+                    .iter()
+                    .find(|&entity| self.nav.data::<NavPage>(entity) == Some(&NavPage::CsvImport));
+                if let Some(entity) = csv_entity {
+                    self.nav.activate(entity);
+                    commands.push(self.update_title());
+                }
+                commands.push(
+                    self.csv_import
+                        .update(pages::csv_import::CsvImportMessage::Update)
+                        .map(cosmic::app::Message::App),
+                );
+                commands.push(
+                    self.csv_import
+                        .update(pages::csv_import::CsvImportMessage::Load(url))
+                        .map(cosmic::app::Message::App),
+                );
+            }
+            AppMessage::ImportStatement => {
+                commands.push(cosmic::command::future(async move {
+                    let filter = FileFilter::new("bank statements")
+                        .glob("*.ofx")
+                        .glob("*.qfx")
+                        .glob("*.xml")
+                        .glob("*.sta")
+                        .glob("*.mt940");
+                    let dialog = file_chooser::open::Dialog::new()
+                        .title("Choose a bank statement")
+                        .filter(filter);
+                    match dialog.open_file().await {
+                        Ok(selected_file) => {
+                            AppMessage::ImportStatementFile(selected_file.url().clone())
+                        }
+                        Err(file_chooser::Error::Cancelled) => {
+                            AppMessage::ShowToast(fl!("operation-cancelled"))
+                        }
+                        Err(_why) => AppMessage::ShowToast(fl!("operation-cancelled")),
+                    }
+                }));
+            }
+            AppMessage::ImportStatementFile(url) => {
+                let statement_entity = self.nav.iter().find(|&entity| {
+                    self.nav.data::<NavPage>(entity) == Some(&NavPage::StatementImport)
+                });
+                if let Some(entity) = statement_entity {
+                    self.nav.activate(entity);
+                    commands.push(self.update_title());
+                }
+                commands.push(
+                    self.statement_import
+                        .update(pages::statement_import::StatementImportMessage::Update)
+                        .map(cosmic::app::Message::App),
+                );
+                commands.push(
+                    self.statement_import
+                        .update(pages::statement_import::StatementImportMessage::Load(url))
+                        .map(cosmic::app::Message::App),
+                );
+            }
+            AppMessage::ImportQif => {
+                commands.push(cosmic::command::future(async move {
+                    let filter = FileFilter::new("qif files").glob("*.qif");
+                    let dialog = file_chooser::open::Dialog::new()
+                        .title("Choose a QIF file")
+                        .filter(filter);
+                    match dialog.open_file().await {
+                        Ok(selected_file) => AppMessage::ImportQifFile(selected_file.url().clone()),
+                        Err(file_chooser::Error::Cancelled) => {
+                            AppMessage::ShowToast(fl!("operation-cancelled"))
+                        }
+                        Err(_why) => AppMessage::ShowToast(fl!("operation-cancelled")),
+                    }
+                }));
+            }
+            // Accounts and categories are matched by name, so a QIF file is
+            // always merged into the ledger.
+            AppMessage::ImportQifFile(url) => match read_qif_file(&url) {
+                Ok(data) => {
+                    let preview = data.preview();
+                    self.pending_import = Some(PendingImport {
+                        data,
+                        mode: ImportMode::Merge,
+                        preview,
+                    });
+                }
+                Err(e) => {
+                    commands.push(Task::perform(async {}, move |_| {
+                        cosmic::app::Message::App(AppMessage::ShowToast(fl!(
+                            "import-error-detail",
+                            error = e
+                        )))
+                    }));
+                }
+            },
+            AppMessage::ExportQif => {
+                commands.push(cosmic::command::future(async move {
+                    let dialog =
+                        file_chooser::open::Dialog::new().title("Choose a destination folder");
+                    match dialog.open_folder().await {
+                        Ok(selected_folder) => {
+                            AppMessage::ExportQifToFolder(selected_folder.url().clone())
+                        }
+                        Err(file_chooser::Error::Cancelled) => {
+                            AppMessage::ShowToast(fl!("operation-cancelled"))
+                        }
+                        Err(_why) => AppMessage::ShowToast(fl!("operation-cancelled")),
+                    }
+                }));
+            }
+            AppMessage::ExportQifToFolder(url) => {
+                let toast = match export_qif_to_folder(url) {
+                    Ok(accounts) => fl!("qif-export-completed", accounts = accounts),
+                    Err(e) => {
+                        log::error!("qif export failed: {}", e);
+                        fl!("export-error")
+                    }
+                };
+                commands.push(Task::perform(async {}, |_| {
+                    cosmic::app::Message::App(AppMessage::ShowToast(toast))
+                }));
+            }
             AppMessage::SystemThemeModeChange => {
                 commands.push(app::command::set_theme(cosmic::theme::active()));
             }
@@ -439,11 +820,102 @@ impl Application for MoneyManager {
         })
     }
 
+    fn dialog(&self) -> Option<Element<AppMessage>> {
+        let pending = self.pending_import.as_ref()?;
+        let preview = &pending.preview;
+
+        let mut body = widget::column().spacing(5).push(widget::text::text(fl!(
+            "import-preview-counts",
+            accounts = preview.accounts,
+            categories = preview.categories,
+            transactions = preview.transactions,
+            transfers = preview.transfers
+        )));
+        if let Some((first, last)) = preview.date_range {
+            body = body.push(widget::text::text(fl!(
+                "import-preview-dates",
+                first = first.format("%d-%m-%Y").to_string(),
+                last = last.format("%d-%m-%Y").to_string()
+            )));
+        }
+        if preview.problems.is_empty() {
+            body = body.push(widget::text::text(match pending.mode {
+                ImportMode::Replace => fl!("import-preview-replace"),
+                ImportMode::Merge => fl!("import-preview-merge"),
+            }));
+        } else {
+            body = body.push(widget::text::title4(fl!(
+                "import-preview-problems",
+                count = preview.problems.len()
+            )));
+            body = body.push(
+                widget::scrollable(
+                    preview
+                        .problems
+                        .iter()
+                        .fold(widget::column().spacing(2), |column, problem| {
+                            column.push(widget::text::text(problem))
+                        }),
+                )
+                .height(Length::Fixed(150.)),
+            );
+        }
+
+        let import_button = widget::button::text(fl!("import"))
+            .class(widget::button::ButtonClass::Suggested)
+            .on_press_maybe(
+                preview
+                    .problems
+                    .is_empty()
+                    .then_some(AppMessage::ConfirmImport),
+            );
+
+        Some(
+            widget::dialog()
+                .title(fl!("import-preview"))
+                .control(body)
+                .primary_action(import_button)
+                .secondary_action(
+                    widget::button::text(fl!("cancel")).on_press(AppMessage::CancelImport),
+                )
+                .into(),
+        )
+    }
+
     fn on_nav_select(&mut self, id: nav_bar::Id) -> Task<Self::Message> {
         // Activate the page in the model.
         self.nav.activate(id);
 
-        self.update_title()
+        let mut commands = vec![self.update_title()];
+        match self.nav.data::<NavPage>(id).copied() {
+            Some(NavPage::SavedFilter(filter_id)) => commands.push(
+                self.saved_filter
+                    .update(pages::saved_filter::SavedFilterMessage::Open(filter_id))
+                    .map(cosmic::app::Message::App),
+            ),
+            Some(NavPage::Duplicates) => commands.push(
+                self.duplicates
+                    .update(pages::duplicates::DuplicatesMessage::Update)
+                    .map(cosmic::app::Message::App),
+            ),
+            Some(NavPage::Rules) => commands.push(
+                self.rules
+                    .update(pages::rules::RulesMessage::Update)
+                    .map(cosmic::app::Message::App),
+            ),
+            Some(NavPage::CsvImport) => commands.push(
+                self.csv_import
+                    .update(pages::csv_import::CsvImportMessage::Update)
+                    .map(cosmic::app::Message::App),
+            ),
+            Some(NavPage::StatementImport) => commands.push(
+                self.statement_import
+                    .update(pages::statement_import::StatementImportMessage::Update)
+                    .map(cosmic::app::Message::App),
+            ),
+            _ => {}
+        }
+        Task::batch(commands)
     }
 }
 
@@ -463,3 +935,28 @@ impl MoneyManager {
         }
     }
 }
+
+/// Replaces the saved filter entries at the bottom of the nav bar with the
+/// ones currently stored.
+fn insert_saved_filters(nav: &mut nav_bar::Model) {
+    let pinned: Vec<nav_bar::Id> = nav
+        .iter()
+        .filter(|&id| matches!(nav.data::<NavPage>(id), Some(NavPage::SavedFilter(_))))
+        .collect();
+    for id in pinned {
+        nav.remove(id);
+    }
+
+    let saved_filters = STORE
+        .lock()
+        .unwrap()
+        .get_saved_filters()
+        .unwrap_or_else(|_| vec![]);
+    for saved_filter in saved_filters {
+        let nav_page = NavPage::SavedFilter(saved_filter.id);
+        nav.insert()
+            .icon(nav_page.icon())
+            .text(saved_filter.filter_name)
+            .data::<NavPage>(nav_page);
+    }
+}
@@ -13,6 +13,10 @@ pub const CONFIG_VERSION: u64 = 1;
 pub struct Config {
     pub is_user_initialized: bool,
     pub currency_id: i32,
+    /// Number of days ahead checked for projected negative balances.
+    pub projection_horizon_days: u32,
+    /// Maximum number of days between two transactions flagged as duplicates.
+    pub duplicate_window_days: u32,
 }
 
 impl Default for Config {
@@ -20,6 +24,8 @@ impl Default for Config {
         Self {
             is_user_initialized: false,
             currency_id: 1,
+            projection_horizon_days: 30,
+            duplicate_window_days: 3,
         }
     }
 }
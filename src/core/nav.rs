@@ -8,9 +8,16 @@ pub enum NavPage {
     Accounts,
     Categories,
     Transactions,
+    Search,
+    Duplicates,
+    Rules,
+    CsvImport,
+    StatementImport,
     Settings,
     Statistics,
     Welcome,
+    /// A saved transaction filter, by id.
+    SavedFilter(i32),
 }
 
 impl Default for &NavPage {
@@ -25,9 +32,15 @@ impl NavPage {
             Self::Accounts => fl!("page_accounts"),
             Self::Categories => fl!("page_categories"),
             Self::Transactions => fl!("page_transactions"),
+            Self::Search => fl!("page-search"),
+            Self::Duplicates => fl!("page-duplicates"),
+            Self::Rules => fl!("page-rules"),
+            Self::CsvImport => fl!("page-csv-import"),
+            Self::StatementImport => fl!("page-statement-import"),
             Self::Settings => fl!("page_settings"),
             Self::Statistics => fl!("statistics"),
             Self::Welcome => fl!("page-welcome"),
+            Self::SavedFilter(_) => fl!("saved-filter"),
         }
     }
 
@@ -36,9 +49,15 @@ impl NavPage {
             NavPage::Accounts => icon::from_name("contact-new-symbolic").into(),
             NavPage::Categories => icon::from_name("sidebar-places-symbolic").into(),
             NavPage::Transactions => icon::from_name("network-transmit-receive-symbolic").into(),
+            NavPage::Search => icon::from_name("system-search-symbolic").into(),
+            NavPage::Duplicates => icon::from_name("edit-copy-symbolic").into(),
+            NavPage::Rules => icon::from_name("view-list-symbolic").into(),
+            NavPage::CsvImport => icon::from_name("document-open-symbolic").into(),
+            NavPage::StatementImport => icon::from_name("x-office-document-symbolic").into(),
             NavPage::Settings => icon::from_name("application-default-symbolic").into(),
             NavPage::Welcome => icon::from_name("application-default-symbolic").into(), //TODO here the icon is useless
             NavPage::Statistics => icon::from_name("preferences-displays-symbolic").into(),
+            NavPage::SavedFilter(_) => icon::from_name("folder-saved-search-symbolic").into(),
         }
     }
 
@@ -47,9 +66,17 @@ impl NavPage {
             NavPage::Accounts => app.accounts.view().map(AppMessage::Accounts),
             NavPage::Categories => app.categories.view().map(AppMessage::Categories),
             NavPage::Transactions => app.transactions.view().map(AppMessage::Transactions),
+            NavPage::Search => app.search.view().map(AppMessage::Search),
+            NavPage::Duplicates => app.duplicates.view().map(AppMessage::Duplicates),
+            NavPage::Rules => app.rules.view().map(AppMessage::Rules),
+            NavPage::CsvImport => app.csv_import.view().map(AppMessage::CsvImport),
+            NavPage::StatementImport => {
+                app.statement_import.view().map(AppMessage::StatementImport)
+            }
             NavPage::Settings => app.settings.view().map(AppMessage::Settings),
             NavPage::Welcome => app.welcome.view().map(AppMessage::Welcome),
             NavPage::Statistics => app.statistics.view().map(AppMessage::Statistics),
+            NavPage::SavedFilter(_) => app.saved_filter.view().map(AppMessage::SavedFilter),
         }
     }
 
@@ -58,6 +85,11 @@ impl NavPage {
             Self::Accounts,
             Self::Categories,
             Self::Transactions,
+            Self::Search,
+            Self::Duplicates,
+            Self::Rules,
+            Self::CsvImport,
+            Self::StatementImport,
             Self::Statistics,
             Self::Settings,
         ]
@@ -16,4 +16,10 @@ pub enum DataStoreError {
 
     #[error("Error happened on database delete: {0}")]
     DeleteError(String),
+
+    #[error("Records changed since they were read")]
+    Outdated,
+
+    #[error("Reconciled transactions can't be changed")]
+    Reconciled,
 }
@@ -1,7 +1,24 @@
+use crate::errors::DataStoreError;
 use crate::schema::account;
+use crate::schema::account_group;
 use crate::schema::account_transfer;
 use crate::schema::category;
+use crate::schema::credit_card;
+use crate::schema::csv_profile;
+use crate::schema::dismissed_duplicate;
+use crate::schema::investment_transaction;
+use crate::schema::loan;
+use crate::schema::loan_payment;
 use crate::schema::money_transaction;
+use crate::schema::rule;
+use crate::schema::saved_filter;
+use crate::schema::security;
+use crate::schema::security_price;
+use crate::schema::statement_account;
+use crate::schema::tag;
+use crate::schema::transaction_tag;
+use crate::schema::transaction_template;
+use crate::synchronization::csv::CsvProfile;
 use diesel::prelude::*;
 use serde::Deserialize;
 use serde::Serialize;
@@ -14,6 +31,14 @@ pub struct Account {
     pub name: String,
     pub initial_balance: f32,
     pub account_description: String,
+    #[serde(default)]
+    pub account_type: i32,
+    #[serde(default)]
+    pub group_id: Option<i32>,
+    #[serde(default)]
+    pub exclude_from_total: bool,
+    #[serde(default)]
+    pub exclude_from_statistics: bool,
 }
 
 impl AsRef<str> for Account {
@@ -22,12 +47,85 @@ impl AsRef<str> for Account {
     }
 }
 
+impl Account {
+    pub fn kind(&self) -> AccountType {
+        AccountType::from(self.account_type)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum AccountType {
+    #[default]
+    Checking,
+    Loan,
+    Investment,
+    Credit,
+    Cash,
+    Savings,
+    Asset,
+}
+
+impl AccountType {
+    pub fn all() -> &'static [Self] {
+        &[
+            Self::Cash,
+            Self::Checking,
+            Self::Savings,
+            Self::Credit,
+            Self::Loan,
+            Self::Investment,
+            Self::Asset,
+        ]
+    }
+
+    /// Types that only differ by name and can be changed after creation,
+    /// unlike loans, investments and credit cards that carry extra data.
+    pub fn is_plain(&self) -> bool {
+        matches!(
+            self,
+            Self::Cash | Self::Checking | Self::Savings | Self::Asset
+        )
+    }
+}
+
+impl From<i32> for AccountType {
+    fn from(value: i32) -> Self {
+        match value {
+            1 => Self::Loan,
+            2 => Self::Investment,
+            3 => Self::Credit,
+            4 => Self::Cash,
+            5 => Self::Savings,
+            6 => Self::Asset,
+            _ => Self::Checking,
+        }
+    }
+}
+
+impl From<AccountType> for i32 {
+    fn from(value: AccountType) -> Self {
+        match value {
+            AccountType::Checking => 0,
+            AccountType::Loan => 1,
+            AccountType::Investment => 2,
+            AccountType::Credit => 3,
+            AccountType::Cash => 4,
+            AccountType::Savings => 5,
+            AccountType::Asset => 6,
+        }
+    }
+}
+
 #[derive(Insertable, Debug, Clone)]
 #[diesel(table_name = account)]
 pub struct NewAccount {
     pub name: String,
     pub initial_balance: f32,
     pub account_description: String,
+    pub account_type: i32,
+    pub group_id: Option<i32>,
+    pub exclude_from_total: bool,
+    pub exclude_from_statistics: bool,
 }
 
 impl From<&Account> for NewAccount {
@@ -36,6 +134,10 @@ impl From<&Account> for NewAccount {
             name: value.name.clone(),
             initial_balance: value.initial_balance,
             account_description: value.account_description.clone(),
+            account_type: value.account_type,
+            group_id: value.group_id,
+            exclude_from_total: value.exclude_from_total,
+            exclude_from_statistics: value.exclude_from_statistics,
         }
     }
 }
@@ -47,6 +149,30 @@ pub struct UpdateAccount {
     pub name: String,
     pub initial_balance: f32,
     pub account_description: String,
+    pub account_type: i32,
+    pub group_id: Option<i32>,
+    pub exclude_from_total: bool,
+    pub exclude_from_statistics: bool,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[diesel(table_name = account_group)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct AccountGroup {
+    pub id: i32,
+    pub group_name: String,
+}
+
+impl AsRef<str> for AccountGroup {
+    fn as_ref(&self) -> &str {
+        &self.group_name
+    }
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = account_group)]
+pub struct NewAccountGroup {
+    pub group_name: String,
 }
 
 #[derive(Queryable, Selectable, Debug, Clone, Serialize, Deserialize)]
@@ -92,7 +218,7 @@ pub struct UpdateCategory<'a> {
     pub category_description: String,
 }
 
-#[derive(Queryable, Selectable, Debug, Serialize, Deserialize)]
+#[derive(Queryable, Selectable, Insertable, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[diesel(table_name = crate::schema::money_transaction)]
 #[diesel(check_for_backend(diesel::sqlite::Sqlite))]
 pub struct MoneyTransaction {
@@ -103,9 +229,22 @@ pub struct MoneyTransaction {
     pub amount: f32,
     pub transaction_date: chrono::NaiveDateTime,
     pub is_expense: bool,
+    #[serde(default)]
+    pub status: i32,
+    #[serde(default)]
+    pub payee: Option<String>,
+    /// Id the bank gave the transaction in an imported statement.
+    #[serde(default)]
+    pub external_id: Option<String>,
 }
 
-#[derive(Insertable)]
+impl MoneyTransaction {
+    pub fn status(&self) -> TransactionStatus {
+        TransactionStatus::from(self.status)
+    }
+}
+
+#[derive(Insertable, Clone)]
 #[diesel(table_name = money_transaction)]
 pub struct NewMoneyTransaction {
     pub bank_account: i32,
@@ -114,6 +253,9 @@ pub struct NewMoneyTransaction {
     pub amount: f32,
     pub transaction_date: chrono::NaiveDateTime,
     pub is_expense: bool,
+    pub status: i32,
+    pub payee: Option<String>,
+    pub external_id: Option<String>,
 }
 
 #[derive(Insertable)]
@@ -126,6 +268,7 @@ pub struct UpdateTransaction {
     pub amount: f32,
     pub transaction_date: chrono::NaiveDateTime,
     pub is_expense: bool,
+    pub payee: Option<String>,
 }
 
 impl From<&MoneyTransaction> for NewMoneyTransaction {
@@ -137,10 +280,354 @@ impl From<&MoneyTransaction> for NewMoneyTransaction {
             amount: value.amount,
             transaction_date: value.transaction_date,
             is_expense: value.is_expense,
+            status: value.status,
+            payee: value.payee.clone(),
+            external_id: value.external_id.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TransactionStatus {
+    #[default]
+    Uncleared,
+    Cleared,
+    Reconciled,
+}
+
+impl From<i32> for TransactionStatus {
+    fn from(value: i32) -> Self {
+        match value {
+            1 => Self::Cleared,
+            2 => Self::Reconciled,
+            _ => Self::Uncleared,
+        }
+    }
+}
+
+impl From<TransactionStatus> for i32 {
+    fn from(value: TransactionStatus) -> Self {
+        match value {
+            TransactionStatus::Uncleared => 0,
+            TransactionStatus::Cleared => 1,
+            TransactionStatus::Reconciled => 2,
+        }
+    }
+}
+
+/// Criteria for narrowing down money transactions. Empty lists and `None`
+/// values leave that criterion out, so the default filter matches everything.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TransactionFilter {
+    #[serde(default)]
+    pub accounts: Vec<i32>,
+    #[serde(default)]
+    pub categories: Vec<i32>,
+    #[serde(default)]
+    pub is_expense: Option<bool>,
+    #[serde(default)]
+    pub min_amount: Option<f32>,
+    #[serde(default)]
+    pub max_amount: Option<f32>,
+    #[serde(default)]
+    pub date_range: Option<(chrono::NaiveDate, chrono::NaiveDate)>,
+    /// Relative dates, resolved when the filter is run. A fixed `date_range`
+    /// takes precedence.
+    #[serde(default)]
+    pub period: Option<FilterPeriod>,
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+impl TransactionFilter {
+    pub fn accounts(mut self, accounts: Vec<i32>) -> Self {
+        self.accounts = accounts;
+        self
+    }
+
+    pub fn categories(mut self, categories: Vec<i32>) -> Self {
+        self.categories = categories;
+        self
+    }
+
+    pub fn expenses(mut self, is_expense: Option<bool>) -> Self {
+        self.is_expense = is_expense;
+        self
+    }
+
+    pub fn amount_range(mut self, min: Option<f32>, max: Option<f32>) -> Self {
+        self.min_amount = min;
+        self.max_amount = max;
+        self
+    }
+
+    pub fn date_range(mut self, first: chrono::NaiveDate, last: chrono::NaiveDate) -> Self {
+        self.date_range = Some((first, last));
+        self
+    }
+
+    pub fn period(mut self, period: Option<FilterPeriod>) -> Self {
+        self.period = period;
+        self
+    }
+
+    pub fn text(mut self, text: &str) -> Self {
+        let text = text.trim();
+        self.text = (!text.is_empty()).then(|| text.to_string());
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = dismissed_duplicate)]
+pub struct NewDismissedDuplicate {
+    pub first_id: i32,
+    pub second_id: i32,
+}
+
+/// A user-defined categorization rule. Empty match fields match any
+/// transaction and empty set fields leave the transaction untouched.
+#[derive(Queryable, Selectable, Debug, Clone, Serialize, Deserialize)]
+#[diesel(table_name = rule)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct Rule {
+    pub id: i32,
+    pub rule_name: String,
+    pub match_text: Option<String>, // Matched against the description
+    pub match_regex: bool,          // Whether match_text is a regular expression
+    pub match_min_amount: Option<f32>,
+    pub match_max_amount: Option<f32>,
+    pub match_account: Option<i32>,
+    pub set_category: Option<i32>,
+    pub set_payee: Option<String>,
+    pub set_tag: Option<i32>,
+    pub set_note: Option<String>,
+}
+
+#[derive(Insertable, Debug, Clone, Default)]
+#[diesel(table_name = rule)]
+pub struct NewRule {
+    pub rule_name: String,
+    pub match_text: Option<String>,
+    pub match_regex: bool,
+    pub match_min_amount: Option<f32>,
+    pub match_max_amount: Option<f32>,
+    pub match_account: Option<i32>,
+    pub set_category: Option<i32>,
+    pub set_payee: Option<String>,
+    pub set_tag: Option<i32>,
+    pub set_note: Option<String>,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize, Deserialize)]
+#[diesel(table_name = tag)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct Tag {
+    pub id: i32,
+    pub tag_name: String,
+}
+
+impl AsRef<str> for Tag {
+    fn as_ref(&self) -> &str {
+        &self.tag_name
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = tag)]
+pub struct NewTag {
+    pub tag_name: String,
+}
+
+#[derive(Queryable, Selectable, Insertable, Debug, Clone, Copy, PartialEq)]
+#[diesel(table_name = transaction_tag)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct TransactionTag {
+    pub transaction_id: i32,
+    pub tag_id: i32,
+}
+
+/// Named set of transaction values used to quickly add recurring
+/// transactions.
+#[derive(Queryable, Selectable, Debug, Clone, Serialize, Deserialize)]
+#[diesel(table_name = transaction_template)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct TransactionTemplate {
+    pub id: i32,
+    pub template_name: String,
+    pub bank_account: i32,
+    pub transaction_category: i32,
+    pub description: String,
+    pub amount: f32,
+    pub is_expense: bool,
+    pub payee: Option<String>,
+}
+
+impl TransactionTemplate {
+    /// A new uncleared transaction with the template values on the given date.
+    pub fn new_transaction(&self, transaction_date: chrono::NaiveDateTime) -> NewMoneyTransaction {
+        NewMoneyTransaction {
+            bank_account: self.bank_account,
+            transaction_category: self.transaction_category,
+            description: self.description.clone(),
+            amount: self.amount,
+            transaction_date,
+            is_expense: self.is_expense,
+            status: TransactionStatus::Uncleared.into(),
+            payee: self.payee.clone(),
+            external_id: None,
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = transaction_template)]
+pub struct NewTransactionTemplate {
+    pub template_name: String,
+    pub bank_account: i32,
+    pub transaction_category: i32,
+    pub description: String,
+    pub amount: f32,
+    pub is_expense: bool,
+    pub payee: Option<String>,
+}
+
+impl NewTransactionTemplate {
+    pub fn from_transaction(template_name: String, transaction: &MoneyTransaction) -> Self {
+        Self {
+            template_name,
+            bank_account: transaction.bank_account,
+            transaction_category: transaction.transaction_category,
+            description: transaction.description.clone(),
+            amount: transaction.amount,
+            is_expense: transaction.is_expense,
+            payee: transaction.payee.clone(),
+        }
+    }
+}
+
+/// Change applied at once to a selection of transactions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BulkEdit {
+    Category(i32),
+    Account(i32),
+    AddTag(i32),
+    ShiftDays(i64),
+    Delete,
+}
+
+/// The transactions touched by a bulk edit as they were before it, with
+/// their tags, so that the edit can be undone as a whole.
+#[derive(Clone, Debug, Default)]
+pub struct BulkUndo {
+    pub transactions: Vec<MoneyTransaction>,
+    pub tags: Vec<TransactionTag>,
+    /// The same transactions and tags as the edit left them, empty after a
+    /// delete. The undo only applies while they are unchanged.
+    pub edited: Vec<MoneyTransaction>,
+    pub edited_tags: Vec<TransactionTag>,
+}
+
+/// Position of a transaction in the (date, id) descending order used to list
+/// transactions, from which the next page starts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TransactionCursor {
+    pub transaction_date: chrono::NaiveDateTime,
+    pub id: i32,
+}
+
+impl From<&MoneyTransaction> for TransactionCursor {
+    fn from(value: &MoneyTransaction) -> Self {
+        Self {
+            transaction_date: value.transaction_date,
+            id: value.id,
         }
     }
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum FilterPeriod {
+    ThisMonth,
+    LastMonth,
+    ThisQuarter,
+    ThisYear,
+    Last30Days,
+}
+
+impl FilterPeriod {
+    pub fn all() -> &'static [Self] {
+        &[
+            Self::ThisMonth,
+            Self::LastMonth,
+            Self::ThisQuarter,
+            Self::ThisYear,
+            Self::Last30Days,
+        ]
+    }
+}
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = crate::schema::saved_filter)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct SavedFilter {
+    pub id: i32,
+    pub filter_name: String,
+    pub definition: String,
+}
+
+impl SavedFilter {
+    /// The stored filter definition. A definition that can't be read is an
+    /// error, not a filter matching everything.
+    pub fn filter(&self) -> Result<TransactionFilter, DataStoreError> {
+        serde_json::from_str(&self.definition)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = saved_filter)]
+pub struct NewSavedFilter {
+    pub filter_name: String,
+    pub definition: String,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = crate::schema::csv_profile)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct SavedCsvProfile {
+    pub id: i32,
+    pub profile_name: String,
+    pub definition: String,
+}
+
+impl SavedCsvProfile {
+    /// The stored profile, or one to be detected again if it can't be read.
+    pub fn profile(&self) -> Option<CsvProfile> {
+        serde_json::from_str(&self.definition).ok()
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = csv_profile)]
+pub struct NewCsvProfile {
+    pub profile_name: String,
+    pub definition: String,
+}
+
+/// App account that the transactions of an account found in statement
+/// files go to.
+#[derive(Queryable, Selectable, Insertable, Debug, Clone)]
+#[diesel(table_name = statement_account)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct StatementAccount {
+    pub external_account: String,
+    pub account_id: i32,
+}
+
 #[derive(Queryable, Selectable, Debug)]
 #[diesel(table_name = crate::schema::currency)]
 #[diesel(check_for_backend(diesel::sqlite::Sqlite))]
@@ -156,7 +643,7 @@ impl AsRef<str> for Currency {
     }
 }
 
-#[derive(Queryable, Selectable, Debug, Serialize, Deserialize)]
+#[derive(Queryable, Selectable, Debug, Clone, Serialize, Deserialize)]
 #[diesel(table_name = account_transfer)]
 #[diesel(check_for_backend(diesel::sqlite::Sqlite))]
 pub struct AccountTransfer {
@@ -166,6 +653,30 @@ pub struct AccountTransfer {
     pub transfer_date: chrono::NaiveDateTime,
     pub amount: f32,
     pub description: Option<String>,
+    /// Status in the account the money leaves.
+    #[serde(default)]
+    pub status: i32,
+    /// Status in the account the money reaches.
+    pub to_status: i32,
+}
+
+impl AccountTransfer {
+    /// Status of the transfer in one of its two accounts.
+    pub fn status_for(&self, account_id: i32) -> TransactionStatus {
+        if account_id == self.to_account {
+            TransactionStatus::from(self.to_status)
+        } else {
+            TransactionStatus::from(self.status)
+        }
+    }
+
+    pub fn set_status_for(&mut self, account_id: i32, new_status: TransactionStatus) {
+        if account_id == self.to_account {
+            self.to_status = new_status.into();
+        } else {
+            self.status = new_status.into();
+        }
+    }
 }
 
 #[derive(Insertable)]
@@ -176,4 +687,174 @@ pub struct NewAccountTransfer {
     pub transfer_date: chrono::NaiveDateTime,
     pub amount: f32,
     pub description: Option<String>,
+    pub status: i32,
+    pub to_status: i32,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize, Deserialize)]
+#[diesel(table_name = loan)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct Loan {
+    pub id: i32,
+    pub account_id: i32,
+    pub principal: f32,
+    pub interest_rate: f32,     // Annual rate, in percent
+    pub term: i32,              // Total number of payments
+    pub payment_frequency: i32, // Payments per year
+    pub start_date: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = loan)]
+pub struct NewLoan {
+    pub account_id: i32,
+    pub principal: f32,
+    pub interest_rate: f32,
+    pub term: i32,
+    pub payment_frequency: i32,
+    pub start_date: chrono::NaiveDateTime,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize, Deserialize)]
+#[diesel(table_name = credit_card)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct CreditCard {
+    pub id: i32,
+    pub account_id: i32,
+    pub closing_day: i32, // Day of the month the statement closes
+    pub due_day: i32,     // Day of the month the payment is due
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = credit_card)]
+pub struct NewCreditCard {
+    pub account_id: i32,
+    pub closing_day: i32,
+    pub due_day: i32,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize, Deserialize)]
+#[diesel(table_name = loan_payment)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct LoanPayment {
+    pub id: i32,
+    pub loan_id: i32,
+    pub transfer_id: i32,
+    pub principal_amount: f32,
+    pub interest_amount: f32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = loan_payment)]
+pub struct NewLoanPayment {
+    pub loan_id: i32,
+    pub transfer_id: i32,
+    pub principal_amount: f32,
+    pub interest_amount: f32,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize, Deserialize)]
+#[diesel(table_name = security)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct Security {
+    pub id: i32,
+    pub symbol: String,
+    pub security_name: String,
+}
+
+impl AsRef<str> for Security {
+    fn as_ref(&self) -> &str {
+        &self.symbol
+    }
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = security)]
+pub struct NewSecurity {
+    pub symbol: String,
+    pub security_name: String,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize, Deserialize)]
+#[diesel(table_name = security_price)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct SecurityPrice {
+    pub id: i32,
+    pub security_id: i32,
+    pub price_date: chrono::NaiveDateTime,
+    pub price: f32,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = security_price)]
+pub struct NewSecurityPrice {
+    pub security_id: i32,
+    pub price_date: chrono::NaiveDateTime,
+    pub price: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum InvestmentKind {
+    #[default]
+    Buy,
+    Sell,
+    Dividend,
+}
+
+impl InvestmentKind {
+    pub fn all() -> &'static [Self] {
+        &[Self::Buy, Self::Sell, Self::Dividend]
+    }
+}
+
+impl From<i32> for InvestmentKind {
+    fn from(value: i32) -> Self {
+        match value {
+            1 => Self::Sell,
+            2 => Self::Dividend,
+            _ => Self::Buy,
+        }
+    }
+}
+
+impl From<InvestmentKind> for i32 {
+    fn from(value: InvestmentKind) -> Self {
+        match value {
+            InvestmentKind::Buy => 0,
+            InvestmentKind::Sell => 1,
+            InvestmentKind::Dividend => 2,
+        }
+    }
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize, Deserialize)]
+#[diesel(table_name = investment_transaction)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct InvestmentTransaction {
+    pub id: i32,
+    pub account_id: i32,
+    pub security_id: i32,
+    pub kind: i32,
+    pub quantity: f32,
+    pub price: f32,
+    pub amount: f32, // Cash moved by the operation, always positive
+    pub transaction_date: chrono::NaiveDateTime,
+}
+
+impl InvestmentTransaction {
+    pub fn kind(&self) -> InvestmentKind {
+        InvestmentKind::from(self.kind)
+    }
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = investment_transaction)]
+pub struct NewInvestmentTransaction {
+    pub account_id: i32,
+    pub security_id: i32,
+    pub kind: i32,
+    pub quantity: f32,
+    pub price: f32,
+    pub amount: f32,
+    pub transaction_date: chrono::NaiveDateTime,
 }
@@ -1,4 +1,4 @@
-use chrono::{NaiveDateTime, Utc};
+use chrono::{Duration, Local, NaiveDateTime, TimeZone, Utc};
 use cosmic::{
     iced::{self, alignment::Vertical, Length, Padding},
     widget::{self, column, settings, Space},
@@ -9,7 +9,19 @@ use crate::{
     app::AppMessage,
     config::Config,
     fl,
-    models::{Account, NewAccount, NewAccountTransfer, UpdateAccount},
+    models::{
+        Account, AccountGroup, AccountTransfer, AccountType, CreditCard, InvestmentKind, Loan,
+        MoneyTransaction, NewAccount, NewAccountGroup, NewAccountTransfer, NewCreditCard,
+        NewInvestmentTransaction, NewLoan, NewSecurityPrice, Security, SecurityPrice,
+        TransactionStatus, UpdateAccount,
+    },
+    store::transfer_effect,
+    utils::{
+        amortization::{self, PaymentFrequency},
+        credit_card::{self, StatementCycle},
+        dates,
+        investments::{self, Holding},
+    },
     widget::date_picker::date_picker,
     STORE,
 };
@@ -39,10 +51,55 @@ pub enum AccountsMessage {
     TransferSubmitted,
     TransferCancel,
     TransferDateChanged(i64),
+    NewAccountTypeChanged(usize),
+    NewLoanRateChanged(String),
+    NewLoanTermChanged(String),
+    NewLoanFrequencyChanged(usize),
+    NewLoanStartDateChanged(i64),
+    ToggleLoanSchedule(i32),
+    InvestmentFormView(i32),
+    InvestmentKindChanged(usize),
+    InvestmentSymbolChanged(String),
+    InvestmentQuantityChanged(String),
+    InvestmentPriceChanged(String),
+    InvestmentAmountChanged(String),
+    InvestmentDateChanged(i64),
+    InvestmentSubmit,
+    InvestmentCancel,
+    PriceFormView(i32),
+    PriceSymbolChanged(usize),
+    PriceValueChanged(String),
+    PriceDateChanged(i64),
+    PriceSubmit,
+    PriceCancel,
+    ImportPrices,
+    ReconcileView(i32),
+    /// Reconciles an account against the balance of a bank statement on
+    /// the given day.
+    ReconcileStatement(i32, i64, f32),
+    ReconcileEndDateChanged(i64),
+    ReconcileStatementBalanceChanged(String),
+    ToggleTransactionCleared(i32, bool),
+    ToggleTransferCleared(i32, bool),
+    ReconcileFinish,
+    ReconcileCancel,
+    NewCardClosingDayChanged(String),
+    NewCardDueDayChanged(String),
+    CardPaymentAccountChanged(usize),
+    PayStatement(i32),
+    ToggleGroupsView,
+    NewGroupNameChanged(String),
+    AddGroup,
+    DeleteGroup(i32),
+    EditAccountType(usize),
+    EditAccountGroup(usize),
+    EditExcludeFromTotal(bool),
+    EditExcludeFromStatistics(bool),
 }
 
 pub struct Accounts {
     currency_symbol: String,
+    projection_horizon_days: u32,
     accounts: Vec<Account>,
     add_account_view_visible: bool,
     money_transfer_view_visible: bool,
@@ -60,6 +117,48 @@ pub struct Accounts {
     transfer_description: String,
     transfer_amount: f32,
     transfer_date: i64,
+    loans: Vec<Loan>,
+    account_type_options: Vec<String>,
+    new_account_type: Option<usize>,
+    frequency_options: Vec<String>,
+    new_loan_frequency: Option<usize>,
+    new_loan_rate: String,
+    new_loan_term: String,
+    new_loan_start_date: i64,
+    loan_schedule_visible: Option<i32>,
+    securities: Vec<Security>,
+    security_prices: Vec<SecurityPrice>,
+    investment_kind_options: Vec<String>,
+    investment_form_account: Option<i32>,
+    investment_kind: Option<usize>,
+    investment_symbol: String,
+    investment_quantity: String,
+    investment_price: String,
+    investment_amount: String,
+    investment_date: i64,
+    price_form_account: Option<i32>,
+    price_security: Option<usize>,
+    price_value: String,
+    price_date: i64,
+    reconcile_account: Option<i32>,
+    reconcile_end_date: i64,
+    reconcile_statement_balance: String,
+    reconcile_transactions: Vec<MoneyTransaction>,
+    reconcile_transfers: Vec<AccountTransfer>,
+    reconciled_balance: f32,
+    credit_cards: Vec<CreditCard>,
+    new_card_closing_day: String,
+    new_card_due_day: String,
+    card_payment_account: Option<usize>,
+    account_groups: Vec<AccountGroup>,
+    group_options: Vec<String>,
+    groups_view_visible: bool,
+    new_group_name: String,
+    plain_type_options: Vec<String>,
+    edit_account_type: Option<usize>,
+    edit_account_group: Option<usize>,
+    edit_exclude_from_total: bool,
+    edit_exclude_from_statistics: bool,
 }
 
 impl Default for Accounts {
@@ -69,9 +168,15 @@ impl Default for Accounts {
 
         let accounts = store.get_accounts();
         let currency_symbol = store.get_currency_symbol_by_id(config.1.currency_id);
+        let loans = store.get_loans().unwrap_or_else(|_| vec![]);
+        let securities = store.get_securities().unwrap_or_else(|_| vec![]);
+        let security_prices = store.get_security_prices().unwrap_or_else(|_| vec![]);
+        let credit_cards = store.get_credit_cards().unwrap_or_else(|_| vec![]);
+        let account_groups = store.get_account_groups().unwrap_or_else(|_| vec![]);
 
         Self {
             currency_symbol: currency_symbol.unwrap_or_else(|_| "USD".to_string()),
+            projection_horizon_days: config.1.projection_horizon_days,
             accounts: if let Ok(accounts) = accounts {
                 accounts
             } else {
@@ -93,6 +198,64 @@ impl Default for Accounts {
             transfer_form_amount: String::default(),
             transfer_date: Utc::now().timestamp(),
             transfer_description: String::default(),
+            loans,
+            account_type_options: AccountType::all()
+                .iter()
+                .map(|t| account_type_title(*t))
+                .collect(),
+            new_account_type: AccountType::all()
+                .iter()
+                .position(|t| *t == AccountType::Checking),
+            frequency_options: PaymentFrequency::all()
+                .iter()
+                .map(|f| frequency_title(*f))
+                .collect(),
+            new_loan_frequency: PaymentFrequency::all()
+                .iter()
+                .position(|f| *f == PaymentFrequency::default()),
+            new_loan_rate: String::default(),
+            new_loan_term: String::default(),
+            new_loan_start_date: Utc::now().timestamp(),
+            loan_schedule_visible: None,
+            securities,
+            security_prices,
+            investment_kind_options: InvestmentKind::all()
+                .iter()
+                .map(|k| investment_kind_title(*k))
+                .collect(),
+            investment_form_account: None,
+            investment_kind: Some(0),
+            investment_symbol: String::default(),
+            investment_quantity: String::default(),
+            investment_price: String::default(),
+            investment_amount: String::default(),
+            investment_date: Utc::now().timestamp(),
+            price_form_account: None,
+            price_security: None,
+            price_value: String::default(),
+            price_date: Utc::now().timestamp(),
+            reconcile_account: None,
+            reconcile_end_date: Utc::now().timestamp(),
+            reconcile_statement_balance: String::default(),
+            reconcile_transactions: vec![],
+            reconcile_transfers: vec![],
+            reconciled_balance: 0.,
+            credit_cards,
+            new_card_closing_day: String::default(),
+            new_card_due_day: String::default(),
+            card_payment_account: Some(0),
+            group_options: group_options(&account_groups),
+            account_groups,
+            groups_view_visible: false,
+            new_group_name: String::default(),
+            plain_type_options: plain_account_types()
+                .iter()
+                .map(|t| account_type_title(*t))
+                .collect(),
+            edit_account_type: None,
+            edit_account_group: Some(0),
+            edit_exclude_from_total: false,
+            edit_exclude_from_statistics: false,
         }
     }
 }
@@ -125,7 +288,11 @@ impl Accounts {
                         )
                     } else {
                         None
-                    }),
+                    })
+                    .push(
+                        cosmic::widget::button::text(fl!("account-groups"))
+                            .on_press(AccountsMessage::ToggleGroupsView),
+                    ),
             )
             .width(iced::Length::Fill)
             .align_x(iced::alignment::Horizontal::Right),
@@ -139,116 +306,264 @@ impl Accounts {
             col = col.push(self.transfer_money_view())
         }
 
+        if self.groups_view_visible {
+            col = col.push(self.account_groups_view())
+        }
+
         if self.accounts.len() > 0 {
-            for account in &self.accounts {
-                let edit_button = widget::button::icon(widget::icon::from_name("edit-symbolic"))
-                    .on_press(AccountsMessage::EditAccount(account.id));
-                let mut main_col = widget::column().push(
+            for (title, accounts) in self.grouped_accounts() {
+                col = col.push(
+                    widget::row()
+                        .align_y(Vertical::Center)
+                        .push(widget::text::title3(title))
+                        .push(widget::Space::with_width(Length::Fill))
+                        .push(widget::text::title4(format!(
+                            "{:.2} {}",
+                            self.calc_subtotal(&accounts),
+                            self.currency_symbol
+                        ))),
+                );
+                col = col.push(Space::with_height(10));
+                for account in accounts {
+                    col = col
+                        .push(self.account_section(account))
+                        .push(Space::with_height(20));
+                }
+            }
+        } else {
+            col = col.push(widget::text::text(fl!("no-elements")));
+        }
+
+        widget::scrollable(
+            widget::container(col)
+                .width(iced::Length::Fill)
+                .height(iced::Length::Shrink),
+        )
+        .into()
+    }
+
+    fn account_section<'a>(&'a self, account: &'a Account) -> Element<'a, AccountsMessage> {
+        let edit_button = widget::button::icon(widget::icon::from_name("edit-symbolic"))
+            .on_press(AccountsMessage::EditAccount(account.id));
+        let mut main_col = widget::column().push(
+            widget::row()
+                .push(
+                    widget::column()
+                        .push(widget::text::text(fl!(
+                            "current-balance",
+                            balance = format!("{:.2}", self.read_account_balance(account.id)),
+                            currency = self.currency_symbol.clone()
+                        )))
+                        .push(widget::text::text(fl!(
+                            "projected-balance",
+                            balance = format!("{:.2}", self.read_projected_balance(account.id)),
+                            currency = self.currency_symbol.clone(),
+                            days = self.projection_horizon_days
+                        )))
+                        .push_maybe(self.read_negative_projection(account).map(
+                            |(date, balance)| {
+                                widget::text::text(fl!(
+                                    "negative-balance-warning",
+                                    date = Local
+                                        .from_utc_datetime(&date)
+                                        .format("%d-%m-%Y")
+                                        .to_string(),
+                                    balance = format!("{:.2}", balance),
+                                    currency = self.currency_symbol.clone()
+                                ))
+                                .class(cosmic::theme::Text::Accent)
+                            },
+                        ))
+                        .width(Length::Fill),
+                )
+                .push(match self.editing_account {
+                    Some(id) => {
+                        if id == account.id {
+                            widget::button::icon(widget::icon::from_name("window-close-symbolic"))
+                                .on_press(AccountsMessage::CloseEditAccount)
+                        } else {
+                            edit_button
+                        }
+                    }
+                    None => edit_button,
+                })
+                .width(Length::Fill),
+        );
+        if let Some(loan) = self.loans.iter().find(|l| l.account_id == account.id) {
+            main_col = main_col.push(self.loan_view(loan));
+        }
+        if account.kind() == AccountType::Investment {
+            main_col = main_col.push(self.investment_view(account));
+        }
+        if let Some(card) = self
+            .credit_cards
+            .iter()
+            .find(|c| c.account_id == account.id)
+        {
+            main_col = main_col.push(self.credit_card_view(card));
+        }
+        if self.reconcile_account == Some(account.id) {
+            main_col = main_col.push(self.reconcile_view(account.id));
+        } else {
+            main_col = main_col.push(
+                widget::button::text(fl!("reconcile"))
+                    .on_press(AccountsMessage::ReconcileView(account.id)),
+            );
+        }
+        if let Some(account_id) = self.editing_account {
+            if account_id == account.id {
+                main_col = main_col.push(widget::divider::horizontal::default());
+                main_col = main_col.push(Space::with_height(10));
+                main_col = main_col.push(
                     widget::row()
                         .push(
                             widget::column()
-                                .push(widget::text::text(format!(
-                                    "{}: {} {}",
-                                    "Balance",
-                                    format!("{:.2}", self.read_account_balance(account.id)),
-                                    self.currency_symbol
-                                )))
+                                .push(widget::text::title4(fl!("account-name")))
+                                .push(
+                                    widget::text_input(
+                                        fl!("account-name"),
+                                        &self.edit_account_name,
+                                    )
+                                    .on_input(AccountsMessage::EditAccountName),
+                                )
                                 .width(Length::Fill),
                         )
-                        .push(match self.editing_account {
-                            Some(id) => {
-                                if id == account.id {
-                                    widget::button::icon(widget::icon::from_name(
-                                        "window-close-symbolic",
+                        .push(Space::with_width(10))
+                        .push(
+                            widget::column()
+                                .push(widget::text::title4(fl!("balance")))
+                                .push(
+                                    widget::text_input(fl!("balance"), &self.edit_account_balance)
+                                        .on_input(AccountsMessage::EditAccountBalance),
+                                )
+                                .width(Length::Fill),
+                        ),
+                );
+                main_col = main_col.push(Space::with_height(10));
+                main_col = main_col.push(
+                    widget::column()
+                        .push(widget::text::text(fl!("description")))
+                        .push(
+                            widget::text_input(fl!("description"), &self.edit_account_description)
+                                .on_input(AccountsMessage::EditAccountDescription),
+                        ),
+                );
+                main_col = main_col.push(Space::with_height(10));
+                main_col = main_col.push(
+                    widget::row()
+                        .push_maybe(if account.kind().is_plain() {
+                            Some(
+                                widget::column()
+                                    .push(widget::text::text(fl!("account-type")))
+                                    .push(widget::dropdown(
+                                        &self.plain_type_options,
+                                        self.edit_account_type,
+                                        AccountsMessage::EditAccountType,
                                     ))
-                                    .on_press(AccountsMessage::CloseEditAccount)
-                                } else {
-                                    edit_button
-                                }
-                            }
-                            None => edit_button,
+                                    .width(Length::Fill),
+                            )
+                        } else {
+                            None
                         })
-                        .width(Length::Fill),
+                        .push(
+                            widget::column()
+                                .push(widget::text::text(fl!("account-group")))
+                                .push(widget::dropdown(
+                                    &self.group_options,
+                                    self.edit_account_group,
+                                    AccountsMessage::EditAccountGroup,
+                                ))
+                                .width(Length::Fill),
+                        )
+                        .spacing(10),
                 );
-                if let Some(account_id) = self.editing_account {
-                    if account_id == account.id {
-                        main_col = main_col.push(widget::divider::horizontal::default());
-                        main_col = main_col.push(Space::with_height(10));
-                        main_col = main_col.push(
+                main_col = main_col.push(Space::with_height(10));
+                main_col = main_col
+                    .push(
+                        widget::checkbox(fl!("exclude-from-total"), self.edit_exclude_from_total)
+                            .on_toggle(AccountsMessage::EditExcludeFromTotal),
+                    )
+                    .push(Space::with_height(5))
+                    .push(
+                        widget::checkbox(
+                            fl!("exclude-from-statistics"),
+                            self.edit_exclude_from_statistics,
+                        )
+                        .on_toggle(AccountsMessage::EditExcludeFromStatistics),
+                    );
+                main_col = main_col.push(Space::with_height(10));
+                main_col = main_col.push(
+                    widget::row()
+                        .push(
+                            widget::button::text(fl!("save"))
+                                .on_press(AccountsMessage::EditAccountSubmit)
+                                .class(widget::button::ButtonClass::Suggested),
+                        )
+                        .push(Space::with_width(10))
+                        .push(
+                            widget::button::text(fl!("cancel"))
+                                .on_press(AccountsMessage::CloseEditAccount)
+                                .class(widget::button::ButtonClass::Destructive),
+                        ),
+                )
+            }
+        }
+        settings::section()
+            .title(account.name.to_string())
+            .add(main_col)
+            .into()
+    }
+
+    fn account_groups_view<'a>(&'a self) -> Element<'a, AccountsMessage> {
+        let mut groups = widget::column();
+        for group in &self.account_groups {
+            groups = groups.push(
+                widget::row()
+                    .push(widget::text::text(group.group_name.clone()).width(Length::Fill))
+                    .push(
+                        widget::button::icon(widget::icon::from_name("edit-delete-symbolic"))
+                            .on_press(AccountsMessage::DeleteGroup(group.id)),
+                    )
+                    .align_y(Vertical::Center),
+            );
+        }
+
+        widget::column()
+            .push(Space::with_height(10))
+            .push(
+                widget::container(
+                    widget::column()
+                        .push(widget::text::title3(fl!("account-groups")))
+                        .push(Space::with_height(5))
+                        .push(groups)
+                        .push(Space::with_height(5))
+                        .push(
                             widget::row()
                                 .push(
-                                    widget::column()
-                                        .push(widget::text::title4(fl!("account-name")))
-                                        .push(
-                                            widget::text_input(
-                                                fl!("account-name"),
-                                                &self.edit_account_name,
-                                            )
-                                            .on_input(AccountsMessage::EditAccountName),
-                                        )
+                                    widget::text_input(fl!("group-name"), &self.new_group_name)
+                                        .on_input(AccountsMessage::NewGroupNameChanged)
                                         .width(Length::Fill),
                                 )
                                 .push(Space::with_width(10))
                                 .push(
-                                    widget::column()
-                                        .push(widget::text::title4(fl!("balance")))
-                                        .push(
-                                            widget::text_input(
-                                                fl!("balance"),
-                                                &self.edit_account_balance,
-                                            )
-                                            .on_input(AccountsMessage::EditAccountBalance),
-                                        )
-                                        .width(Length::Fill),
-                                ),
-                        );
-                        main_col = main_col.push(Space::with_height(10));
-                        main_col = main_col.push(
-                            widget::column()
-                                .push(widget::text::text(fl!("description")))
-                                .push(
-                                    widget::text_input(
-                                        fl!("description"),
-                                        &self.edit_account_description,
-                                    )
-                                    .on_input(AccountsMessage::EditAccountDescription),
-                                ),
-                        );
-                        main_col = main_col.push(Space::with_height(10));
-                        main_col = main_col.push(
-                            widget::row()
-                                .push(
-                                    widget::button::text(fl!("save"))
-                                        .on_press(AccountsMessage::EditAccountSubmit)
-                                        .class(widget::button::ButtonClass::Suggested),
+                                    widget::button::suggested(fl!("add")).on_press_maybe(
+                                        (!self.new_group_name.trim().is_empty())
+                                            .then_some(AccountsMessage::AddGroup),
+                                    ),
                                 )
                                 .push(Space::with_width(10))
                                 .push(
-                                    widget::button::text(fl!("cancel"))
-                                        .on_press(AccountsMessage::CloseEditAccount)
-                                        .class(widget::button::ButtonClass::Destructive),
+                                    widget::button::text(fl!("close"))
+                                        .on_press(AccountsMessage::ToggleGroupsView),
                                 ),
-                        )
-                    }
-                }
-                col = col
-                    .push(
-                        settings::section()
-                            .title(account.name.to_string())
-                            .add(main_col),
-                    )
-                    .push(Space::with_height(20));
-            }
-        } else {
-            col = col.push(widget::text::text(fl!("no-elements")));
-        }
-
-        widget::scrollable(
-            widget::container(col)
-                .width(iced::Length::Fill)
-                .height(iced::Length::Shrink),
-        )
-        .into()
+                        ),
+                )
+                .width(Length::Fill)
+                .padding(Padding::new(10.))
+                .class(cosmic::theme::Container::Card),
+            )
+            .push(Space::with_height(10))
+            .into()
     }
 
     fn add_account_view<'a>(&'a self) -> Element<'a, AccountsMessage> {
@@ -261,6 +576,13 @@ impl Accounts {
                 widget::column()
                     .push(widget::text::title3(fl!("new-account")))
                     .push(Space::with_height(5))
+                    .push(widget::text::text(fl!("account-type")))
+                    .push(widget::dropdown(
+                        &self.account_type_options,
+                        self.new_account_type,
+                        AccountsMessage::NewAccountTypeChanged,
+                    ))
+                    .push(Space::with_height(10))
                     .push(
                         widget::row()
                             .push(
@@ -278,7 +600,11 @@ impl Accounts {
                             .push(Space::with_width(10))
                             .push(
                                 widget::column()
-                                    .push(widget::text::text(fl!("initial-value")))
+                                    .push(widget::text::text(if self.is_new_account_loan() {
+                                        fl!("principal")
+                                    } else {
+                                        fl!("initial-value")
+                                    }))
                                     .push(
                                         cosmic::widget::text_input(
                                             "0",
@@ -305,6 +631,16 @@ impl Accounts {
                                 .on_input(AccountsMessage::NewAccountDescriptionChanged),
                             ),
                     )
+                    .push_maybe(if self.is_new_account_loan() {
+                        Some(self.new_loan_fields_view())
+                    } else {
+                        None
+                    })
+                    .push_maybe(if self.selected_new_account_type() == AccountType::Credit {
+                        Some(self.new_card_fields_view())
+                    } else {
+                        None
+                    })
                     .push(Space::with_height(10))
                     .push(
                         widget::row()
@@ -334,6 +670,623 @@ impl Accounts {
         element.into()
     }
 
+    fn new_card_fields_view<'a>(&'a self) -> Element<'a, AccountsMessage> {
+        widget::column()
+            .push(Space::with_height(10))
+            .push(
+                widget::row()
+                    .push(
+                        widget::column()
+                            .push(widget::text::text(fl!("closing-day")))
+                            .push(
+                                widget::text_input("1-31", &self.new_card_closing_day)
+                                    .on_input(AccountsMessage::NewCardClosingDayChanged),
+                            )
+                            .width(Length::Fill),
+                    )
+                    .push(Space::with_width(10))
+                    .push(
+                        widget::column()
+                            .push(widget::text::text(fl!("due-day")))
+                            .push(
+                                widget::text_input("1-31", &self.new_card_due_day)
+                                    .on_input(AccountsMessage::NewCardDueDayChanged),
+                            )
+                            .width(Length::Fill),
+                    ),
+            )
+            .into()
+    }
+
+    fn credit_card_view<'a>(&'a self, card: &CreditCard) -> Element<'a, AccountsMessage> {
+        let today = dates::local_today();
+        let statements = self.read_statements(card);
+        let format_date =
+            |date: &NaiveDateTime| Local.from_utc_datetime(date).format("%d-%m-%Y").to_string();
+
+        let mut element = widget::column()
+            .push(widget::text::text(fl!(
+                "next-closing-date",
+                date = credit_card::next_closing_date(card, today)
+                    .format("%d-%m-%Y")
+                    .to_string()
+            )))
+            .push(Space::with_height(5));
+
+        if let Some((cycle, statement, paid)) = statements.first() {
+            let remaining = statement - paid;
+            if remaining > 0.005 {
+                let days_left = (dates::local_date(&Local, &cycle.due_date) - today).num_days();
+                element = element.push(
+                    widget::text::text(if days_left < 0 {
+                        fl!(
+                            "statement-overdue",
+                            amount = format!("{:.2}", remaining),
+                            currency = self.currency_symbol.clone(),
+                            date = format_date(&cycle.due_date)
+                        )
+                    } else {
+                        fl!(
+                            "statement-due",
+                            amount = format!("{:.2}", remaining),
+                            currency = self.currency_symbol.clone(),
+                            date = format_date(&cycle.due_date),
+                            days = days_left
+                        )
+                    })
+                    .class(cosmic::theme::Text::Accent),
+                );
+            }
+        }
+
+        element = element.push(
+            widget::row()
+                .push(widget::text::text(fl!("closing-date")).width(Length::Fill))
+                .push(widget::text::text(fl!("due-date")).width(Length::Fill))
+                .push(widget::text::text(fl!("statement-balance")).width(Length::Fill))
+                .push(widget::text::text(fl!("paid")).width(Length::Fill)),
+        );
+        for (cycle, statement, paid) in &statements {
+            element = element.push(
+                widget::row()
+                    .push(widget::text::text(format_date(&cycle.closing_date)).width(Length::Fill))
+                    .push(widget::text::text(format_date(&cycle.due_date)).width(Length::Fill))
+                    .push(widget::text::text(format!("{:.2}", statement)).width(Length::Fill))
+                    .push(widget::text::text(format!("{:.2}", paid)).width(Length::Fill)),
+            );
+        }
+
+        let can_pay = statements
+            .first()
+            .is_some_and(|(_, statement, paid)| statement - paid > 0.005);
+        element = element.push(Space::with_height(5)).push(
+            widget::row()
+                .push(widget::text::text(fl!("pay-from")))
+                .push(Space::with_width(5))
+                .push(widget::dropdown(
+                    &self.accounts,
+                    self.card_payment_account,
+                    AccountsMessage::CardPaymentAccountChanged,
+                ))
+                .push(Space::with_width(10))
+                .push(
+                    widget::button::suggested(fl!("pay-statement")).on_press_maybe(
+                        can_pay.then_some(AccountsMessage::PayStatement(card.account_id)),
+                    ),
+                )
+                .align_y(Vertical::Center),
+        );
+
+        element.into()
+    }
+
+    fn new_loan_fields_view<'a>(&'a self) -> Element<'a, AccountsMessage> {
+        widget::column()
+            .push(Space::with_height(10))
+            .push(
+                widget::row()
+                    .push(
+                        widget::column()
+                            .push(widget::text::text(fl!("interest-rate")))
+                            .push(
+                                widget::text_input("0", &self.new_loan_rate)
+                                    .on_input(AccountsMessage::NewLoanRateChanged),
+                            )
+                            .width(Length::Fill),
+                    )
+                    .push(Space::with_width(10))
+                    .push(
+                        widget::column()
+                            .push(widget::text::text(fl!("loan-term")))
+                            .push(
+                                widget::text_input("0", &self.new_loan_term)
+                                    .on_input(AccountsMessage::NewLoanTermChanged),
+                            )
+                            .width(Length::Fill),
+                    )
+                    .push(Space::with_width(10))
+                    .push(
+                        widget::column()
+                            .push(widget::text::text(fl!("payment-frequency")))
+                            .push(widget::dropdown(
+                                &self.frequency_options,
+                                self.new_loan_frequency,
+                                AccountsMessage::NewLoanFrequencyChanged,
+                            ))
+                            .width(Length::Fill),
+                    ),
+            )
+            .push(Space::with_height(10))
+            .push(widget::text::text(fl!("start-date")))
+            .push(Space::with_height(5))
+            .push(date_picker(self.new_loan_start_date, |date| {
+                AccountsMessage::NewLoanStartDateChanged(date)
+            }))
+            .into()
+    }
+
+    fn loan_view<'a>(&'a self, loan: &Loan) -> Element<'a, AccountsMessage> {
+        let remaining = -self.read_account_balance(loan.account_id);
+        let payments_made = self.read_loan_payments_count(loan.id);
+        let installment = amortization::loan_payment_amount(loan);
+
+        let mut element = widget::column()
+            .push(widget::text::text(fl!(
+                "remaining-balance",
+                balance = format!("{:.2}", remaining.max(0.)),
+                currency = self.currency_symbol.clone()
+            )))
+            .push(widget::text::text(fl!(
+                "loan-installment",
+                amount = format!("{:.2}", installment),
+                currency = self.currency_symbol.clone(),
+                frequency = frequency_title(PaymentFrequency::from_payments_per_year(
+                    loan.payment_frequency
+                ))
+            )))
+            .push(widget::text::text(if remaining <= 0. {
+                fl!("loan-paid-off")
+            } else {
+                match amortization::payoff_date(loan, remaining, payments_made) {
+                    Some(date) => fl!(
+                        "payoff-date",
+                        date = Local
+                            .from_utc_datetime(&date)
+                            .format("%d-%m-%Y")
+                            .to_string()
+                    ),
+                    None => fl!("payoff-never"),
+                }
+            }))
+            .push(Space::with_height(5))
+            .push(
+                widget::button::text(if self.loan_schedule_visible == Some(loan.account_id) {
+                    fl!("hide-schedule")
+                } else {
+                    fl!("show-schedule")
+                })
+                .on_press(AccountsMessage::ToggleLoanSchedule(loan.account_id)),
+            );
+
+        if self.loan_schedule_visible == Some(loan.account_id) {
+            element = element.push(Space::with_height(5)).push(
+                widget::row()
+                    .push(widget::text::text("#").width(Length::Fixed(40.)))
+                    .push(widget::text::text(fl!("date")).width(Length::Fill))
+                    .push(widget::text::text(fl!("loan-payment")).width(Length::Fill))
+                    .push(widget::text::text(fl!("principal")).width(Length::Fill))
+                    .push(widget::text::text(fl!("interest")).width(Length::Fill))
+                    .push(widget::text::text(fl!("balance")).width(Length::Fill)),
+            );
+            for entry in amortization::schedule(loan) {
+                let row = widget::row()
+                    .push(
+                        widget::text::text(if entry.number <= payments_made {
+                            format!("{} ✓", entry.number)
+                        } else {
+                            entry.number.to_string()
+                        })
+                        .width(Length::Fixed(40.)),
+                    )
+                    .push(
+                        widget::text::text(
+                            Local
+                                .from_utc_datetime(&entry.date)
+                                .format("%d-%m-%Y")
+                                .to_string(),
+                        )
+                        .width(Length::Fill),
+                    )
+                    .push(widget::text::text(format!("{:.2}", entry.payment)).width(Length::Fill))
+                    .push(widget::text::text(format!("{:.2}", entry.principal)).width(Length::Fill))
+                    .push(widget::text::text(format!("{:.2}", entry.interest)).width(Length::Fill))
+                    .push(widget::text::text(format!("{:.2}", entry.balance)).width(Length::Fill));
+                element = element.push(row);
+            }
+        }
+
+        element.into()
+    }
+
+    fn investment_view<'a>(&'a self, account: &Account) -> Element<'a, AccountsMessage> {
+        let holdings = self.read_holdings(account.id);
+        let market_value: f32 = holdings.iter().map(|h| h.market_value()).sum();
+        let gain: f32 = holdings.iter().map(|h| h.unrealized_gain()).sum();
+
+        let mut element = widget::column()
+            .push(widget::text::text(fl!(
+                "market-value",
+                value = format!("{:.2}", market_value),
+                currency = self.currency_symbol.clone()
+            )))
+            .push(widget::text::text(fl!(
+                "unrealized-gain",
+                value = format!("{:+.2}", gain),
+                currency = self.currency_symbol.clone()
+            )))
+            .push(Space::with_height(5));
+
+        if holdings.is_empty() {
+            element = element.push(widget::text::text(fl!("no-holdings")));
+        } else {
+            element = element.push(
+                widget::row()
+                    .push(widget::text::text(fl!("symbol")).width(Length::Fill))
+                    .push(widget::text::text(fl!("quantity")).width(Length::Fill))
+                    .push(widget::text::text(fl!("cost-basis")).width(Length::Fill))
+                    .push(widget::text::text(fl!("price")).width(Length::Fill))
+                    .push(widget::text::text(fl!("value")).width(Length::Fill))
+                    .push(widget::text::text(fl!("gain")).width(Length::Fill)),
+            );
+            for holding in holdings {
+                element = element.push(
+                    widget::row()
+                        .push(widget::text::text(holding.symbol.clone()).width(Length::Fill))
+                        .push(
+                            widget::text::text(format!("{}", holding.quantity)).width(Length::Fill),
+                        )
+                        .push(
+                            widget::text::text(format!("{:.2}", holding.cost_basis))
+                                .width(Length::Fill),
+                        )
+                        .push(
+                            widget::text::text(match (holding.price, holding.price_date) {
+                                (Some(price), Some(date)) => format!(
+                                    "{:.2} ({})",
+                                    price,
+                                    Local.from_utc_datetime(&date).format("%d-%m-%Y")
+                                ),
+                                _ => "-".to_string(),
+                            })
+                            .width(Length::Fill),
+                        )
+                        .push(
+                            widget::text::text(format!("{:.2}", holding.market_value()))
+                                .width(Length::Fill),
+                        )
+                        .push(
+                            widget::text::text(format!("{:+.2}", holding.unrealized_gain()))
+                                .width(Length::Fill),
+                        ),
+                );
+            }
+        }
+
+        element = element.push(Space::with_height(5)).push(
+            widget::row()
+                .push(
+                    widget::button::text(fl!("add-investment-operation"))
+                        .on_press(AccountsMessage::InvestmentFormView(account.id)),
+                )
+                .push(Space::with_width(10))
+                .push(
+                    widget::button::text(fl!("update-price"))
+                        .on_press(AccountsMessage::PriceFormView(account.id)),
+                )
+                .push(Space::with_width(10))
+                .push(
+                    widget::button::text(fl!("import-prices"))
+                        .on_press(AccountsMessage::ImportPrices),
+                ),
+        );
+
+        if self.investment_form_account == Some(account.id) {
+            element = element.push(self.investment_form_view());
+        }
+        if self.price_form_account == Some(account.id) {
+            element = element.push(self.price_form_view());
+        }
+
+        element.into()
+    }
+
+    fn investment_form_view<'a>(&'a self) -> Element<'a, AccountsMessage> {
+        let is_dividend = self.selected_investment_kind() == InvestmentKind::Dividend;
+
+        widget::container(
+            widget::column()
+                .push(widget::text::title4(fl!("add-investment-operation")))
+                .push(Space::with_height(5))
+                .push(
+                    widget::row()
+                        .push(
+                            widget::column()
+                                .push(widget::text::text(fl!("operation")))
+                                .push(widget::dropdown(
+                                    &self.investment_kind_options,
+                                    self.investment_kind,
+                                    AccountsMessage::InvestmentKindChanged,
+                                ))
+                                .width(Length::Fill),
+                        )
+                        .push(Space::with_width(10))
+                        .push(
+                            widget::column()
+                                .push(widget::text::text(fl!("symbol")))
+                                .push(
+                                    widget::text_input(fl!("symbol"), &self.investment_symbol)
+                                        .on_input(AccountsMessage::InvestmentSymbolChanged),
+                                )
+                                .width(Length::Fill),
+                        ),
+                )
+                .push(Space::with_height(5))
+                .push(if is_dividend {
+                    widget::row().push(
+                        widget::column()
+                            .push(widget::text::text(fl!("amount")))
+                            .push(
+                                widget::text_input("0", &self.investment_amount)
+                                    .on_input(AccountsMessage::InvestmentAmountChanged),
+                            )
+                            .width(Length::Fill),
+                    )
+                } else {
+                    widget::row()
+                        .push(
+                            widget::column()
+                                .push(widget::text::text(fl!("quantity")))
+                                .push(
+                                    widget::text_input("0", &self.investment_quantity)
+                                        .on_input(AccountsMessage::InvestmentQuantityChanged),
+                                )
+                                .width(Length::Fill),
+                        )
+                        .push(Space::with_width(10))
+                        .push(
+                            widget::column()
+                                .push(widget::text::text(fl!("price")))
+                                .push(
+                                    widget::text_input("0", &self.investment_price)
+                                        .on_input(AccountsMessage::InvestmentPriceChanged),
+                                )
+                                .width(Length::Fill),
+                        )
+                })
+                .push(Space::with_height(5))
+                .push(widget::text::text(fl!("date")))
+                .push(date_picker(self.investment_date, |date| {
+                    AccountsMessage::InvestmentDateChanged(date)
+                }))
+                .push(Space::with_height(10))
+                .push(
+                    widget::row()
+                        .push(
+                            widget::button::suggested(fl!("confirm"))
+                                .on_press(AccountsMessage::InvestmentSubmit),
+                        )
+                        .push(Space::with_width(10))
+                        .push(
+                            widget::button::destructive(fl!("cancel"))
+                                .on_press(AccountsMessage::InvestmentCancel),
+                        ),
+                ),
+        )
+        .width(Length::Fill)
+        .padding(Padding::new(10.))
+        .class(cosmic::theme::Container::Card)
+        .into()
+    }
+
+    fn price_form_view<'a>(&'a self) -> Element<'a, AccountsMessage> {
+        widget::container(
+            widget::column()
+                .push(widget::text::title4(fl!("update-price")))
+                .push(Space::with_height(5))
+                .push(
+                    widget::row()
+                        .push(
+                            widget::column()
+                                .push(widget::text::text(fl!("symbol")))
+                                .push(widget::dropdown(
+                                    &self.securities,
+                                    self.price_security,
+                                    AccountsMessage::PriceSymbolChanged,
+                                ))
+                                .width(Length::Fill),
+                        )
+                        .push(Space::with_width(10))
+                        .push(
+                            widget::column()
+                                .push(widget::text::text(fl!("price")))
+                                .push(
+                                    widget::text_input("0", &self.price_value)
+                                        .on_input(AccountsMessage::PriceValueChanged),
+                                )
+                                .width(Length::Fill),
+                        ),
+                )
+                .push(Space::with_height(5))
+                .push(widget::text::text(fl!("date")))
+                .push(date_picker(self.price_date, |date| {
+                    AccountsMessage::PriceDateChanged(date)
+                }))
+                .push(Space::with_height(10))
+                .push(
+                    widget::row()
+                        .push(
+                            widget::button::suggested(fl!("save"))
+                                .on_press(AccountsMessage::PriceSubmit),
+                        )
+                        .push(Space::with_width(10))
+                        .push(
+                            widget::button::destructive(fl!("cancel"))
+                                .on_press(AccountsMessage::PriceCancel),
+                        ),
+                ),
+        )
+        .width(Length::Fill)
+        .padding(Padding::new(10.))
+        .class(cosmic::theme::Container::Card)
+        .into()
+    }
+
+    fn reconcile_view<'a>(&'a self, account_id: i32) -> Element<'a, AccountsMessage> {
+        let cleared_balance = self.reconcile_cleared_balance(account_id);
+        let difference = self
+            .reconcile_statement_balance
+            .parse::<f32>()
+            .ok()
+            .map(|statement| statement - cleared_balance);
+
+        let mut items = widget::column();
+        if self.reconcile_transactions.is_empty() && self.reconcile_transfers.is_empty() {
+            items = items.push(widget::text::text(fl!("nothing-to-reconcile")));
+        }
+        for t in &self.reconcile_transactions {
+            let id = t.id;
+            items = items.push(
+                widget::row()
+                    .push(
+                        widget::checkbox("", t.status() == TransactionStatus::Cleared).on_toggle(
+                            move |checked| AccountsMessage::ToggleTransactionCleared(id, checked),
+                        ),
+                    )
+                    .push(
+                        widget::text::text(
+                            Local
+                                .from_utc_datetime(&t.transaction_date)
+                                .format("%d-%m-%Y")
+                                .to_string(),
+                        )
+                        .width(Length::Fill),
+                    )
+                    .push(widget::text::text(t.description.clone()).width(Length::Fill))
+                    .push(widget::text::text(format!(
+                        "{}{:.2} {}",
+                        if t.is_expense { "-" } else { "+" },
+                        t.amount,
+                        self.currency_symbol
+                    )))
+                    .align_y(Vertical::Center),
+            );
+        }
+        for t in &self.reconcile_transfers {
+            let id = t.id;
+            let effect = if t.from_account == account_id {
+                -t.amount
+            } else {
+                t.amount
+            };
+            items = items.push(
+                widget::row()
+                    .push(
+                        widget::checkbox(
+                            "",
+                            t.status_for(account_id) == TransactionStatus::Cleared,
+                        )
+                        .on_toggle(move |checked| {
+                            AccountsMessage::ToggleTransferCleared(id, checked)
+                        }),
+                    )
+                    .push(
+                        widget::text::text(
+                            Local
+                                .from_utc_datetime(&t.transfer_date)
+                                .format("%d-%m-%Y")
+                                .to_string(),
+                        )
+                        .width(Length::Fill),
+                    )
+                    .push(
+                        widget::text::text(
+                            t.description.clone().unwrap_or_else(|| fl!("transfer")),
+                        )
+                        .width(Length::Fill),
+                    )
+                    .push(widget::text::text(format!(
+                        "{:+.2} {}",
+                        effect, self.currency_symbol
+                    )))
+                    .align_y(Vertical::Center),
+            );
+        }
+
+        widget::container(
+            widget::column()
+                .push(widget::text::title4(fl!("reconcile")))
+                .push(Space::with_height(5))
+                .push(
+                    widget::row()
+                        .push(
+                            widget::column()
+                                .push(widget::text::text(fl!("statement-end-date")))
+                                .push(date_picker(self.reconcile_end_date, |date| {
+                                    AccountsMessage::ReconcileEndDateChanged(date)
+                                }))
+                                .width(Length::Fill),
+                        )
+                        .push(Space::with_width(10))
+                        .push(
+                            widget::column()
+                                .push(widget::text::text(fl!("statement-balance")))
+                                .push(
+                                    widget::text_input("0", &self.reconcile_statement_balance)
+                                        .on_input(
+                                            AccountsMessage::ReconcileStatementBalanceChanged,
+                                        ),
+                                )
+                                .width(Length::Fill),
+                        ),
+                )
+                .push(Space::with_height(10))
+                .push(items)
+                .push(Space::with_height(10))
+                .push(widget::text::text(fl!(
+                    "cleared-balance",
+                    balance = format!("{:.2}", cleared_balance),
+                    currency = self.currency_symbol.clone()
+                )))
+                .push(widget::text::text(fl!(
+                    "reconcile-difference",
+                    difference = difference
+                        .map(|d| format!("{:.2}", d))
+                        .unwrap_or_else(|| "-".to_string()),
+                    currency = self.currency_symbol.clone()
+                )))
+                .push(Space::with_height(10))
+                .push(
+                    widget::row()
+                        .push(
+                            widget::button::suggested(fl!("finish-reconcile")).on_press_maybe(
+                                difference
+                                    .filter(|d| d.abs() < 0.005)
+                                    .map(|_| AccountsMessage::ReconcileFinish),
+                            ),
+                        )
+                        .push(Space::with_width(10))
+                        .push(
+                            widget::button::destructive(fl!("cancel"))
+                                .on_press(AccountsMessage::ReconcileCancel),
+                        ),
+                ),
+        )
+        .width(Length::Fill)
+        .padding(Padding::new(10.))
+        .class(cosmic::theme::Container::Card)
+        .into()
+    }
+
     fn transfer_money_view<'a>(&'a self) -> Element<'a, AccountsMessage> {
         let mut element = widget::column();
 
@@ -374,6 +1327,14 @@ impl Accounts {
                             .width(Length::Fill)
                             .on_input(AccountsMessage::TransferAmountChanged),
                     )
+                    .push_maybe(self.transfer_loan_split().map(|(principal, interest)| {
+                        widget::text::text(fl!(
+                            "loan-payment-split",
+                            principal = format!("{:.2}", principal),
+                            interest = format!("{:.2}", interest),
+                            currency = self.currency_symbol.clone()
+                        ))
+                    }))
                     .push(Space::with_height(5))
                     .push(widget::text::text(fl!("description")))
                     .push(
@@ -419,6 +1380,13 @@ impl Accounts {
                     self.accounts = accounts;
                     self.currency_symbol = currency_symbol.unwrap_or_else(|_| "USD".to_string());
                 }
+                self.projection_horizon_days = config.1.projection_horizon_days;
+                self.loans = store.get_loans().unwrap_or_else(|_| vec![]);
+                self.securities = store.get_securities().unwrap_or_else(|_| vec![]);
+                self.security_prices = store.get_security_prices().unwrap_or_else(|_| vec![]);
+                self.credit_cards = store.get_credit_cards().unwrap_or_else(|_| vec![]);
+                self.account_groups = store.get_account_groups().unwrap_or_else(|_| vec![]);
+                self.group_options = group_options(&self.account_groups);
             }
             AccountsMessage::AddAccountView => {
                 self.add_account_view_visible = true;
@@ -447,13 +1415,53 @@ impl Accounts {
                 }
             }
             AccountsMessage::SubmitNewBankAccount => {
+                let account_type = self.selected_new_account_type();
                 let new_account = NewAccount {
                     name: self.form_new_account_name_value.clone(),
-                    initial_balance: self.new_account_initial_value,
+                    initial_balance: if account_type == AccountType::Loan {
+                        -self.new_account_initial_value.abs()
+                    } else {
+                        self.new_account_initial_value
+                    },
                     account_description: self.new_account_description.clone(),
+                    account_type: account_type.into(),
+                    group_id: None,
+                    exclude_from_total: false,
+                    exclude_from_statistics: false,
                 };
                 let mut store = STORE.lock().unwrap();
-                let _ = store.create_account(&new_account);
+                if account_type == AccountType::Credit {
+                    let new_card = NewCreditCard {
+                        account_id: 0,
+                        closing_day: self.new_card_closing_day.parse::<i32>().unwrap_or(1),
+                        due_day: self.new_card_due_day.parse::<i32>().unwrap_or(1),
+                    };
+                    let _ = store.create_credit_card(&new_account, &new_card);
+                } else if account_type == AccountType::Loan {
+                    let frequency = self
+                        .new_loan_frequency
+                        .and_then(|i| PaymentFrequency::all().get(i))
+                        .copied()
+                        .unwrap_or_default();
+                    let new_loan = NewLoan {
+                        account_id: 0,
+                        principal: self.new_account_initial_value.abs(),
+                        interest_rate: self.new_loan_rate.parse::<f32>().unwrap_or(0.),
+                        term: self.new_loan_term.parse::<i32>().unwrap_or(1).max(1),
+                        payment_frequency: frequency.payments_per_year(),
+                        start_date: NaiveDateTime::from_timestamp(self.new_loan_start_date, 0),
+                    };
+                    let _ = store.create_loan(&new_account, &new_loan);
+                } else {
+                    let _ = store.create_account(&new_account);
+                }
+                self.new_account_type = AccountType::all()
+                    .iter()
+                    .position(|t| *t == AccountType::Checking);
+                self.new_loan_rate = String::default();
+                self.new_loan_term = String::default();
+                self.new_card_closing_day = String::default();
+                self.new_card_due_day = String::default();
                 commands.push(Task::perform(async {}, |_| {
                     AppMessage::Accounts(AccountsMessage::Update)
                 }));
@@ -463,9 +1471,21 @@ impl Accounts {
                 self.editing_account = Some(id);
                 let account = self.accounts.clone().into_iter().find(|a| a.id == id);
                 if let Some(account) = account {
-                    self.edit_account_name = account.name;
+                    self.edit_account_name = account.name.clone();
                     self.edit_account_balance = self.read_account_balance(account.id).to_string();
-                    self.edit_account_description = account.account_description;
+                    self.edit_account_description = account.account_description.clone();
+                    self.edit_account_type = plain_account_types()
+                        .iter()
+                        .position(|t| *t == account.kind());
+                    // the first option is "no group"
+                    self.edit_account_group = Some(
+                        account
+                            .group_id
+                            .and_then(|g| self.account_groups.iter().position(|ag| ag.id == g))
+                            .map_or(0, |i| i + 1),
+                    );
+                    self.edit_exclude_from_total = account.exclude_from_total;
+                    self.edit_exclude_from_statistics = account.exclude_from_statistics;
                 }
             }
             AccountsMessage::CloseEditAccount => self.editing_account = None,
@@ -496,6 +1516,18 @@ impl Accounts {
                             name: self.edit_account_name.clone(),
                             initial_balance: account.initial_balance + difference,
                             account_description: self.edit_account_description.clone(),
+                            account_type: self
+                                .edit_account_type
+                                .and_then(|i| plain_account_types().get(i))
+                                .filter(|_| account.kind().is_plain())
+                                .map_or(account.account_type, |t| (*t).into()),
+                            group_id: self
+                                .edit_account_group
+                                .and_then(|i| i.checked_sub(1))
+                                .and_then(|i| self.account_groups.get(i))
+                                .map(|g| g.id),
+                            exclude_from_total: self.edit_exclude_from_total,
+                            exclude_from_statistics: self.edit_exclude_from_statistics,
                         };
                         let mut store = STORE.lock().unwrap();
                         let _ = store.update_account(&update_account);
@@ -554,8 +1586,26 @@ impl Accounts {
                         } else {
                             Some(self.transfer_description.clone())
                         },
+                        status: TransactionStatus::Uncleared.into(),
+                        to_status: TransactionStatus::Uncleared.into(),
                     };
-                    let _ = store.create_account_transfer(&new_account_transfer);
+                    let to_account_id = to_account.unwrap().id;
+                    match self.loans.iter().find(|l| l.account_id == to_account_id) {
+                        Some(loan) => {
+                            let remaining = -store.get_account_balance(to_account_id).unwrap_or(0.);
+                            let (principal, interest) =
+                                amortization::split_payment(loan, remaining, self.transfer_amount);
+                            let _ = store.create_loan_payment(
+                                &new_account_transfer,
+                                loan.id,
+                                principal,
+                                interest,
+                            );
+                        }
+                        None => {
+                            let _ = store.create_account_transfer(&new_account_transfer);
+                        }
+                    }
                 } //TODO else show error toast
 
                 self.transfer_amount = 0.;
@@ -579,6 +1629,338 @@ impl Accounts {
             AccountsMessage::TransferDescriptionChanged(description) => {
                 self.transfer_description = description;
             }
+            AccountsMessage::NewAccountTypeChanged(selected) => {
+                self.new_account_type = Some(selected);
+            }
+            AccountsMessage::NewLoanRateChanged(rate) => {
+                if rate.parse::<f32>().is_ok() || rate.is_empty() {
+                    self.new_loan_rate = rate;
+                }
+            }
+            AccountsMessage::NewLoanTermChanged(term) => {
+                if term.parse::<u32>().is_ok() || term.is_empty() {
+                    self.new_loan_term = term;
+                }
+            }
+            AccountsMessage::NewLoanFrequencyChanged(selected) => {
+                self.new_loan_frequency = Some(selected);
+            }
+            AccountsMessage::NewLoanStartDateChanged(date) => {
+                self.new_loan_start_date = date;
+            }
+            AccountsMessage::ToggleLoanSchedule(account_id) => {
+                if self.loan_schedule_visible == Some(account_id) {
+                    self.loan_schedule_visible = None;
+                } else {
+                    self.loan_schedule_visible = Some(account_id);
+                }
+            }
+            AccountsMessage::InvestmentFormView(account_id) => {
+                self.investment_form_account = Some(account_id);
+                self.price_form_account = None;
+            }
+            AccountsMessage::InvestmentKindChanged(selected) => {
+                self.investment_kind = Some(selected);
+            }
+            AccountsMessage::InvestmentSymbolChanged(symbol) => {
+                self.investment_symbol = symbol.to_uppercase();
+            }
+            AccountsMessage::InvestmentQuantityChanged(quantity) => {
+                if quantity.parse::<f32>().is_ok() || quantity.is_empty() {
+                    self.investment_quantity = quantity;
+                }
+            }
+            AccountsMessage::InvestmentPriceChanged(price) => {
+                if price.parse::<f32>().is_ok() || price.is_empty() {
+                    self.investment_price = price;
+                }
+            }
+            AccountsMessage::InvestmentAmountChanged(amount) => {
+                if amount.parse::<f32>().is_ok() || amount.is_empty() {
+                    self.investment_amount = amount;
+                }
+            }
+            AccountsMessage::InvestmentDateChanged(date) => {
+                self.investment_date = date;
+            }
+            AccountsMessage::InvestmentSubmit => {
+                let symbol = self.investment_symbol.trim().to_string();
+                if let Some(account_id) =
+                    self.investment_form_account.filter(|_| !symbol.is_empty())
+                {
+                    let kind = self.selected_investment_kind();
+                    let quantity = self.investment_quantity.parse::<f32>().unwrap_or(0.);
+                    let price = self.investment_price.parse::<f32>().unwrap_or(0.);
+                    let amount = if kind == InvestmentKind::Dividend {
+                        self.investment_amount.parse::<f32>().unwrap_or(0.)
+                    } else {
+                        quantity * price
+                    };
+                    let mut store = STORE.lock().unwrap();
+                    match store.get_or_create_security(&symbol) {
+                        Ok(security) => {
+                            let _ =
+                                store.create_investment_transaction(&NewInvestmentTransaction {
+                                    account_id,
+                                    security_id: security.id,
+                                    kind: kind.into(),
+                                    quantity: if kind == InvestmentKind::Dividend {
+                                        0.
+                                    } else {
+                                        quantity
+                                    },
+                                    price: if kind == InvestmentKind::Dividend {
+                                        0.
+                                    } else {
+                                        price
+                                    },
+                                    amount,
+                                    transaction_date: NaiveDateTime::from_timestamp(
+                                        self.investment_date,
+                                        0,
+                                    ),
+                                });
+                        }
+                        Err(e) => log::error!("failed to create security: {}", e),
+                    }
+                    commands.push(Task::perform(async {}, |_| AppMessage::UpdateAllPages));
+                }
+                self.reset_investment_form();
+            }
+            AccountsMessage::InvestmentCancel => {
+                self.reset_investment_form();
+            }
+            AccountsMessage::PriceFormView(account_id) => {
+                self.price_form_account = Some(account_id);
+                self.investment_form_account = None;
+                if self.price_security.is_none() && !self.securities.is_empty() {
+                    self.price_security = Some(0);
+                }
+            }
+            AccountsMessage::PriceSymbolChanged(selected) => {
+                self.price_security = Some(selected);
+            }
+            AccountsMessage::PriceValueChanged(price) => {
+                if price.parse::<f32>().is_ok() || price.is_empty() {
+                    self.price_value = price;
+                }
+            }
+            AccountsMessage::PriceDateChanged(date) => {
+                self.price_date = date;
+            }
+            AccountsMessage::PriceSubmit => {
+                let security = self.price_security.and_then(|i| self.securities.get(i));
+                if let (Some(security), Ok(price)) = (security, self.price_value.parse::<f32>()) {
+                    let mut store = STORE.lock().unwrap();
+                    let _ = store.create_security_prices(&vec![NewSecurityPrice {
+                        security_id: security.id,
+                        price_date: NaiveDateTime::from_timestamp(self.price_date, 0),
+                        price,
+                    }]);
+                    commands.push(Task::perform(async {}, |_| {
+                        AppMessage::Accounts(AccountsMessage::Update)
+                    }));
+                }
+                self.price_form_account = None;
+                self.price_value = String::default();
+            }
+            AccountsMessage::PriceCancel => {
+                self.price_form_account = None;
+                self.price_value = String::default();
+            }
+            AccountsMessage::ImportPrices => {
+                commands.push(Task::perform(async {}, |_| AppMessage::ImportPrices));
+            }
+            AccountsMessage::ReconcileView(account_id) => {
+                self.reconcile_account = Some(account_id);
+                self.reconcile_statement_balance = String::default();
+                self.load_reconcile_items();
+            }
+            AccountsMessage::ReconcileStatement(account_id, end_date, balance) => {
+                self.reconcile_account = Some(account_id);
+                self.reconcile_end_date = end_date;
+                self.reconcile_statement_balance = format!("{:.2}", balance);
+                self.load_reconcile_items();
+            }
+            AccountsMessage::ReconcileEndDateChanged(date) => {
+                self.reconcile_end_date = date;
+                self.load_reconcile_items();
+            }
+            AccountsMessage::ReconcileStatementBalanceChanged(value) => {
+                if value.parse::<f32>().is_ok() || value.is_empty() || value == "-" {
+                    self.reconcile_statement_balance = value;
+                }
+            }
+            AccountsMessage::ToggleTransactionCleared(transaction_id, checked) => {
+                let status = if checked {
+                    TransactionStatus::Cleared
+                } else {
+                    TransactionStatus::Uncleared
+                };
+                let mut store = STORE.lock().unwrap();
+                match store.set_transaction_status(transaction_id, status) {
+                    Ok(_) => {
+                        if let Some(t) = self
+                            .reconcile_transactions
+                            .iter_mut()
+                            .find(|t| t.id == transaction_id)
+                        {
+                            t.status = status.into();
+                        }
+                    }
+                    Err(e) => log::error!("failed to update transaction status: {}", e),
+                }
+            }
+            AccountsMessage::ToggleTransferCleared(transfer_id, checked) => {
+                let Some(account_id) = self.reconcile_account else {
+                    return Task::none();
+                };
+                let status = if checked {
+                    TransactionStatus::Cleared
+                } else {
+                    TransactionStatus::Uncleared
+                };
+                let mut store = STORE.lock().unwrap();
+                match store.set_transfer_status(transfer_id, account_id, status) {
+                    Ok(_) => {
+                        if let Some(t) = self
+                            .reconcile_transfers
+                            .iter_mut()
+                            .find(|t| t.id == transfer_id)
+                        {
+                            t.set_status_for(account_id, status);
+                        }
+                    }
+                    Err(e) => log::error!("failed to update transfer status: {}", e),
+                }
+            }
+            AccountsMessage::ReconcileFinish => {
+                let Some(account_id) = self.reconcile_account else {
+                    return Task::none();
+                };
+                let cleared = |status: TransactionStatus| status == TransactionStatus::Cleared;
+                let transaction_ids: Vec<i32> = self
+                    .reconcile_transactions
+                    .iter()
+                    .filter(|t| cleared(t.status()))
+                    .map(|t| t.id)
+                    .collect();
+                let transfer_ids: Vec<i32> = self
+                    .reconcile_transfers
+                    .iter()
+                    .filter(|t| cleared(t.status_for(account_id)))
+                    .map(|t| t.id)
+                    .collect();
+                let mut store = STORE.lock().unwrap();
+                match store.reconcile(account_id, &transaction_ids, &transfer_ids) {
+                    Ok(_) => {
+                        self.reset_reconcile();
+                        commands.push(Task::perform(async {}, |_| {
+                            AppMessage::Transactions(TransactionMessage::UpdatePage)
+                        }));
+                    }
+                    Err(e) => log::error!("failed to reconcile account: {}", e),
+                }
+            }
+            AccountsMessage::ReconcileCancel => {
+                self.reset_reconcile();
+            }
+            AccountsMessage::NewCardClosingDayChanged(day) => {
+                if day.is_empty() || day.parse::<u32>().is_ok_and(|d| (1..=31).contains(&d)) {
+                    self.new_card_closing_day = day;
+                }
+            }
+            AccountsMessage::NewCardDueDayChanged(day) => {
+                if day.is_empty() || day.parse::<u32>().is_ok_and(|d| (1..=31).contains(&d)) {
+                    self.new_card_due_day = day;
+                }
+            }
+            AccountsMessage::CardPaymentAccountChanged(selected) => {
+                self.card_payment_account = Some(selected);
+            }
+            AccountsMessage::ToggleGroupsView => {
+                self.groups_view_visible = !self.groups_view_visible;
+            }
+            AccountsMessage::NewGroupNameChanged(value) => {
+                self.new_group_name = value;
+            }
+            AccountsMessage::AddGroup => {
+                let mut store = STORE.lock().unwrap();
+                match store.create_account_group(&NewAccountGroup {
+                    group_name: self.new_group_name.trim().to_string(),
+                }) {
+                    Ok(_) => {
+                        self.new_group_name = String::default();
+                        commands.push(Task::perform(async {}, |_| {
+                            AppMessage::Accounts(AccountsMessage::Update)
+                        }));
+                    }
+                    Err(e) => log::error!("failed to create account group: {}", e),
+                }
+            }
+            AccountsMessage::DeleteGroup(group_id) => {
+                let mut store = STORE.lock().unwrap();
+                if let Err(e) = store.delete_account_group(group_id) {
+                    log::error!("failed to delete account group: {}", e);
+                }
+                commands.push(Task::perform(async {}, |_| {
+                    AppMessage::Accounts(AccountsMessage::Update)
+                }));
+            }
+            AccountsMessage::EditAccountType(selected) => {
+                self.edit_account_type = Some(selected);
+            }
+            AccountsMessage::EditAccountGroup(selected) => {
+                self.edit_account_group = Some(selected);
+            }
+            AccountsMessage::EditExcludeFromTotal(value) => {
+                self.edit_exclude_from_total = value;
+            }
+            AccountsMessage::EditExcludeFromStatistics(value) => {
+                self.edit_exclude_from_statistics = value;
+            }
+            AccountsMessage::PayStatement(card_account) => {
+                let card = self
+                    .credit_cards
+                    .iter()
+                    .find(|c| c.account_id == card_account)
+                    .cloned();
+                let from_account = self
+                    .card_payment_account
+                    .and_then(|i| self.accounts.get(i))
+                    .map(|a| a.id)
+                    .filter(|id| *id != card_account);
+                let remaining = card.and_then(|card| {
+                    self.read_statements(&card)
+                        .first()
+                        .map(|(cycle, statement, paid)| (cycle.clone(), statement - paid))
+                });
+                if let (Some(from_account), Some((cycle, amount))) = (from_account, remaining) {
+                    if amount > 0.005 {
+                        let mut store = STORE.lock().unwrap();
+                        let payment = NewAccountTransfer {
+                            from_account,
+                            to_account: card_account,
+                            transfer_date: Utc::now().naive_utc(),
+                            amount: (amount * 100.).round() / 100.,
+                            description: Some(fl!(
+                                "statement-payment",
+                                date = Local
+                                    .from_utc_datetime(&cycle.closing_date)
+                                    .format("%d-%m-%Y")
+                                    .to_string()
+                            )),
+                            status: TransactionStatus::Uncleared.into(),
+                            to_status: TransactionStatus::Uncleared.into(),
+                        };
+                        match store.create_account_transfer(&payment) {
+                            Ok(_) => commands
+                                .push(Task::perform(async {}, |_| AppMessage::UpdateAllPages)),
+                            Err(e) => log::error!("failed to pay statement: {}", e),
+                        }
+                    }
+                }
+            }
         }
         Task::batch(commands)
     }
@@ -592,10 +1974,263 @@ impl Accounts {
         }
     }
 
+    fn projection_end(&self) -> NaiveDateTime {
+        Utc::now().naive_utc() + Duration::days(self.projection_horizon_days as i64)
+    }
+
+    fn read_projected_balance(&self, account_id: i32) -> f32 {
+        let mut store = STORE.lock().unwrap();
+        store
+            .get_account_balance_at(account_id, self.projection_end())
+            .unwrap_or(0.)
+    }
+
+    /// Date and amount of the first projected negative balance within the
+    /// horizon. Debt accounts are negative by design and are not checked.
+    fn read_negative_projection(&self, account: &Account) -> Option<(NaiveDateTime, f32)> {
+        if matches!(account.kind(), AccountType::Loan | AccountType::Credit) {
+            return None;
+        }
+        let mut store = STORE.lock().unwrap();
+        store
+            .get_first_negative_balance(account.id, self.projection_end())
+            .ok()
+            .flatten()
+    }
+
+    /// Accounts split by group, in group name order, with the ungrouped ones last.
+    fn grouped_accounts(&self) -> Vec<(String, Vec<&Account>)> {
+        let mut grouped: Vec<(String, Vec<&Account>)> = self
+            .account_groups
+            .iter()
+            .map(|g| {
+                (
+                    g.group_name.clone(),
+                    self.accounts
+                        .iter()
+                        .filter(|a| a.group_id == Some(g.id))
+                        .collect(),
+                )
+            })
+            .filter(|(_, accounts)| !accounts.is_empty())
+            .collect();
+
+        let ungrouped: Vec<&Account> = self
+            .accounts
+            .iter()
+            .filter(|a| {
+                a.group_id
+                    .is_none_or(|g| !self.account_groups.iter().any(|ag| ag.id == g))
+            })
+            .collect();
+        if !ungrouped.is_empty() {
+            grouped.push((fl!("no-group"), ungrouped));
+        }
+
+        grouped
+    }
+
+    /// Balance of an account as counted in totals, including the market
+    /// value of investments.
+    fn read_total_value(&self, account: &Account) -> f32 {
+        let mut balance = self.read_account_balance(account.id);
+        if account.kind() == AccountType::Investment {
+            balance += self
+                .read_holdings(account.id)
+                .iter()
+                .map(|h| h.market_value())
+                .sum::<f32>();
+        }
+        balance
+    }
+
+    fn calc_subtotal(&self, accounts: &[&Account]) -> f32 {
+        accounts
+            .iter()
+            .filter(|a| !a.exclude_from_total)
+            .map(|a| self.read_total_value(a))
+            .sum()
+    }
+
     fn calc_total_balance(&self) -> f32 {
         self.accounts
             .iter()
-            .map(|a| self.read_account_balance(a.id))
+            .filter(|a| !a.exclude_from_total)
+            .map(|a| self.read_total_value(a))
             .sum()
     }
+
+    fn read_holdings(&self, account_id: i32) -> Vec<Holding> {
+        let mut store = STORE.lock().unwrap();
+        let operations = store
+            .get_investment_transactions(account_id)
+            .unwrap_or_else(|_| vec![]);
+        investments::holdings(
+            &operations,
+            &self.securities,
+            &investments::latest_prices(&self.security_prices),
+        )
+    }
+
+    fn selected_investment_kind(&self) -> InvestmentKind {
+        self.investment_kind
+            .and_then(|i| InvestmentKind::all().get(i))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn reset_investment_form(&mut self) {
+        self.investment_form_account = None;
+        self.investment_kind = Some(0);
+        self.investment_symbol = String::default();
+        self.investment_quantity = String::default();
+        self.investment_price = String::default();
+        self.investment_amount = String::default();
+    }
+
+    fn load_reconcile_items(&mut self) {
+        let Some(account_id) = self.reconcile_account else {
+            return;
+        };
+        let mut store = STORE.lock().unwrap();
+        let until = NaiveDateTime::from_timestamp(self.reconcile_end_date, 0);
+        let (transactions, transfers) = store
+            .get_unreconciled(account_id, until)
+            .unwrap_or_else(|_| (vec![], vec![]));
+        self.reconcile_transactions = transactions;
+        self.reconcile_transfers = transfers;
+        self.reconciled_balance = store
+            .get_reconciled_balance(account_id, until)
+            .unwrap_or(0.);
+    }
+
+    /// Reconciled balance plus the transactions ticked as cleared so far.
+    fn reconcile_cleared_balance(&self, account_id: i32) -> f32 {
+        let interests = STORE
+            .lock()
+            .unwrap()
+            .get_loan_interests(account_id)
+            .unwrap_or_default();
+        let transactions: f32 = self
+            .reconcile_transactions
+            .iter()
+            .filter(|t| t.status() == TransactionStatus::Cleared)
+            .map(|t| if t.is_expense { -t.amount } else { t.amount })
+            .sum();
+        let transfers: f32 = self
+            .reconcile_transfers
+            .iter()
+            .filter(|t| t.status_for(account_id) == TransactionStatus::Cleared)
+            .map(|t| transfer_effect(t, account_id, &interests))
+            .sum();
+        self.reconciled_balance + transactions + transfers
+    }
+
+    fn reset_reconcile(&mut self) {
+        self.reconcile_account = None;
+        self.reconcile_statement_balance = String::default();
+        self.reconcile_transactions = vec![];
+        self.reconcile_transfers = vec![];
+        self.reconciled_balance = 0.;
+    }
+
+    /// Closed statements of a credit card, newest first, with the amount owed
+    /// at closing and the payments received before the following statement.
+    fn read_statements(&self, card: &CreditCard) -> Vec<(StatementCycle, f32, f32)> {
+        let now = Utc::now().naive_utc();
+        let cycles = credit_card::closed_cycles(card, dates::local_today(), 3);
+        let mut store = STORE.lock().unwrap();
+
+        let mut payments_until = now;
+        cycles
+            .into_iter()
+            .map(|cycle| {
+                let owed = -store
+                    .get_account_balance_at(card.account_id, cycle.closing_date)
+                    .unwrap_or(0.);
+                let paid = store
+                    .get_received_transfers(card.account_id, cycle.closing_date, payments_until)
+                    .unwrap_or(0.);
+                payments_until = cycle.closing_date;
+                (cycle, owed.max(0.), paid)
+            })
+            .collect()
+    }
+
+    fn read_loan_payments_count(&self, loan_id: i32) -> i32 {
+        let mut store = STORE.lock().unwrap();
+        store
+            .get_loan_payments(loan_id)
+            .map(|payments| payments.len() as i32)
+            .unwrap_or(0)
+    }
+
+    fn selected_new_account_type(&self) -> AccountType {
+        self.new_account_type
+            .and_then(|i| AccountType::all().get(i))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn is_new_account_loan(&self) -> bool {
+        self.selected_new_account_type() == AccountType::Loan
+    }
+
+    /// Principal and interest parts of the transfer being edited, when its
+    /// destination is a loan account.
+    fn transfer_loan_split(&self) -> Option<(f32, f32)> {
+        let to_account = self.accounts.get(self.transfer_to_account?)?;
+        let loan = self.loans.iter().find(|l| l.account_id == to_account.id)?;
+        let remaining = -self.read_account_balance(to_account.id);
+        Some(amortization::split_payment(
+            loan,
+            remaining,
+            self.transfer_amount,
+        ))
+    }
+}
+
+fn account_type_title(account_type: AccountType) -> String {
+    match account_type {
+        AccountType::Checking => fl!("account-type-checking"),
+        AccountType::Loan => fl!("account-type-loan"),
+        AccountType::Investment => fl!("account-type-investment"),
+        AccountType::Credit => fl!("account-type-credit"),
+        AccountType::Cash => fl!("account-type-cash"),
+        AccountType::Savings => fl!("account-type-savings"),
+        AccountType::Asset => fl!("account-type-asset"),
+    }
+}
+
+fn plain_account_types() -> Vec<AccountType> {
+    AccountType::all()
+        .iter()
+        .filter(|t| t.is_plain())
+        .copied()
+        .collect()
+}
+
+/// Options of the group dropdown, starting with "no group".
+fn group_options(groups: &[AccountGroup]) -> Vec<String> {
+    std::iter::once(fl!("no-group"))
+        .chain(groups.iter().map(|g| g.group_name.clone()))
+        .collect()
+}
+
+fn investment_kind_title(kind: InvestmentKind) -> String {
+    match kind {
+        InvestmentKind::Buy => fl!("buy"),
+        InvestmentKind::Sell => fl!("sell"),
+        InvestmentKind::Dividend => fl!("dividend"),
+    }
+}
+
+fn frequency_title(frequency: PaymentFrequency) -> String {
+    match frequency {
+        PaymentFrequency::Weekly => fl!("frequency-weekly"),
+        PaymentFrequency::Biweekly => fl!("frequency-biweekly"),
+        PaymentFrequency::Monthly => fl!("frequency-monthly"),
+        PaymentFrequency::Quarterly => fl!("frequency-quarterly"),
+        PaymentFrequency::Yearly => fl!("frequency-yearly"),
+    }
 }
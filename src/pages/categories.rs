@@ -491,9 +491,15 @@ impl Categories {
     fn percentabe_by_category(&self, category_id: i32, is_income: bool) -> u32 {
         let mut store = STORE.lock().unwrap();
         let (start_date, end_date) = get_month_date_range(self.view_year, self.view_month);
-        let transactions = store
-            .get_money_transactions_date_range(&start_date, &end_date)
+        let excluded = store
+            .get_statistics_excluded_accounts()
             .unwrap_or_else(|_| vec![]);
+        let transactions: Vec<_> = store
+            .get_money_transactions_date_range(&start_date, &end_date)
+            .unwrap_or_else(|_| vec![])
+            .into_iter()
+            .filter(|t| !excluded.contains(&t.bank_account))
+            .collect();
 
         let category_sum: f32 = transactions
             .iter()
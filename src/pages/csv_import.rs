@@ -0,0 +1,779 @@
+use std::fs;
+
+use chrono::Local;
+use cosmic::{
+    dialog::ashpd::url::Url,
+    iced::{Alignment, Length},
+    widget::{self, Space},
+    Element, Task,
+};
+
+use crate::{
+    app::AppMessage,
+    config::Config,
+    fl,
+    models::{Account, Category, NewMoneyTransaction, SavedCsvProfile},
+    synchronization::csv::{
+        date_format_label, CsvProfile, CsvRow, TextEncoding, DATE_FORMATS, DELIMITERS,
+    },
+    utils::dates,
+    widget::form::{card, labeled},
+    STORE,
+};
+
+/// Number of records shown as a sample of the file.
+const SAMPLE_ROWS: usize = 5;
+/// Number of rows and problems listed in the preview.
+const PREVIEW_ROWS: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CsvColumn {
+    Date,
+    Amount,
+    Debit,
+    Credit,
+    Description,
+    Category,
+}
+
+#[derive(Debug, Clone)]
+pub enum CsvImportMessage {
+    Update,
+    ChooseFile,
+    Load(Url),
+    ProfileSelected(usize),
+    DeleteProfile,
+    EncodingChanged(usize),
+    DelimiterChanged(usize),
+    HeaderToggled(bool),
+    DateFormatChanged(usize),
+    DecimalCommaToggled(bool),
+    ColumnChanged(CsvColumn, usize),
+    AccountChanged(usize),
+    ExpenseCategoryChanged(usize),
+    IncomeCategoryChanged(usize),
+    ProfileNameChanged(String),
+    SaveProfile,
+    Next,
+    Back,
+    Import,
+    Cancel,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum CsvStep {
+    #[default]
+    ChooseFile,
+    Format,
+    Mapping,
+    Preview,
+}
+
+/// Wizard importing the transactions of a bank CSV export into an account:
+/// the format of the file, then the meaning of its columns, then a preview.
+#[derive(Default)]
+pub struct CsvImport {
+    step: CsvStep,
+    file_name: String,
+    bytes: Vec<u8>,
+    records: Vec<Vec<String>>,
+    profile: CsvProfile,
+    rows: Vec<Result<CsvRow, String>>,
+    accounts: Vec<Account>,
+    expense_categories: Vec<Category>,
+    income_categories: Vec<Category>,
+    saved_profiles: Vec<SavedCsvProfile>,
+    /// Index into the profile options, where 0 detects the format again.
+    selected_profile: Option<usize>,
+    profile_name: String,
+    selected_account: Option<usize>,
+    expense_category: Option<usize>,
+    income_category: Option<usize>,
+    currency_symbol: String,
+    profile_options: Vec<String>,
+    encoding_options: Vec<String>,
+    delimiter_options: Vec<String>,
+    date_format_options: Vec<String>,
+    column_options: Vec<String>,
+    account_options: Vec<String>,
+    expense_options: Vec<String>,
+    income_options: Vec<String>,
+}
+
+impl CsvImport {
+    pub fn view<'a>(&'a self) -> Element<'a, CsvImportMessage> {
+        let mut col = widget::column()
+            .push(
+                widget::row()
+                    .push(widget::text::title1(fl!("page-csv-import")).width(Length::Fill))
+                    .push(
+                        widget::button::text(fl!("choose-file"))
+                            .on_press(CsvImportMessage::ChooseFile)
+                            .class(widget::button::ButtonClass::Suggested),
+                    )
+                    .align_y(Alignment::Center),
+            )
+            .push(Space::with_height(10));
+
+        col = match self.step {
+            CsvStep::ChooseFile => col.push(widget::text::text(fl!("csv-import-desc"))),
+            CsvStep::Format => col.push(self.format_view()),
+            CsvStep::Mapping => col.push(self.mapping_view()),
+            CsvStep::Preview => col.push(self.preview_view()),
+        };
+
+        widget::scrollable(widget::container(col).width(Length::Fill)).into()
+    }
+
+    fn format_view<'a>(&'a self) -> Element<'a, CsvImportMessage> {
+        let date_format = DATE_FORMATS
+            .iter()
+            .position(|format| *format == self.profile.date_format);
+        let delimiter = DELIMITERS.iter().position(|d| *d == self.profile.delimiter);
+        let encoding = TextEncoding::ALL
+            .iter()
+            .position(|e| *e == self.profile.encoding);
+
+        let settings = widget::column()
+            .push(widget::text::title4(fl!("csv-format")))
+            .push(Space::with_height(10))
+            .push(widget::text::text(fl!("csv-profile")))
+            .push(
+                widget::row()
+                    .push(widget::dropdown(
+                        &self.profile_options,
+                        self.selected_profile,
+                        CsvImportMessage::ProfileSelected,
+                    ))
+                    .push(Space::with_width(10))
+                    .push_maybe(self.selected_profile.filter(|&i| i > 0).map(|_| {
+                        widget::button::icon(widget::icon::from_name("edit-delete-symbolic"))
+                            .on_press(CsvImportMessage::DeleteProfile)
+                    }))
+                    .align_y(Alignment::Center),
+            )
+            .push(Space::with_height(10))
+            .push(
+                widget::row()
+                    .push(labeled(
+                        fl!("csv-encoding"),
+                        widget::dropdown(
+                            &self.encoding_options,
+                            encoding,
+                            CsvImportMessage::EncodingChanged,
+                        ),
+                    ))
+                    .push(Space::with_width(10))
+                    .push(labeled(
+                        fl!("csv-delimiter"),
+                        widget::dropdown(
+                            &self.delimiter_options,
+                            delimiter,
+                            CsvImportMessage::DelimiterChanged,
+                        ),
+                    ))
+                    .push(Space::with_width(10))
+                    .push(labeled(
+                        fl!("csv-date-format"),
+                        widget::dropdown(
+                            &self.date_format_options,
+                            date_format,
+                            CsvImportMessage::DateFormatChanged,
+                        ),
+                    )),
+            )
+            .push(Space::with_height(10))
+            .push(
+                widget::checkbox(fl!("csv-has-header"), self.profile.has_header)
+                    .on_toggle(CsvImportMessage::HeaderToggled),
+            )
+            .push(Space::with_height(5))
+            .push(
+                widget::checkbox(fl!("csv-decimal-comma"), self.profile.decimal_comma)
+                    .on_toggle(CsvImportMessage::DecimalCommaToggled),
+            )
+            .push(Space::with_height(10))
+            .push(widget::text::title4(fl!(
+                "csv-sample",
+                file = self.file_name.clone()
+            )))
+            .push(Space::with_height(5))
+            .push(self.sample_view())
+            .push(Space::with_height(10))
+            .push(self.navigation(Some(CsvImportMessage::Next), fl!("next")));
+
+        card(settings)
+    }
+
+    fn sample_view<'a>(&'a self) -> Element<'a, CsvImportMessage> {
+        self.records
+            .iter()
+            .take(SAMPLE_ROWS)
+            .enumerate()
+            .fold(widget::column().spacing(5), |table, (index, record)| {
+                let row = record.iter().fold(widget::row().spacing(10), |row, field| {
+                    let text = if index == 0 && self.profile.has_header {
+                        widget::text::heading(field.clone())
+                    } else {
+                        widget::text::text(field.clone())
+                    };
+                    row.push(text.width(Length::Fill))
+                });
+                table.push(row)
+            })
+            .into()
+    }
+
+    fn mapping_view<'a>(&'a self) -> Element<'a, CsvImportMessage> {
+        let column = |label: String, role: CsvColumn, value: Option<usize>| {
+            labeled(
+                label,
+                widget::dropdown(
+                    &self.column_options,
+                    Some(value.map_or(0, |i| i + 1)),
+                    move |i| CsvImportMessage::ColumnChanged(role, i),
+                ),
+            )
+        };
+
+        let mapping = widget::column()
+            .push(widget::text::title4(fl!("csv-mapping")))
+            .push(Space::with_height(5))
+            .push(widget::text::text(fl!("csv-mapping-desc")))
+            .push(Space::with_height(10))
+            .push(
+                widget::row()
+                    .push(column(
+                        fl!("date"),
+                        CsvColumn::Date,
+                        self.profile.date_column,
+                    ))
+                    .push(Space::with_width(10))
+                    .push(column(
+                        fl!("description"),
+                        CsvColumn::Description,
+                        self.profile.description_column,
+                    ))
+                    .push(Space::with_width(10))
+                    .push(column(
+                        fl!("category"),
+                        CsvColumn::Category,
+                        self.profile.category_column,
+                    )),
+            )
+            .push(Space::with_height(10))
+            .push(
+                widget::row()
+                    .push(column(
+                        fl!("amount"),
+                        CsvColumn::Amount,
+                        self.profile.amount_column,
+                    ))
+                    .push(Space::with_width(10))
+                    .push(column(
+                        fl!("csv-debit"),
+                        CsvColumn::Debit,
+                        self.profile.debit_column,
+                    ))
+                    .push(Space::with_width(10))
+                    .push(column(
+                        fl!("csv-credit"),
+                        CsvColumn::Credit,
+                        self.profile.credit_column,
+                    )),
+            )
+            .push(Space::with_height(10))
+            .push(widget::text::title4(fl!("csv-target")))
+            .push(Space::with_height(5))
+            .push(
+                widget::row()
+                    .push(labeled(
+                        fl!("bank-account"),
+                        widget::dropdown(
+                            &self.account_options,
+                            self.selected_account,
+                            CsvImportMessage::AccountChanged,
+                        ),
+                    ))
+                    .push(Space::with_width(10))
+                    .push(labeled(
+                        fl!("csv-expense-category"),
+                        widget::dropdown(
+                            &self.expense_options,
+                            self.expense_category,
+                            CsvImportMessage::ExpenseCategoryChanged,
+                        ),
+                    ))
+                    .push(Space::with_width(10))
+                    .push(labeled(
+                        fl!("csv-income-category"),
+                        widget::dropdown(
+                            &self.income_options,
+                            self.income_category,
+                            CsvImportMessage::IncomeCategoryChanged,
+                        ),
+                    )),
+            )
+            .push(Space::with_height(10))
+            .push(widget::text::title4(fl!("csv-save-profile")))
+            .push(Space::with_height(5))
+            .push(
+                widget::row()
+                    .push(
+                        widget::text_input(fl!("csv-profile-name"), &self.profile_name)
+                            .on_input(CsvImportMessage::ProfileNameChanged),
+                    )
+                    .push(Space::with_width(10))
+                    .push(
+                        widget::button::text(fl!("save"))
+                            .on_press_maybe(
+                                (!self.profile_name.trim().is_empty())
+                                    .then_some(CsvImportMessage::SaveProfile),
+                            )
+                            .class(widget::button::ButtonClass::Standard),
+                    )
+                    .align_y(Alignment::Center),
+            )
+            .push(Space::with_height(10))
+            .push(self.navigation(
+                self.mapping_complete().then_some(CsvImportMessage::Next),
+                fl!("preview"),
+            ));
+
+        card(mapping)
+    }
+
+    fn preview_view<'a>(&'a self) -> Element<'a, CsvImportMessage> {
+        let valid: Vec<&CsvRow> = self.rows.iter().filter_map(|r| r.as_ref().ok()).collect();
+        let problems: Vec<&String> = self.rows.iter().filter_map(|r| r.as_ref().err()).collect();
+
+        let mut preview = widget::column()
+            .push(widget::text::title4(fl!("csv-preview")))
+            .push(Space::with_height(5))
+            .push(widget::text::text(fl!(
+                "csv-preview-counts",
+                count = valid.len(),
+                account = self
+                    .selected_account
+                    .and_then(|i| self.accounts.get(i))
+                    .map(|a| a.name.clone())
+                    .unwrap_or_default()
+            )));
+        let first = valid.iter().map(|r| r.date).min();
+        let last = valid.iter().map(|r| r.date).max();
+        if let (Some(first), Some(last)) = (first, last) {
+            preview = preview.push(widget::text::text(fl!(
+                "import-preview-dates",
+                first = first.format("%d-%m-%Y").to_string(),
+                last = last.format("%d-%m-%Y").to_string()
+            )));
+        }
+        if !problems.is_empty() {
+            preview = preview
+                .push(Space::with_height(10))
+                .push(widget::text::title4(fl!(
+                    "csv-preview-problems",
+                    count = problems.len()
+                )));
+            for problem in problems.iter().take(PREVIEW_ROWS) {
+                preview = preview.push(widget::text::text(problem.as_str()));
+            }
+        }
+
+        preview = preview.push(Space::with_height(10)).push(self.navigation(
+            (!valid.is_empty()).then_some(CsvImportMessage::Import),
+            fl!("import"),
+        ));
+        preview = preview.push(Space::with_height(10));
+        for row in valid.iter().take(PREVIEW_ROWS) {
+            preview = preview.push(
+                widget::row()
+                    .push(
+                        widget::text::text(row.date.format("%d-%m-%Y").to_string())
+                            .width(Length::Fixed(100.)),
+                    )
+                    .push(widget::text::text(row.description.clone()).width(Length::Fill))
+                    .push(
+                        widget::text::text(
+                            self.category_for(row)
+                                .map(|c| c.name.clone())
+                                .unwrap_or_default(),
+                        )
+                        .width(Length::Fixed(140.)),
+                    )
+                    .push(
+                        widget::text::text(format!("{:.2} {}", row.amount, self.currency_symbol))
+                            .width(Length::Fixed(120.)),
+                    )
+                    .spacing(10),
+            );
+        }
+        if valid.len() > PREVIEW_ROWS {
+            preview = preview.push(widget::text::text(fl!(
+                "csv-preview-more",
+                count = valid.len() - PREVIEW_ROWS
+            )));
+        }
+
+        card(preview)
+    }
+
+    /// Back and cancel buttons, with a button moving forward when `next`
+    /// is available.
+    fn navigation<'a>(
+        &'a self,
+        next: Option<CsvImportMessage>,
+        next_label: String,
+    ) -> Element<'a, CsvImportMessage> {
+        widget::row()
+            .push_maybe((self.step != CsvStep::Format).then(|| {
+                widget::button::text(fl!("back"))
+                    .on_press(CsvImportMessage::Back)
+                    .class(widget::button::ButtonClass::Standard)
+            }))
+            .push(
+                widget::button::text(fl!("cancel"))
+                    .on_press(CsvImportMessage::Cancel)
+                    .class(widget::button::ButtonClass::Destructive),
+            )
+            .push(Space::with_width(Length::Fill))
+            .push(
+                widget::button::text(next_label)
+                    .on_press_maybe(next)
+                    .class(widget::button::ButtonClass::Suggested),
+            )
+            .spacing(10)
+            .into()
+    }
+
+    fn mapping_complete(&self) -> bool {
+        self.profile.date_column.is_some()
+            && (self.profile.amount_column.is_some()
+                || self.profile.debit_column.is_some()
+                || self.profile.credit_column.is_some())
+            && self.selected_account.is_some()
+            && self.expense_category.is_some()
+            && self.income_category.is_some()
+    }
+
+    /// The category named in the row, if it exists with the right type, or
+    /// else the default one for expenses or income.
+    fn category_for(&self, row: &CsvRow) -> Option<&Category> {
+        let (categories, default) = if row.amount < 0. {
+            (&self.expense_categories, self.expense_category)
+        } else {
+            (&self.income_categories, self.income_category)
+        };
+        row.category
+            .as_ref()
+            .and_then(|name| {
+                categories
+                    .iter()
+                    .find(|c| c.name.to_lowercase() == name.to_lowercase())
+            })
+            .or_else(|| default.and_then(|i| categories.get(i)))
+    }
+
+    /// Reads the file again with the current format and refreshes the
+    /// column names.
+    fn reload_records(&mut self) {
+        self.records = self.profile.read_records(&self.bytes);
+        let header = self
+            .records
+            .first()
+            .filter(|_| self.profile.has_header)
+            .cloned();
+        let columns = self.records.iter().map(Vec::len).max().unwrap_or(0);
+        self.column_options = std::iter::once(fl!("csv-no-column"))
+            .chain((0..columns).map(|i| {
+                match header
+                    .as_ref()
+                    .and_then(|h| h.get(i))
+                    .filter(|h| !h.is_empty())
+                {
+                    Some(name) => name.clone(),
+                    None => fl!("csv-column", number = i + 1),
+                }
+            }))
+            .collect();
+    }
+
+    fn apply_profile(&mut self, profile: CsvProfile) {
+        if let Some(account_id) = profile.account_id {
+            self.selected_account = self.accounts.iter().position(|a| a.id == account_id);
+        }
+        self.profile = profile;
+        self.reload_records();
+    }
+
+    fn reset(&mut self) {
+        self.step = CsvStep::ChooseFile;
+        self.file_name = String::new();
+        self.bytes = vec![];
+        self.records = vec![];
+        self.rows = vec![];
+        self.profile = CsvProfile::default();
+        self.selected_profile = Some(0);
+        self.profile_name = String::new();
+    }
+
+    pub fn update(&mut self, message: CsvImportMessage) -> Task<AppMessage> {
+        let mut commands = Vec::new();
+        match message {
+            CsvImportMessage::Update => {
+                let mut store = STORE.lock().unwrap();
+                let config = Config::load();
+                self.accounts = store.get_accounts().unwrap_or_else(|_| vec![]);
+                (self.income_categories, self.expense_categories) = store
+                    .get_categories()
+                    .unwrap_or_else(|_| vec![])
+                    .into_iter()
+                    .partition(|c: &Category| c.is_income);
+                self.saved_profiles = store.get_csv_profiles().unwrap_or_else(|e| {
+                    log::error!("error loading csv profiles: {}", e);
+                    vec![]
+                });
+                self.currency_symbol = store
+                    .get_currency_symbol_by_id(config.1.currency_id)
+                    .unwrap_or_else(|_| "USD".to_string());
+
+                self.profile_options = std::iter::once(fl!("csv-detect"))
+                    .chain(self.saved_profiles.iter().map(|p| p.profile_name.clone()))
+                    .collect();
+                self.encoding_options = TextEncoding::ALL
+                    .iter()
+                    .map(|e| e.name().to_string())
+                    .collect();
+                self.delimiter_options = DELIMITERS
+                    .iter()
+                    .map(|d| match d {
+                        '\t' => fl!("csv-tab"),
+                        d => d.to_string(),
+                    })
+                    .collect();
+                self.date_format_options = DATE_FORMATS
+                    .iter()
+                    .map(|format| date_format_label(format))
+                    .collect();
+                self.account_options = self.accounts.iter().map(|a| a.name.clone()).collect();
+                self.expense_options = self
+                    .expense_categories
+                    .iter()
+                    .map(|c| c.name.clone())
+                    .collect();
+                self.income_options = self
+                    .income_categories
+                    .iter()
+                    .map(|c| c.name.clone())
+                    .collect();
+                self.selected_profile = self
+                    .saved_profiles
+                    .iter()
+                    .position(|p| p.profile_name == self.profile_name.trim())
+                    .map_or(Some(0), |i| Some(i + 1));
+                if self.expense_category.is_none() && !self.expense_categories.is_empty() {
+                    self.expense_category = Some(0);
+                }
+                if self.income_category.is_none() && !self.income_categories.is_empty() {
+                    self.income_category = Some(0);
+                }
+            }
+            CsvImportMessage::ChooseFile => {
+                commands.push(Task::perform(async {}, |_| AppMessage::ImportCsv));
+            }
+            CsvImportMessage::Load(url) => {
+                let bytes = url
+                    .to_file_path()
+                    .map_err(|_| "Failed to read the selected file".to_string())
+                    .and_then(|path| fs::read(path).map_err(|e| e.to_string()));
+                match bytes {
+                    Ok(bytes) => {
+                        self.reset();
+                        self.file_name = url
+                            .path_segments()
+                            .and_then(|mut segments| segments.next_back())
+                            .unwrap_or_default()
+                            .to_string();
+                        self.bytes = bytes;
+                        // A saved profile reading every line of the file is
+                        // most likely the one of its bank.
+                        let saved = self.saved_profiles.iter().enumerate().find_map(|(i, p)| {
+                            let profile = p.profile()?;
+                            let rows = profile.parse_rows(&profile.read_records(&self.bytes));
+                            (!rows.is_empty() && rows.iter().all(Result::is_ok))
+                                .then_some((i, profile))
+                        });
+                        match saved {
+                            Some((index, profile)) => {
+                                self.selected_profile = Some(index + 1);
+                                self.profile_name = self.saved_profiles[index].profile_name.clone();
+                                self.apply_profile(profile);
+                            }
+                            None => self.apply_profile(CsvProfile::detect(&self.bytes)),
+                        }
+                        self.step = CsvStep::Format;
+                    }
+                    Err(e) => {
+                        log::error!("error reading csv file: {}", e);
+                        commands.push(Task::perform(async {}, |_| {
+                            AppMessage::ShowToast(fl!("csv-read-error"))
+                        }));
+                    }
+                }
+            }
+            CsvImportMessage::ProfileSelected(index) => {
+                self.selected_profile = Some(index);
+                let saved = index
+                    .checked_sub(1)
+                    .and_then(|i| self.saved_profiles.get(i));
+                match saved.and_then(|p| p.profile().map(|profile| (p, profile))) {
+                    Some((saved, profile)) => {
+                        self.profile_name = saved.profile_name.clone();
+                        self.apply_profile(profile);
+                    }
+                    None => {
+                        self.profile_name = String::new();
+                        self.apply_profile(CsvProfile::detect(&self.bytes));
+                    }
+                }
+            }
+            CsvImportMessage::DeleteProfile => {
+                let saved = self
+                    .selected_profile
+                    .and_then(|index| index.checked_sub(1))
+                    .and_then(|i| self.saved_profiles.get(i));
+                if let Some(saved) = saved {
+                    let mut store = STORE.lock().unwrap();
+                    if let Err(e) = store.delete_csv_profile(saved.id) {
+                        log::error!("error deleting csv profile: {}", e);
+                    }
+                    self.selected_profile = Some(0);
+                    self.profile_name = String::new();
+                    commands.push(Task::perform(async {}, |_| {
+                        AppMessage::CsvImport(CsvImportMessage::Update)
+                    }));
+                }
+            }
+            CsvImportMessage::EncodingChanged(index) => {
+                if let Some(encoding) = TextEncoding::ALL.get(index) {
+                    self.profile.encoding = *encoding;
+                    self.reload_records();
+                }
+            }
+            CsvImportMessage::DelimiterChanged(index) => {
+                if let Some(delimiter) = DELIMITERS.get(index) {
+                    self.profile.delimiter = *delimiter;
+                    self.reload_records();
+                }
+            }
+            CsvImportMessage::HeaderToggled(value) => {
+                self.profile.has_header = value;
+                self.reload_records();
+            }
+            CsvImportMessage::DateFormatChanged(index) => {
+                if let Some(format) = DATE_FORMATS.get(index) {
+                    self.profile.date_format = format.to_string();
+                }
+            }
+            CsvImportMessage::DecimalCommaToggled(value) => self.profile.decimal_comma = value,
+            CsvImportMessage::ColumnChanged(column, index) => {
+                let value = index.checked_sub(1);
+                match column {
+                    CsvColumn::Date => self.profile.date_column = value,
+                    CsvColumn::Amount => self.profile.amount_column = value,
+                    CsvColumn::Debit => self.profile.debit_column = value,
+                    CsvColumn::Credit => self.profile.credit_column = value,
+                    CsvColumn::Description => self.profile.description_column = value,
+                    CsvColumn::Category => self.profile.category_column = value,
+                }
+            }
+            CsvImportMessage::AccountChanged(index) => self.selected_account = Some(index),
+            CsvImportMessage::ExpenseCategoryChanged(index) => self.expense_category = Some(index),
+            CsvImportMessage::IncomeCategoryChanged(index) => self.income_category = Some(index),
+            CsvImportMessage::ProfileNameChanged(value) => self.profile_name = value,
+            CsvImportMessage::SaveProfile => {
+                let mut profile = self.profile.clone();
+                profile.account_id = self
+                    .selected_account
+                    .and_then(|i| self.accounts.get(i))
+                    .map(|a| a.id);
+                let mut store = STORE.lock().unwrap();
+                match store.save_csv_profile(self.profile_name.trim(), &profile) {
+                    Ok(_) => {
+                        commands.push(Task::perform(async {}, |_| {
+                            AppMessage::ShowToast(fl!("csv-profile-saved"))
+                        }));
+                        commands.push(Task::perform(async {}, |_| {
+                            AppMessage::CsvImport(CsvImportMessage::Update)
+                        }));
+                    }
+                    Err(e) => {
+                        log::error!("error saving csv profile: {}", e);
+                        commands.push(Task::perform(async {}, |_| {
+                            AppMessage::ShowToast(fl!("csv-profile-save-error"))
+                        }));
+                    }
+                }
+            }
+            CsvImportMessage::Next => match self.step {
+                CsvStep::Format => self.step = CsvStep::Mapping,
+                CsvStep::Mapping => {
+                    self.rows = self.profile.parse_rows(&self.records);
+                    self.step = CsvStep::Preview;
+                }
+                _ => {}
+            },
+            CsvImportMessage::Back => match self.step {
+                CsvStep::Mapping => self.step = CsvStep::Format,
+                CsvStep::Preview => self.step = CsvStep::Mapping,
+                _ => {}
+            },
+            CsvImportMessage::Import => {
+                let Some(account) = self.selected_account.and_then(|i| self.accounts.get(i)) else {
+                    return Task::none();
+                };
+                let new_transactions: Vec<NewMoneyTransaction> = self
+                    .rows
+                    .iter()
+                    .filter_map(|row| row.as_ref().ok())
+                    .filter_map(|row| {
+                        let category = self.category_for(row)?;
+                        Some(NewMoneyTransaction {
+                            bank_account: account.id,
+                            transaction_category: category.id,
+                            description: row.description.clone(),
+                            amount: row.amount.abs(),
+                            transaction_date: dates::day_start_utc(&Local, row.date),
+                            is_expense: row.amount < 0.,
+                            status: 0,
+                            payee: None,
+                            external_id: None,
+                        })
+                    })
+                    .collect();
+                let count = new_transactions.len();
+                let mut store = STORE.lock().unwrap();
+                match store.create_money_transactions_with_rules(new_transactions) {
+                    Ok(_) => {
+                        log::info!("{} transactions imported from csv", count);
+                        self.reset();
+                        commands.push(Task::perform(async {}, move |_| {
+                            AppMessage::ShowToast(fl!("csv-import-success", count = count))
+                        }));
+                        commands.push(Task::perform(async {}, |_| AppMessage::UpdateAllPages));
+                        commands.push(Task::perform(async {}, |_| AppMessage::CheckDuplicates));
+                    }
+                    Err(e) => {
+                        log::error!("error importing csv: {}", e);
+                        let error = e.to_string();
+                        commands.push(Task::perform(async {}, move |_| {
+                            AppMessage::ShowToast(fl!("import-error-detail", error = error))
+                        }));
+                    }
+                }
+            }
+            CsvImportMessage::Cancel => self.reset(),
+        }
+        Task::batch(commands)
+    }
+}
@@ -0,0 +1,143 @@
+use cosmic::{
+    iced::{Alignment, Length, Padding},
+    widget::{self, Space},
+    Element, Task,
+};
+
+use crate::{
+    app::AppMessage,
+    config::Config,
+    fl,
+    models::{Account, Category, MoneyTransaction},
+    widget::transaction_card::transaction_card,
+    STORE,
+};
+
+#[derive(Debug, Clone)]
+pub enum DuplicatesMessage {
+    Update,
+    /// Keeps the first transaction and merges the second into it.
+    Merge(i32, i32),
+    KeepBoth(i32, i32),
+}
+
+/// Review screen for transactions that look like they were entered twice.
+#[derive(Default)]
+pub struct Duplicates {
+    pairs: Vec<(MoneyTransaction, MoneyTransaction)>,
+    accounts: Vec<Account>,
+    categories: Vec<Category>,
+    currency_symbol: String,
+}
+
+impl Duplicates {
+    pub fn count(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn view<'a>(&'a self) -> Element<'a, DuplicatesMessage> {
+        let mut col = widget::column()
+            .push(widget::text::title1(fl!("page-duplicates")))
+            .push(Space::with_height(10))
+            .push(widget::text::text(fl!("duplicates-desc")))
+            .push(Space::with_height(10));
+
+        if self.pairs.is_empty() {
+            col = col.push(widget::text::text(fl!("no-duplicates")));
+        }
+
+        for (first, second) in &self.pairs {
+            col = col
+                .push(
+                    widget::container(
+                        widget::column()
+                            .push(transaction_card(
+                                first,
+                                &self.categories,
+                                &self.accounts,
+                                &self.currency_symbol,
+                            ))
+                            .push(Space::with_height(5))
+                            .push(transaction_card(
+                                second,
+                                &self.categories,
+                                &self.accounts,
+                                &self.currency_symbol,
+                            ))
+                            .push(Space::with_height(10))
+                            .push(
+                                widget::row()
+                                    .push(
+                                        widget::button::text(fl!("keep-first"))
+                                            .on_press(DuplicatesMessage::Merge(first.id, second.id))
+                                            .class(widget::button::ButtonClass::Suggested),
+                                    )
+                                    .push(Space::with_width(10))
+                                    .push(
+                                        widget::button::text(fl!("keep-second"))
+                                            .on_press(DuplicatesMessage::Merge(second.id, first.id))
+                                            .class(widget::button::ButtonClass::Suggested),
+                                    )
+                                    .push(Space::with_width(10))
+                                    .push(
+                                        widget::button::text(fl!("keep-both"))
+                                            .on_press(DuplicatesMessage::KeepBoth(
+                                                first.id, second.id,
+                                            ))
+                                            .class(widget::button::ButtonClass::Standard),
+                                    )
+                                    .align_y(Alignment::Center),
+                            ),
+                    )
+                    .width(Length::Fill)
+                    .padding(Padding::new(10.)),
+                )
+                .push(Space::with_height(10));
+        }
+
+        widget::scrollable(widget::container(col).width(Length::Fill)).into()
+    }
+
+    pub fn update(&mut self, message: DuplicatesMessage) -> Task<AppMessage> {
+        let mut commands = Vec::new();
+        match message {
+            DuplicatesMessage::Update => {
+                let mut store = STORE.lock().unwrap();
+                let config = Config::load();
+                self.pairs = store
+                    .find_duplicate_transactions(config.1.duplicate_window_days)
+                    .unwrap_or_else(|e| {
+                        log::error!("error looking for duplicates: {}", e);
+                        vec![]
+                    });
+                self.accounts = store.get_accounts().unwrap_or_else(|_| vec![]);
+                self.categories = store.get_categories().unwrap_or_else(|_| vec![]);
+                self.currency_symbol = store
+                    .get_currency_symbol_by_id(config.1.currency_id)
+                    .unwrap_or_else(|_| "USD".to_string());
+            }
+            DuplicatesMessage::Merge(keep, remove) => {
+                let mut store = STORE.lock().unwrap();
+                match store.merge_duplicate(keep, remove) {
+                    Ok(_) => {
+                        self.pairs.retain(|(a, b)| ![a.id, b.id].contains(&remove));
+                        commands.push(Task::perform(async {}, |_| AppMessage::UpdateAllPages));
+                    }
+                    Err(e) => {
+                        log::error!("error merging duplicates: {}", e);
+                        commands.push(Task::perform(async {}, |_| {
+                            AppMessage::ShowToast(fl!("merge-duplicate-error"))
+                        }));
+                    }
+                }
+            }
+            DuplicatesMessage::KeepBoth(first, second) => {
+                let mut store = STORE.lock().unwrap();
+                let _ = store.dismiss_duplicate(first, second);
+                self.pairs
+                    .retain(|(a, b)| !(a.id == first && b.id == second));
+            }
+        }
+        Task::batch(commands)
+    }
+}
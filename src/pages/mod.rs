@@ -3,4 +3,10 @@ pub mod settings;
 pub mod categories;
 pub mod transactions;
 pub mod welcome;
-pub mod statistics;
\ No newline at end of file
+pub mod statistics;
+pub mod search;
+pub mod saved_filter;
+pub mod duplicates;
+pub mod rules;
+pub mod csv_import;
+pub mod statement_import;
\ No newline at end of file
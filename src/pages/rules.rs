@@ -0,0 +1,547 @@
+use cosmic::{
+    iced::{Alignment, Length, Padding},
+    widget::{self, Space},
+    Element, Task,
+};
+
+use crate::{
+    app::AppMessage,
+    config::Config,
+    fl,
+    models::{Account, Category, MoneyTransaction, NewRule, Rule, Tag},
+    utils::rules::validate_pattern,
+    widget::transaction_card::transaction_card,
+    STORE,
+};
+
+#[derive(Debug, Clone)]
+pub enum RulesMessage {
+    Update,
+    AddRule,
+    CancelRule,
+    SubmitRule,
+    NameChanged(String),
+    MatchTextChanged(String),
+    MatchRegexToggled(bool),
+    MinAmountChanged(String),
+    MaxAmountChanged(String),
+    AccountChanged(usize),
+    CategoryChanged(usize),
+    PayeeChanged(String),
+    TagChanged(String),
+    NoteChanged(String),
+    Delete(i32),
+    Preview(i32),
+    ClosePreview,
+    Apply,
+}
+
+/// User-defined rules that categorize transactions as they are entered or
+/// imported, and can be applied again to existing ones.
+#[derive(Default)]
+pub struct Rules {
+    rules: Vec<Rule>,
+    accounts: Vec<Account>,
+    categories: Vec<Category>,
+    tags: Vec<Tag>,
+    currency_symbol: String,
+    add_rule_view: bool,
+    form_name: String,
+    form_match_text: String,
+    form_match_regex: bool,
+    form_min_amount: String,
+    form_max_amount: String,
+    /// Index into the account options, where 0 is any account.
+    form_account: Option<usize>,
+    /// Index into the category options, where 0 keeps the category.
+    form_category: Option<usize>,
+    form_payee: String,
+    form_tag: String,
+    form_note: String,
+    form_error: Option<String>,
+    account_options: Vec<String>,
+    category_options: Vec<String>,
+    /// Rule being previewed and the transactions it would change.
+    preview: Option<(i32, Vec<MoneyTransaction>)>,
+}
+
+impl Rules {
+    pub fn view<'a>(&'a self) -> Element<'a, RulesMessage> {
+        let mut col = widget::column()
+            .push(
+                widget::row()
+                    .push(widget::text::title1(fl!("page-rules")).width(Length::Fill))
+                    .push_maybe(if self.add_rule_view || self.preview.is_some() {
+                        None
+                    } else {
+                        Some(
+                            widget::button::text(fl!("add-rule"))
+                                .on_press(RulesMessage::AddRule)
+                                .class(widget::button::ButtonClass::Suggested),
+                        )
+                    })
+                    .align_y(Alignment::Center),
+            )
+            .push(Space::with_height(10))
+            .push(widget::text::text(fl!("rules-desc")))
+            .push(Space::with_height(10));
+
+        if let Some((rule_id, transactions)) = &self.preview {
+            col = col.push(self.preview_view(*rule_id, transactions));
+        } else if self.add_rule_view {
+            col = col.push(self.add_rule_view());
+        } else {
+            if self.rules.is_empty() {
+                col = col.push(widget::text::text(fl!("no-rules")));
+            }
+            for rule in &self.rules {
+                col = col.push(self.rule_card(rule)).push(Space::with_height(10));
+            }
+        }
+
+        widget::scrollable(widget::container(col).width(Length::Fill)).into()
+    }
+
+    fn rule_card<'a>(&'a self, rule: &'a Rule) -> Element<'a, RulesMessage> {
+        widget::container(
+            widget::row()
+                .push(
+                    widget::column()
+                        .push(widget::text::title4(rule.rule_name.clone()))
+                        .push(Space::with_height(5))
+                        .push(widget::text::text(format!(
+                            "{}: {}",
+                            fl!("rule-when"),
+                            self.conditions_text(rule)
+                        )))
+                        .push(widget::text::text(format!(
+                            "{}: {}",
+                            fl!("rule-then"),
+                            self.actions_text(rule)
+                        )))
+                        .width(Length::Fill),
+                )
+                .push(
+                    widget::button::text(fl!("preview"))
+                        .on_press(RulesMessage::Preview(rule.id))
+                        .class(widget::button::ButtonClass::Standard),
+                )
+                .push(Space::with_width(10))
+                .push(
+                    widget::button::icon(widget::icon::from_name("edit-delete-symbolic"))
+                        .on_press(RulesMessage::Delete(rule.id)),
+                )
+                .align_y(Alignment::Center),
+        )
+        .width(Length::Fill)
+        .padding(Padding::new(10.))
+        .class(cosmic::theme::Container::Card)
+        .into()
+    }
+
+    fn preview_view<'a>(
+        &'a self,
+        rule_id: i32,
+        transactions: &'a [MoneyTransaction],
+    ) -> Element<'a, RulesMessage> {
+        let rule_name = self
+            .rules
+            .iter()
+            .find(|r| r.id == rule_id)
+            .map(|r| r.rule_name.clone())
+            .unwrap_or_default();
+        let mut col = widget::column()
+            .push(widget::text::title4(rule_name))
+            .push(Space::with_height(5))
+            .push(widget::text::text(fl!(
+                "rule-preview",
+                count = transactions.len()
+            )))
+            .push(Space::with_height(10))
+            .push(
+                widget::row()
+                    .push(
+                        widget::button::text(fl!("apply-rule"))
+                            .on_press_maybe(
+                                (!transactions.is_empty()).then_some(RulesMessage::Apply),
+                            )
+                            .class(widget::button::ButtonClass::Suggested),
+                    )
+                    .push(Space::with_width(10))
+                    .push(
+                        widget::button::text(fl!("cancel"))
+                            .on_press(RulesMessage::ClosePreview)
+                            .class(widget::button::ButtonClass::Standard),
+                    ),
+            )
+            .push(Space::with_height(10));
+
+        for t in transactions {
+            col = col
+                .push(transaction_card(
+                    t,
+                    &self.categories,
+                    &self.accounts,
+                    &self.currency_symbol,
+                ))
+                .push(Space::with_height(10));
+        }
+
+        col.into()
+    }
+
+    fn add_rule_view<'a>(&'a self) -> Element<'a, RulesMessage> {
+        widget::container(
+            widget::column()
+                .push(widget::text::title4(fl!("new-rule")))
+                .push(Space::with_height(10))
+                .push(widget::text::text(fl!("rule-name")))
+                .push(Space::with_height(3))
+                .push(
+                    widget::text_input(fl!("rule-name"), &self.form_name)
+                        .on_input(RulesMessage::NameChanged),
+                )
+                .push(Space::with_height(10))
+                .push(widget::text::title4(fl!("rule-when")))
+                .push(Space::with_height(5))
+                .push(widget::text::text(fl!("description-contains")))
+                .push(Space::with_height(3))
+                .push(
+                    widget::text_input(fl!("description-contains"), &self.form_match_text)
+                        .on_input(RulesMessage::MatchTextChanged),
+                )
+                .push(Space::with_height(5))
+                .push(
+                    widget::checkbox(fl!("regular-expression"), self.form_match_regex)
+                        .on_toggle(RulesMessage::MatchRegexToggled),
+                )
+                .push(Space::with_height(10))
+                .push(
+                    widget::row()
+                        .push(
+                            widget::column()
+                                .push(widget::text::text(fl!("min-amount")))
+                                .push(
+                                    widget::text_input("0", &self.form_min_amount)
+                                        .on_input(RulesMessage::MinAmountChanged),
+                                )
+                                .width(Length::Fill),
+                        )
+                        .push(Space::with_width(10))
+                        .push(
+                            widget::column()
+                                .push(widget::text::text(fl!("max-amount")))
+                                .push(
+                                    widget::text_input("0", &self.form_max_amount)
+                                        .on_input(RulesMessage::MaxAmountChanged),
+                                )
+                                .width(Length::Fill),
+                        ),
+                )
+                .push(Space::with_height(10))
+                .push(widget::text::text(fl!("bank-account")))
+                .push(widget::dropdown(
+                    &self.account_options,
+                    self.form_account,
+                    RulesMessage::AccountChanged,
+                ))
+                .push(Space::with_height(10))
+                .push(widget::text::title4(fl!("rule-then")))
+                .push(Space::with_height(5))
+                .push(widget::text::text(fl!("category")))
+                .push(widget::dropdown(
+                    &self.category_options,
+                    self.form_category,
+                    RulesMessage::CategoryChanged,
+                ))
+                .push(Space::with_height(10))
+                .push(widget::text::text(fl!("payee")))
+                .push(Space::with_height(3))
+                .push(
+                    widget::text_input(fl!("payee"), &self.form_payee)
+                        .on_input(RulesMessage::PayeeChanged),
+                )
+                .push(Space::with_height(10))
+                .push(widget::text::text(fl!("tag")))
+                .push(Space::with_height(3))
+                .push(
+                    widget::text_input(fl!("tag"), &self.form_tag)
+                        .on_input(RulesMessage::TagChanged),
+                )
+                .push(Space::with_height(10))
+                .push(widget::text::text(fl!("note")))
+                .push(Space::with_height(3))
+                .push(
+                    widget::text_input(fl!("note"), &self.form_note)
+                        .on_input(RulesMessage::NoteChanged),
+                )
+                .push_maybe(self.form_error.as_ref().map(|error| {
+                    widget::column()
+                        .push(Space::with_height(10))
+                        .push(widget::text::text(error.clone()))
+                }))
+                .push(Space::with_height(10))
+                .push(
+                    widget::row()
+                        .push(
+                            widget::button::text(fl!("add-rule"))
+                                .on_press_maybe(
+                                    (!self.form_name.trim().is_empty())
+                                        .then_some(RulesMessage::SubmitRule),
+                                )
+                                .class(widget::button::ButtonClass::Suggested),
+                        )
+                        .push(Space::with_width(10))
+                        .push(
+                            widget::button::text(fl!("cancel"))
+                                .on_press(RulesMessage::CancelRule)
+                                .class(widget::button::ButtonClass::Destructive),
+                        ),
+                )
+                .width(Length::Fill),
+        )
+        .padding(10)
+        .width(Length::Fill)
+        .class(cosmic::theme::Container::Card)
+        .into()
+    }
+
+    fn conditions_text(&self, rule: &Rule) -> String {
+        let mut conditions = vec![];
+        if let Some(text) = &rule.match_text {
+            conditions.push(if rule.match_regex {
+                format!("{} /{}/", fl!("description"), text)
+            } else {
+                format!("{} \"{}\"", fl!("description-contains"), text)
+            });
+        }
+        if let Some(min) = rule.match_min_amount {
+            conditions.push(format!(
+                "{} {} {}",
+                fl!("min-amount"),
+                min,
+                self.currency_symbol
+            ));
+        }
+        if let Some(max) = rule.match_max_amount {
+            conditions.push(format!(
+                "{} {} {}",
+                fl!("max-amount"),
+                max,
+                self.currency_symbol
+            ));
+        }
+        if let Some(account_id) = rule.match_account {
+            conditions.push(format!(
+                "{} {}",
+                fl!("bank-account"),
+                self.accounts
+                    .iter()
+                    .find(|a| a.id == account_id)
+                    .map(|a| a.name.clone())
+                    .unwrap_or_else(|| fl!("not-found"))
+            ));
+        }
+        if conditions.is_empty() {
+            fl!("any-transaction")
+        } else {
+            conditions.join(", ")
+        }
+    }
+
+    fn actions_text(&self, rule: &Rule) -> String {
+        let mut actions = vec![];
+        if let Some(category_id) = rule.set_category {
+            actions.push(format!(
+                "{} {}",
+                fl!("category"),
+                self.categories
+                    .iter()
+                    .find(|c| c.id == category_id)
+                    .map(|c| c.name.clone())
+                    .unwrap_or_else(|| fl!("not-found"))
+            ));
+        }
+        if let Some(payee) = &rule.set_payee {
+            actions.push(format!("{} {}", fl!("payee"), payee));
+        }
+        if let Some(tag_id) = rule.set_tag {
+            actions.push(format!(
+                "{} {}",
+                fl!("tag"),
+                self.tags
+                    .iter()
+                    .find(|t| t.id == tag_id)
+                    .map(|t| t.tag_name.clone())
+                    .unwrap_or_else(|| fl!("not-found"))
+            ));
+        }
+        if let Some(note) = &rule.set_note {
+            actions.push(format!("{} {}", fl!("note"), note));
+        }
+        actions.join(", ")
+    }
+
+    fn reset_form(&mut self) {
+        self.add_rule_view = false;
+        self.form_name = String::new();
+        self.form_match_text = String::new();
+        self.form_match_regex = false;
+        self.form_min_amount = String::new();
+        self.form_max_amount = String::new();
+        self.form_account = Some(0);
+        self.form_category = Some(0);
+        self.form_payee = String::new();
+        self.form_tag = String::new();
+        self.form_note = String::new();
+        self.form_error = None;
+    }
+
+    pub fn update(&mut self, message: RulesMessage) -> Task<AppMessage> {
+        let mut commands = Vec::new();
+        match message {
+            RulesMessage::Update => {
+                let mut store = STORE.lock().unwrap();
+                let config = Config::load();
+                self.rules = store.get_rules().unwrap_or_else(|e| {
+                    log::error!("error loading rules: {}", e);
+                    vec![]
+                });
+                self.accounts = store.get_accounts().unwrap_or_else(|_| vec![]);
+                self.categories = store.get_categories().unwrap_or_else(|_| vec![]);
+                self.tags = store.get_tags().unwrap_or_else(|_| vec![]);
+                self.currency_symbol = store
+                    .get_currency_symbol_by_id(config.1.currency_id)
+                    .unwrap_or_else(|_| "USD".to_string());
+                self.account_options = options(fl!("all-accounts"), &self.accounts);
+                self.category_options = options(fl!("keep-category"), &self.categories);
+                self.preview = None;
+            }
+            RulesMessage::AddRule => {
+                self.reset_form();
+                self.add_rule_view = true;
+            }
+            RulesMessage::CancelRule => self.reset_form(),
+            RulesMessage::NameChanged(value) => self.form_name = value,
+            RulesMessage::MatchTextChanged(value) => self.form_match_text = value,
+            RulesMessage::MatchRegexToggled(value) => self.form_match_regex = value,
+            RulesMessage::MinAmountChanged(value) => {
+                if value.parse::<f32>().is_ok() || value.is_empty() {
+                    self.form_min_amount = value;
+                }
+            }
+            RulesMessage::MaxAmountChanged(value) => {
+                if value.parse::<f32>().is_ok() || value.is_empty() {
+                    self.form_max_amount = value;
+                }
+            }
+            RulesMessage::AccountChanged(index) => self.form_account = Some(index),
+            RulesMessage::CategoryChanged(index) => self.form_category = Some(index),
+            RulesMessage::PayeeChanged(value) => self.form_payee = value,
+            RulesMessage::TagChanged(value) => self.form_tag = value,
+            RulesMessage::NoteChanged(value) => self.form_note = value,
+            RulesMessage::SubmitRule => {
+                let match_text = non_empty(&self.form_match_text);
+                if let Err(e) = validate_pattern(
+                    match_text.as_deref().unwrap_or_default(),
+                    self.form_match_regex,
+                ) {
+                    self.form_error = Some(format!("{}: {}", fl!("invalid-pattern"), e));
+                    return Task::none();
+                }
+
+                let mut store = STORE.lock().unwrap();
+                let set_tag = match non_empty(&self.form_tag) {
+                    Some(tag_name) => match store.get_or_create_tag(&tag_name) {
+                        Ok(tag) => Some(tag.id),
+                        Err(e) => {
+                            log::error!("error creating tag: {}", e);
+                            None
+                        }
+                    },
+                    None => None,
+                };
+                let new_rule = NewRule {
+                    rule_name: self.form_name.trim().to_string(),
+                    match_regex: self.form_match_regex && match_text.is_some(),
+                    match_text,
+                    match_min_amount: self.form_min_amount.parse().ok(),
+                    match_max_amount: self.form_max_amount.parse().ok(),
+                    match_account: self
+                        .form_account
+                        .and_then(|index| index.checked_sub(1))
+                        .and_then(|index| self.accounts.get(index))
+                        .map(|a| a.id),
+                    set_category: self
+                        .form_category
+                        .and_then(|index| index.checked_sub(1))
+                        .and_then(|index| self.categories.get(index))
+                        .map(|c| c.id),
+                    set_payee: non_empty(&self.form_payee),
+                    set_tag,
+                    set_note: non_empty(&self.form_note),
+                };
+                match store.create_rule(&new_rule) {
+                    Ok(_) => {
+                        self.reset_form();
+                        commands.push(Task::perform(async {}, |_| {
+                            AppMessage::Rules(RulesMessage::Update)
+                        }));
+                    }
+                    Err(e) => {
+                        log::error!("error creating rule: {}", e);
+                        self.form_error = Some(fl!("rule-save-error"));
+                    }
+                }
+            }
+            RulesMessage::Delete(rule_id) => {
+                let mut store = STORE.lock().unwrap();
+                if let Err(e) = store.delete_rule(rule_id) {
+                    log::error!("error deleting rule: {}", e);
+                }
+                self.rules.retain(|r| r.id != rule_id);
+            }
+            RulesMessage::Preview(rule_id) => {
+                let mut store = STORE.lock().unwrap();
+                match store.preview_rule(rule_id) {
+                    Ok(transactions) => self.preview = Some((rule_id, transactions)),
+                    Err(e) => log::error!("error previewing rule: {}", e),
+                }
+            }
+            RulesMessage::ClosePreview => self.preview = None,
+            RulesMessage::Apply => {
+                let Some((rule_id, _)) = self.preview.take() else {
+                    return Task::none();
+                };
+                let mut store = STORE.lock().unwrap();
+                match store.apply_rule(rule_id) {
+                    Ok(count) => {
+                        commands.push(Task::perform(async {}, |_| AppMessage::UpdateAllPages));
+                        commands.push(Task::perform(async {}, move |_| {
+                            AppMessage::ShowToast(fl!("rule-applied", count = count))
+                        }));
+                    }
+                    Err(e) => {
+                        log::error!("error applying rule: {}", e);
+                        commands.push(Task::perform(async {}, |_| {
+                            AppMessage::ShowToast(fl!("rule-apply-error"))
+                        }));
+                    }
+                }
+            }
+        }
+        Task::batch(commands)
+    }
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Dropdown labels with a leading option meaning "none of these".
+fn options<T: AsRef<str>>(first: String, items: &[T]) -> Vec<String> {
+    std::iter::once(first)
+        .chain(items.iter().map(|item| item.as_ref().to_string()))
+        .collect()
+}
@@ -0,0 +1,176 @@
+use cosmic::{
+    iced::{Alignment, Length},
+    widget::{self, Space},
+    Element, Task,
+};
+
+use crate::{
+    app::AppMessage,
+    config::Config,
+    fl,
+    models::{Account, Category, MoneyTransaction, SavedFilter},
+    widget::transaction_card::transaction_card,
+    STORE,
+};
+
+#[derive(Debug, Clone)]
+pub enum SavedFilterMessage {
+    Open(i32),
+    Update,
+    Delete,
+}
+
+/// Page shown for a saved filter pinned in the nav bar.
+#[derive(Default)]
+pub struct SavedFilterView {
+    saved_filter: Option<SavedFilter>,
+    transactions: Vec<MoneyTransaction>,
+    accounts: Vec<Account>,
+    categories: Vec<Category>,
+    currency_symbol: String,
+    /// Why the saved filter could not be applied.
+    error: Option<String>,
+}
+
+impl SavedFilterView {
+    pub fn view<'a>(&'a self) -> Element<'a, SavedFilterMessage> {
+        let Some(saved_filter) = &self.saved_filter else {
+            return widget::text::text(fl!("not-found")).into();
+        };
+
+        let (income, expense) = self
+            .transactions
+            .iter()
+            .fold((0., 0.), |(income, expense), t| {
+                if t.is_expense {
+                    (income, expense + t.amount)
+                } else {
+                    (income + t.amount, expense)
+                }
+            });
+
+        let mut col = widget::column()
+            .push(
+                widget::row()
+                    .push(
+                        widget::text::title1(saved_filter.filter_name.clone()).width(Length::Fill),
+                    )
+                    .push(
+                        widget::button::text(fl!("delete-filter"))
+                            .on_press(SavedFilterMessage::Delete)
+                            .class(widget::button::ButtonClass::Destructive),
+                    )
+                    .align_y(Alignment::Center),
+            )
+            .push(Space::with_height(10))
+            .push(
+                widget::row()
+                    .push(
+                        widget::text::text(format!(
+                            "{}: +{:.2} {}",
+                            fl!("income"),
+                            income,
+                            self.currency_symbol
+                        ))
+                        .width(Length::Fill),
+                    )
+                    .push(
+                        widget::text::text(format!(
+                            "{}: -{:.2} {}",
+                            fl!("expense"),
+                            expense,
+                            self.currency_symbol
+                        ))
+                        .width(Length::Fill),
+                    )
+                    .push(
+                        widget::text::text(format!(
+                            "{}: {:.2} {}",
+                            fl!("net"),
+                            income - expense,
+                            self.currency_symbol
+                        ))
+                        .width(Length::Fill),
+                    ),
+            )
+            .push(Space::with_height(10));
+
+        if let Some(error) = &self.error {
+            col = col.push(widget::text::text(fl!(
+                "saved-filter-invalid",
+                error = error.clone()
+            )));
+        } else if self.transactions.is_empty() {
+            col = col.push(widget::text::text(fl!("no-elements")));
+        }
+        for t in &self.transactions {
+            col = col
+                .push(transaction_card(
+                    t,
+                    &self.categories,
+                    &self.accounts,
+                    &self.currency_symbol,
+                ))
+                .push(Space::with_height(10));
+        }
+
+        widget::scrollable(widget::container(col).width(Length::Fill)).into()
+    }
+
+    pub fn update(&mut self, message: SavedFilterMessage) -> Task<AppMessage> {
+        let mut commands = Vec::new();
+        match message {
+            SavedFilterMessage::Open(filter_id) => {
+                let mut store = STORE.lock().unwrap();
+                self.saved_filter = store
+                    .get_saved_filters()
+                    .unwrap_or_else(|_| vec![])
+                    .into_iter()
+                    .find(|f| f.id == filter_id);
+                drop(store);
+                self.reload();
+            }
+            SavedFilterMessage::Update => {
+                self.reload();
+            }
+            SavedFilterMessage::Delete => {
+                if let Some(saved_filter) = self.saved_filter.take() {
+                    let mut store = STORE.lock().unwrap();
+                    if let Err(e) = store.delete_saved_filter(saved_filter.id) {
+                        log::error!("error deleting saved filter: {}", e);
+                    }
+                    self.transactions = vec![];
+                    commands.push(Task::perform(async {}, |_| AppMessage::SavedFiltersChanged));
+                }
+            }
+        }
+        Task::batch(commands)
+    }
+
+    fn reload(&mut self) {
+        let Some(saved_filter) = &self.saved_filter else {
+            return;
+        };
+        let mut store = STORE.lock().unwrap();
+        let config = Config::load();
+        let transactions = saved_filter
+            .filter()
+            .and_then(|filter| store.get_filtered_transactions(&filter));
+        match transactions {
+            Ok(transactions) => {
+                self.transactions = transactions;
+                self.error = None;
+            }
+            Err(e) => {
+                log::error!("error applying saved filter: {}", e);
+                self.transactions = vec![];
+                self.error = Some(e.to_string());
+            }
+        }
+        self.accounts = store.get_accounts().unwrap_or_else(|_| vec![]);
+        self.categories = store.get_categories().unwrap_or_else(|_| vec![]);
+        self.currency_symbol = store
+            .get_currency_symbol_by_id(config.1.currency_id)
+            .unwrap_or_else(|_| "USD".to_string());
+    }
+}
@@ -0,0 +1,206 @@
+use chrono::{Local, NaiveDateTime, Utc};
+use cosmic::{
+    iced::Length,
+    widget::{self, Space},
+    Element, Task,
+};
+
+use crate::{
+    app::AppMessage,
+    config::Config,
+    fl,
+    models::{Account, Category, MoneyTransaction},
+    utils::dates,
+    widget::{date_picker::date_picker, transaction_card::transaction_card},
+    STORE,
+};
+
+#[derive(Debug, Clone)]
+pub enum SearchMessage {
+    Update,
+    QueryChanged(String),
+    ClearQuery,
+    MinAmountChanged(String),
+    MaxAmountChanged(String),
+    DateRangeToggled(bool),
+    DateFromChanged(i64),
+    DateToChanged(i64),
+}
+
+pub struct Search {
+    query: String,
+    results: Vec<MoneyTransaction>,
+    accounts: Vec<Account>,
+    categories: Vec<Category>,
+    currency_symbol: String,
+    min_amount: String,
+    max_amount: String,
+    date_range_enabled: bool,
+    date_from: i64,
+    date_to: i64,
+}
+
+impl Default for Search {
+    fn default() -> Self {
+        let mut store = STORE.lock().unwrap();
+        let config = Config::load();
+        let currency_symbol = store.get_currency_symbol_by_id(config.1.currency_id);
+
+        Self {
+            query: String::default(),
+            results: vec![],
+            accounts: store.get_accounts().unwrap_or_else(|_| vec![]),
+            categories: store.get_categories().unwrap_or_else(|_| vec![]),
+            currency_symbol: currency_symbol.unwrap_or_else(|_| "USD".to_string()),
+            min_amount: String::default(),
+            max_amount: String::default(),
+            date_range_enabled: false,
+            date_from: Utc::now().timestamp(),
+            date_to: Utc::now().timestamp(),
+        }
+    }
+}
+
+impl Search {
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn view<'a>(&'a self) -> Element<'a, SearchMessage> {
+        let mut col = widget::column()
+            .push(widget::text::title1(fl!("page-search")))
+            .push(Space::with_height(10))
+            .push(self.refinements_view())
+            .push(Space::with_height(10));
+
+        if self.query.trim().is_empty() {
+            col = col.push(widget::text::text(fl!("search-hint")));
+        } else if self.results.is_empty() {
+            col = col.push(widget::text::text(fl!("no-search-results")));
+        } else {
+            col = col
+                .push(widget::text::text(fl!(
+                    "search-results",
+                    count = self.results.len()
+                )))
+                .push(Space::with_height(5));
+            for t in &self.results {
+                col = col.push(self.result_view(t)).push(Space::with_height(10));
+            }
+        }
+
+        widget::scrollable(widget::container(col).width(Length::Fill)).into()
+    }
+
+    fn refinements_view<'a>(&'a self) -> Element<'a, SearchMessage> {
+        widget::column()
+            .push(
+                widget::row()
+                    .push(
+                        widget::column()
+                            .push(widget::text::text(fl!("min-amount")))
+                            .push(
+                                widget::text_input("0", &self.min_amount)
+                                    .on_input(SearchMessage::MinAmountChanged),
+                            )
+                            .width(Length::Fill),
+                    )
+                    .push(Space::with_width(10))
+                    .push(
+                        widget::column()
+                            .push(widget::text::text(fl!("max-amount")))
+                            .push(
+                                widget::text_input("0", &self.max_amount)
+                                    .on_input(SearchMessage::MaxAmountChanged),
+                            )
+                            .width(Length::Fill),
+                    ),
+            )
+            .push(Space::with_height(5))
+            .push(
+                widget::checkbox(fl!("filter-by-date"), self.date_range_enabled)
+                    .on_toggle(SearchMessage::DateRangeToggled),
+            )
+            .push_maybe(if self.date_range_enabled {
+                Some(
+                    widget::column()
+                        .push(widget::text::text(fl!("from")))
+                        .push(date_picker(self.date_from, SearchMessage::DateFromChanged))
+                        .push(widget::text::text(fl!("to")))
+                        .push(date_picker(self.date_to, SearchMessage::DateToChanged)),
+                )
+            } else {
+                None
+            })
+            .into()
+    }
+
+    pub fn update(&mut self, message: SearchMessage) -> Task<AppMessage> {
+        match message {
+            SearchMessage::Update => {
+                let mut store = STORE.lock().unwrap();
+                let config = Config::load();
+                self.accounts = store.get_accounts().unwrap_or_else(|_| vec![]);
+                self.categories = store.get_categories().unwrap_or_else(|_| vec![]);
+                self.currency_symbol = store
+                    .get_currency_symbol_by_id(config.1.currency_id)
+                    .unwrap_or_else(|_| "USD".to_string());
+            }
+            SearchMessage::QueryChanged(query) => {
+                self.query = query;
+            }
+            SearchMessage::ClearQuery => {
+                self.query = String::default();
+            }
+            SearchMessage::MinAmountChanged(value) => {
+                if value.parse::<f32>().is_ok() || value.is_empty() {
+                    self.min_amount = value;
+                }
+            }
+            SearchMessage::MaxAmountChanged(value) => {
+                if value.parse::<f32>().is_ok() || value.is_empty() {
+                    self.max_amount = value;
+                }
+            }
+            SearchMessage::DateRangeToggled(enabled) => {
+                self.date_range_enabled = enabled;
+            }
+            SearchMessage::DateFromChanged(date) => {
+                self.date_from = date;
+            }
+            SearchMessage::DateToChanged(date) => {
+                self.date_to = date;
+            }
+        }
+        self.run_search();
+        Task::none()
+    }
+
+    fn run_search(&mut self) {
+        let date_range = if self.date_range_enabled {
+            let from = NaiveDateTime::from_timestamp(self.date_from, 0);
+            let to = NaiveDateTime::from_timestamp(self.date_to, 0);
+            Some((
+                dates::local_date(&Local, &from),
+                dates::local_date(&Local, &to),
+            ))
+        } else {
+            None
+        };
+
+        let mut store = STORE.lock().unwrap();
+        self.results = store
+            .search_transactions(
+                &self.query,
+                (
+                    self.min_amount.parse::<f32>().ok(),
+                    self.max_amount.parse::<f32>().ok(),
+                ),
+                date_range,
+            )
+            .unwrap_or_else(|e| {
+                log::error!("search failed: {}", e);
+                vec![]
+            });
+    }
+}
@@ -1,27 +1,69 @@
-use crate::{app::AppMessage, config::Config, fl, models::Currency, STORE};
+use chrono::{Local, NaiveDateTime, Utc};
 use cosmic::{
     iced::Length,
     widget::{self, Space},
     Element, Task,
 };
 
+use crate::{
+    app::AppMessage,
+    config::Config,
+    fl,
+    models::{Account, Currency},
+    synchronization::{
+        csv::{date_format_label, DATE_FORMATS, DELIMITERS},
+        export::CsvExportOptions,
+        import::ImportMode,
+    },
+    utils::dates,
+    widget::date_picker::date_picker,
+    STORE,
+};
+
 #[derive(Debug, Clone)]
 pub enum SettingsMessage {
     Update,
     CurrencyChanged(usize),
-    Import,
+    ProjectionHorizonChanged(String),
+    DuplicateWindowChanged(String),
+    Import(ImportMode),
+    ImportCsv,
+    ImportStatement,
+    ImportQif,
     Export,
+    ExportQif,
+    CsvDelimiterChanged(usize),
+    CsvDateFormatChanged(usize),
+    CsvAccountChanged(usize),
+    CsvDateRangeToggled(bool),
+    CsvDateFromChanged(i64),
+    CsvDateToChanged(i64),
+    ExportCsv,
 }
 
 pub struct Settings {
     currency_list: Vec<Currency>,
     selected_currency: Option<usize>,
+    projection_horizon: String,
+    duplicate_window: String,
+    accounts: Vec<Account>,
+    /// Index into the account options, where 0 exports every account.
+    csv_account: Option<usize>,
+    csv_delimiter: Option<usize>,
+    csv_date_format: Option<usize>,
+    csv_date_range_enabled: bool,
+    csv_date_from: i64,
+    csv_date_to: i64,
+    account_options: Vec<String>,
+    delimiter_options: Vec<String>,
+    date_format_options: Vec<String>,
 }
 
 impl Default for Settings {
     fn default() -> Self {
         let mut store = STORE.lock().unwrap();
         let currencies = store.get_currencies().unwrap_or_else(|_| vec![]);
+        let accounts = store.get_accounts().unwrap_or_else(|_| vec![]);
         let config = Config::load();
 
         let selected_currency_id = config.1.currency_id;
@@ -34,6 +76,27 @@ impl Default for Settings {
         Self {
             currency_list: currencies,
             selected_currency: Some(selected_currency),
+            projection_horizon: config.1.projection_horizon_days.to_string(),
+            duplicate_window: config.1.duplicate_window_days.to_string(),
+            account_options: account_options(&accounts),
+            accounts,
+            csv_account: Some(0),
+            csv_delimiter: Some(0),
+            csv_date_format: Some(0),
+            csv_date_range_enabled: false,
+            csv_date_from: Utc::now().timestamp(),
+            csv_date_to: Utc::now().timestamp(),
+            delimiter_options: DELIMITERS
+                .iter()
+                .map(|d| match d {
+                    '\t' => fl!("csv-tab"),
+                    d => d.to_string(),
+                })
+                .collect(),
+            date_format_options: DATE_FORMATS
+                .iter()
+                .map(|format| date_format_label(format))
+                .collect(),
         }
     }
 }
@@ -54,6 +117,26 @@ impl Settings {
                 SettingsMessage::CurrencyChanged,
             ));
 
+        settings_col = settings_col
+            .push(Space::with_height(20))
+            .push(widget::text::title4(fl!("projection-horizon")))
+            .push(widget::text::text(fl!("projection-horizon-desc")))
+            .push(Space::with_height(5))
+            .push(
+                widget::text_input("30", &self.projection_horizon)
+                    .on_input(SettingsMessage::ProjectionHorizonChanged),
+            );
+
+        settings_col = settings_col
+            .push(Space::with_height(20))
+            .push(widget::text::title4(fl!("duplicate-window")))
+            .push(widget::text::text(fl!("duplicate-window-desc")))
+            .push(Space::with_height(5))
+            .push(
+                widget::text_input("3", &self.duplicate_window)
+                    .on_input(SettingsMessage::DuplicateWindowChanged),
+            );
+
         settings_col = settings_col
             .push(Space::with_height(20))
             .push(widget::text::title4(fl!("import-export")))
@@ -63,17 +146,115 @@ impl Settings {
                 widget::row()
                     .push(
                         widget::button::text(fl!("import"))
-                            .on_press(SettingsMessage::Import)
+                            .on_press(SettingsMessage::Import(ImportMode::Replace))
                             .class(widget::button::ButtonClass::Suggested),
                     )
                     .push(Space::with_width(10))
+                    .push(
+                        widget::button::text(fl!("merge-import"))
+                            .on_press(SettingsMessage::Import(ImportMode::Merge))
+                            .class(widget::button::ButtonClass::Standard),
+                    )
+                    .push(Space::with_width(10))
+                    .push(
+                        widget::button::text(fl!("import-csv"))
+                            .on_press(SettingsMessage::ImportCsv)
+                            .class(widget::button::ButtonClass::Standard),
+                    )
+                    .push(Space::with_width(10))
+                    .push(
+                        widget::button::text(fl!("import-statement"))
+                            .on_press(SettingsMessage::ImportStatement)
+                            .class(widget::button::ButtonClass::Standard),
+                    )
+                    .push(Space::with_width(10))
+                    .push(
+                        widget::button::text(fl!("import-qif"))
+                            .on_press(SettingsMessage::ImportQif)
+                            .class(widget::button::ButtonClass::Standard),
+                    )
+                    .push(Space::with_width(10))
                     .push(
                         widget::button::text(fl!("export"))
                             .on_press(SettingsMessage::Export)
                             .class(widget::button::ButtonClass::Suggested),
+                    )
+                    .push(Space::with_width(10))
+                    .push(
+                        widget::button::text(fl!("export-qif"))
+                            .on_press(SettingsMessage::ExportQif)
+                            .class(widget::button::ButtonClass::Standard),
                     ),
             );
 
+        settings_col = settings_col
+            .push(Space::with_height(20))
+            .push(widget::text::title4(fl!("csv-export")))
+            .push(widget::text::text(fl!("csv-export-desc")))
+            .push(Space::with_height(5))
+            .push(
+                widget::row()
+                    .push(
+                        widget::column()
+                            .push(widget::text::text(fl!("csv-delimiter")))
+                            .push(widget::dropdown(
+                                &self.delimiter_options,
+                                self.csv_delimiter,
+                                SettingsMessage::CsvDelimiterChanged,
+                            ))
+                            .width(Length::Fill),
+                    )
+                    .push(
+                        widget::column()
+                            .push(widget::text::text(fl!("csv-date-format")))
+                            .push(widget::dropdown(
+                                &self.date_format_options,
+                                self.csv_date_format,
+                                SettingsMessage::CsvDateFormatChanged,
+                            ))
+                            .width(Length::Fill),
+                    )
+                    .push(
+                        widget::column()
+                            .push(widget::text::text(fl!("bank-account")))
+                            .push(widget::dropdown(
+                                &self.account_options,
+                                self.csv_account,
+                                SettingsMessage::CsvAccountChanged,
+                            ))
+                            .width(Length::Fill),
+                    )
+                    .spacing(10),
+            )
+            .push(Space::with_height(5))
+            .push(
+                widget::checkbox(fl!("filter-by-date"), self.csv_date_range_enabled)
+                    .on_toggle(SettingsMessage::CsvDateRangeToggled),
+            )
+            .push_maybe(if self.csv_date_range_enabled {
+                Some(
+                    widget::column()
+                        .push(widget::text::text(fl!("from")))
+                        .push(date_picker(
+                            self.csv_date_from,
+                            SettingsMessage::CsvDateFromChanged,
+                        ))
+                        .push(widget::text::text(fl!("to")))
+                        .push(date_picker(
+                            self.csv_date_to,
+                            SettingsMessage::CsvDateToChanged,
+                        )),
+                )
+            } else {
+                None
+            })
+            .push(Space::with_height(5))
+            .push(
+                widget::button::text(fl!("export-csv"))
+                    .on_press(SettingsMessage::ExportCsv)
+                    .class(widget::button::ButtonClass::Standard),
+            );
+
         let main_container = widget::container(settings_col);
 
         widget::scrollable(main_container).into()
@@ -100,6 +281,34 @@ impl Settings {
                     AppMessage::Transactions(super::transactions::TransactionMessage::UpdatePage)
                 }));
             }
+            SettingsMessage::ProjectionHorizonChanged(value) => {
+                if value.is_empty() {
+                    self.projection_horizon = value;
+                } else if let Ok(days) = value.parse::<u32>() {
+                    self.projection_horizon = value;
+                    let mut config = Config::load();
+                    if let Some(handler) = config.0 {
+                        let _ = config.1.set_projection_horizon_days(&handler, days);
+                    }
+                    commands.push(Task::perform(async {}, |_| {
+                        AppMessage::Accounts(super::accounts::AccountsMessage::Update)
+                    }));
+                }
+            }
+            SettingsMessage::DuplicateWindowChanged(value) => {
+                if value.is_empty() {
+                    self.duplicate_window = value;
+                } else if let Ok(days) = value.parse::<u32>() {
+                    self.duplicate_window = value;
+                    let mut config = Config::load();
+                    if let Some(handler) = config.0 {
+                        let _ = config.1.set_duplicate_window_days(&handler, days);
+                    }
+                    commands.push(Task::perform(async {}, |_| {
+                        AppMessage::Duplicates(super::duplicates::DuplicatesMessage::Update)
+                    }));
+                }
+            }
             SettingsMessage::Update => {
                 let mut store = STORE.lock().unwrap();
                 let currencies = store.get_currencies().unwrap_or_else(|_| vec![]);
@@ -113,14 +322,84 @@ impl Settings {
                     .unwrap_or(0);
 
                 self.selected_currency = Some(selected_currency);
+                self.projection_horizon = config.1.projection_horizon_days.to_string();
+                self.duplicate_window = config.1.duplicate_window_days.to_string();
+
+                let selected_account = self
+                    .csv_account
+                    .and_then(|index| index.checked_sub(1))
+                    .and_then(|index| self.accounts.get(index))
+                    .map(|a| a.id);
+                self.accounts = store.get_accounts().unwrap_or_else(|_| vec![]);
+                self.account_options = account_options(&self.accounts);
+                self.csv_account = Some(
+                    selected_account
+                        .and_then(|id| self.accounts.iter().position(|a| a.id == id))
+                        .map_or(0, |index| index + 1),
+                );
+            }
+            SettingsMessage::Import(mode) => {
+                commands.push(Task::perform(async {}, move |_| AppMessage::Import(mode)));
+            }
+            SettingsMessage::ImportCsv => {
+                commands.push(Task::perform(async {}, |_| AppMessage::ImportCsv));
             }
-            SettingsMessage::Import => {
-                commands.push(Task::perform(async {}, |_| AppMessage::Import));
+            SettingsMessage::ImportStatement => {
+                commands.push(Task::perform(async {}, |_| AppMessage::ImportStatement));
+            }
+            SettingsMessage::ImportQif => {
+                commands.push(Task::perform(async {}, |_| AppMessage::ImportQif));
             }
             SettingsMessage::Export => {
                 commands.push(Task::perform(async {}, |_| AppMessage::Export));
             }
+            SettingsMessage::ExportQif => {
+                commands.push(Task::perform(async {}, |_| AppMessage::ExportQif));
+            }
+            SettingsMessage::CsvDelimiterChanged(index) => self.csv_delimiter = Some(index),
+            SettingsMessage::CsvDateFormatChanged(index) => self.csv_date_format = Some(index),
+            SettingsMessage::CsvAccountChanged(index) => self.csv_account = Some(index),
+            SettingsMessage::CsvDateRangeToggled(enabled) => self.csv_date_range_enabled = enabled,
+            SettingsMessage::CsvDateFromChanged(date) => self.csv_date_from = date,
+            SettingsMessage::CsvDateToChanged(date) => self.csv_date_to = date,
+            SettingsMessage::ExportCsv => {
+                let date_range = self.csv_date_range_enabled.then(|| {
+                    let from = NaiveDateTime::from_timestamp(self.csv_date_from, 0);
+                    let to = NaiveDateTime::from_timestamp(self.csv_date_to, 0);
+                    (
+                        dates::local_date(&Local, &from),
+                        dates::local_date(&Local, &to),
+                    )
+                });
+                let options = CsvExportOptions {
+                    delimiter: self
+                        .csv_delimiter
+                        .and_then(|index| DELIMITERS.get(index))
+                        .copied()
+                        .unwrap_or(','),
+                    date_format: self
+                        .csv_date_format
+                        .and_then(|index| DATE_FORMATS.get(index))
+                        .unwrap_or(&DATE_FORMATS[0])
+                        .to_string(),
+                    date_range,
+                    account_id: self
+                        .csv_account
+                        .and_then(|index| index.checked_sub(1))
+                        .and_then(|index| self.accounts.get(index))
+                        .map(|a| a.id),
+                };
+                commands.push(Task::perform(async {}, move |_| {
+                    AppMessage::ExportCsv(options)
+                }));
+            }
         }
         Task::batch(commands)
     }
 }
+
+fn account_options(accounts: &[Account]) -> Vec<String> {
+    std::iter::once(fl!("all-accounts"))
+        .chain(accounts.iter().map(|a| a.name.clone()))
+        .collect()
+}
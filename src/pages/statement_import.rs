@@ -0,0 +1,413 @@
+use std::fs;
+
+use cosmic::{
+    dialog::ashpd::url::Url,
+    iced::{Alignment, Length},
+    widget::{self, Space},
+    Element, Task,
+};
+
+use crate::{
+    app::AppMessage,
+    fl,
+    models::{Account, Category},
+    pages::accounts::AccountsMessage,
+    synchronization::{
+        merge::RecordCounts,
+        statement::{self, LedgerBalance, Statement},
+    },
+    utils::dates,
+    widget::form::{card, labeled},
+    STORE,
+};
+
+#[derive(Debug, Clone)]
+pub enum StatementImportMessage {
+    Update,
+    ChooseFile,
+    Load(Url),
+    /// Account chosen for a statement of the file, by index.
+    AccountChanged(usize, usize),
+    ExpenseCategoryChanged(usize),
+    IncomeCategoryChanged(usize),
+    Import,
+    /// Opens the reconciliation of an imported statement, by index.
+    Reconcile(usize),
+    Cancel,
+}
+
+/// Statement of the file with the account it goes to.
+struct StatementPlan {
+    statement: Statement,
+    account: Option<usize>,
+    /// Entries not imported into the chosen account before.
+    new_entries: usize,
+}
+
+/// Imports the statement files banks export, such as OFX, into the matching
+/// accounts, skipping what was already imported.
+#[derive(Default)]
+pub struct StatementImport {
+    file_name: String,
+    plans: Vec<StatementPlan>,
+    /// Accounts just imported with the balance their bank reports, ready to
+    /// be reconciled.
+    imported: Vec<(Account, LedgerBalance)>,
+    accounts: Vec<Account>,
+    expense_categories: Vec<Category>,
+    income_categories: Vec<Category>,
+    expense_category: Option<usize>,
+    income_category: Option<usize>,
+    account_options: Vec<String>,
+    expense_options: Vec<String>,
+    income_options: Vec<String>,
+}
+
+impl StatementImport {
+    pub fn view<'a>(&'a self) -> Element<'a, StatementImportMessage> {
+        let mut col = widget::column()
+            .push(
+                widget::row()
+                    .push(widget::text::title1(fl!("page-statement-import")).width(Length::Fill))
+                    .push(
+                        widget::button::text(fl!("choose-file"))
+                            .on_press(StatementImportMessage::ChooseFile)
+                            .class(widget::button::ButtonClass::Suggested),
+                    )
+                    .align_y(Alignment::Center),
+            )
+            .push(Space::with_height(10));
+
+        if !self.imported.is_empty() {
+            col = col.push(self.reconcile_view());
+        } else if self.plans.is_empty() {
+            col = col.push(widget::text::text(fl!("statement-import-desc")));
+        } else {
+            col = col
+                .push(widget::text::title4(fl!(
+                    "statement-file",
+                    file = self.file_name.clone()
+                )))
+                .push(Space::with_height(10));
+            for (index, plan) in self.plans.iter().enumerate() {
+                col = col
+                    .push(self.statement_view(index, plan))
+                    .push(Space::with_height(10));
+            }
+            col = col.push(self.categories_view());
+        }
+
+        widget::scrollable(widget::container(col).width(Length::Fill)).into()
+    }
+
+    fn statement_view<'a>(
+        &'a self,
+        index: usize,
+        plan: &'a StatementPlan,
+    ) -> Element<'a, StatementImportMessage> {
+        let statement = &plan.statement;
+        let mut details = widget::column()
+            .push(widget::text::title4(fl!(
+                "statement-account",
+                account = statement
+                    .account
+                    .clone()
+                    .unwrap_or_else(|| fl!("statement-unknown-account")),
+                currency = statement.currency.clone().unwrap_or_default()
+            )))
+            .push(Space::with_height(5))
+            .push(widget::text::text(fl!(
+                "statement-counts",
+                count = statement.entries.len(),
+                new = plan.new_entries
+            )));
+        if let Some((first, last)) = statement.date_range() {
+            details = details.push(widget::text::text(fl!(
+                "import-preview-dates",
+                first = first.format("%d-%m-%Y").to_string(),
+                last = last.format("%d-%m-%Y").to_string()
+            )));
+        }
+        if let Some(balance) = &statement.ledger_balance {
+            details = details.push(widget::text::text(fl!(
+                "statement-ledger-balance",
+                amount = format!("{:.2}", balance.amount),
+                currency = statement.currency.clone().unwrap_or_default(),
+                date = balance.date.format("%d-%m-%Y").to_string()
+            )));
+        }
+        details = details.push(Space::with_height(10)).push(labeled(
+            fl!("bank-account"),
+            widget::dropdown(&self.account_options, plan.account, move |account| {
+                StatementImportMessage::AccountChanged(index, account)
+            }),
+        ));
+
+        card(details)
+    }
+
+    fn categories_view<'a>(&'a self) -> Element<'a, StatementImportMessage> {
+        let ready = !self.plans.is_empty()
+            && self.plans.iter().all(|plan| plan.account.is_some())
+            && self.expense_category.is_some()
+            && self.income_category.is_some();
+
+        let content = widget::column()
+            .push(widget::text::text(fl!("statement-categories-desc")))
+            .push(Space::with_height(5))
+            .push(
+                widget::row()
+                    .push(labeled(
+                        fl!("csv-expense-category"),
+                        widget::dropdown(
+                            &self.expense_options,
+                            self.expense_category,
+                            StatementImportMessage::ExpenseCategoryChanged,
+                        ),
+                    ))
+                    .push(Space::with_width(10))
+                    .push(labeled(
+                        fl!("csv-income-category"),
+                        widget::dropdown(
+                            &self.income_options,
+                            self.income_category,
+                            StatementImportMessage::IncomeCategoryChanged,
+                        ),
+                    )),
+            )
+            .push(Space::with_height(10))
+            .push(
+                widget::row()
+                    .push(
+                        widget::button::text(fl!("cancel"))
+                            .on_press(StatementImportMessage::Cancel)
+                            .class(widget::button::ButtonClass::Destructive),
+                    )
+                    .push(Space::with_width(Length::Fill))
+                    .push(
+                        widget::button::text(fl!("import"))
+                            .on_press_maybe(ready.then_some(StatementImportMessage::Import))
+                            .class(widget::button::ButtonClass::Suggested),
+                    ),
+            );
+
+        card(content)
+    }
+
+    fn reconcile_view<'a>(&'a self) -> Element<'a, StatementImportMessage> {
+        let mut content = widget::column()
+            .push(widget::text::title4(fl!("statement-reconcile")))
+            .push(Space::with_height(5))
+            .push(widget::text::text(fl!("statement-reconcile-desc")))
+            .push(Space::with_height(10));
+        for (index, (account, balance)) in self.imported.iter().enumerate() {
+            content = content.push(
+                widget::row()
+                    .push(widget::text::text(account.name.clone()).width(Length::Fill))
+                    .push(widget::text::text(fl!(
+                        "statement-balance-on",
+                        amount = format!("{:.2}", balance.amount),
+                        date = balance.date.format("%d-%m-%Y").to_string()
+                    )))
+                    .push(Space::with_width(10))
+                    .push(
+                        widget::button::text(fl!("reconcile"))
+                            .on_press(StatementImportMessage::Reconcile(index))
+                            .class(widget::button::ButtonClass::Suggested),
+                    )
+                    .align_y(Alignment::Center),
+            );
+        }
+        content = content.push(Space::with_height(10)).push(
+            widget::button::text(fl!("close"))
+                .on_press(StatementImportMessage::Cancel)
+                .class(widget::button::ButtonClass::Standard),
+        );
+
+        card(content)
+    }
+
+    /// Counts the entries of a statement that the chosen account lacks.
+    fn count_new_entries(&self, plan: &StatementPlan) -> usize {
+        let Some(account) = plan.account.and_then(|i| self.accounts.get(i)) else {
+            return plan.statement.new_entries(&Default::default()).len();
+        };
+        let mut store = STORE.lock().unwrap();
+        let known_ids = store.get_external_ids(account.id).unwrap_or_else(|e| {
+            log::error!("error loading external ids: {}", e);
+            Default::default()
+        });
+        plan.statement.new_entries(&known_ids).len()
+    }
+
+    fn reset(&mut self) {
+        self.file_name = String::new();
+        self.plans = vec![];
+        self.imported = vec![];
+    }
+
+    pub fn update(&mut self, message: StatementImportMessage) -> Task<AppMessage> {
+        let mut commands = Vec::new();
+        match message {
+            StatementImportMessage::Update => {
+                let mut store = STORE.lock().unwrap();
+                self.accounts = store.get_accounts().unwrap_or_else(|_| vec![]);
+                (self.income_categories, self.expense_categories) = store
+                    .get_categories()
+                    .unwrap_or_else(|_| vec![])
+                    .into_iter()
+                    .partition(|c: &Category| c.is_income);
+                self.account_options = self.accounts.iter().map(|a| a.name.clone()).collect();
+                self.expense_options = self
+                    .expense_categories
+                    .iter()
+                    .map(|c| c.name.clone())
+                    .collect();
+                self.income_options = self
+                    .income_categories
+                    .iter()
+                    .map(|c| c.name.clone())
+                    .collect();
+                if self.expense_category.is_none() && !self.expense_categories.is_empty() {
+                    self.expense_category = Some(0);
+                }
+                if self.income_category.is_none() && !self.income_categories.is_empty() {
+                    self.income_category = Some(0);
+                }
+            }
+            StatementImportMessage::ChooseFile => {
+                commands.push(Task::perform(async {}, |_| AppMessage::ImportStatement));
+            }
+            StatementImportMessage::Load(url) => {
+                let statements = url
+                    .to_file_path()
+                    .map_err(|_| "Failed to read the selected file".to_string())
+                    .and_then(|path| fs::read(path).map_err(|e| e.to_string()))
+                    .and_then(|bytes| statement::read_statements(&bytes));
+                match statements {
+                    Ok(statements) => {
+                        self.reset();
+                        self.file_name = url
+                            .path_segments()
+                            .and_then(|mut segments| segments.next_back())
+                            .unwrap_or_default()
+                            .to_string();
+                        let known = STORE
+                            .lock()
+                            .unwrap()
+                            .get_statement_accounts()
+                            .unwrap_or_else(|e| {
+                                log::error!("error loading statement accounts: {}", e);
+                                vec![]
+                            });
+                        for statement in statements {
+                            let account =
+                                statement::match_account(&statement, &self.accounts, &known)
+                                    .and_then(|id| self.accounts.iter().position(|a| a.id == id));
+                            let mut plan = StatementPlan {
+                                statement,
+                                account,
+                                new_entries: 0,
+                            };
+                            plan.new_entries = self.count_new_entries(&plan);
+                            self.plans.push(plan);
+                        }
+                    }
+                    Err(e) => {
+                        log::error!("error reading statement file: {}", e);
+                        commands.push(Task::perform(async {}, move |_| {
+                            AppMessage::ShowToast(fl!("statement-read-error", error = e))
+                        }));
+                    }
+                }
+            }
+            StatementImportMessage::AccountChanged(index, account) => {
+                if index < self.plans.len() {
+                    self.plans[index].account = Some(account);
+                    self.plans[index].new_entries = self.count_new_entries(&self.plans[index]);
+                }
+            }
+            StatementImportMessage::ExpenseCategoryChanged(index) => {
+                self.expense_category = Some(index)
+            }
+            StatementImportMessage::IncomeCategoryChanged(index) => {
+                self.income_category = Some(index)
+            }
+            StatementImportMessage::Import => {
+                let (Some(expense), Some(income)) = (
+                    self.expense_category
+                        .and_then(|i| self.expense_categories.get(i)),
+                    self.income_category
+                        .and_then(|i| self.income_categories.get(i)),
+                ) else {
+                    return Task::none();
+                };
+                let plans: Vec<(&Statement, &Account)> = self
+                    .plans
+                    .iter()
+                    .filter_map(|plan| Some((&plan.statement, self.accounts.get(plan.account?)?)))
+                    .collect();
+                let mut store = STORE.lock().unwrap();
+                let result = store.atomically(|store| {
+                    let mut total = RecordCounts::default();
+                    for (statement, account) in &plans {
+                        let counts = statement::import_statement(
+                            store, statement, account.id, expense.id, income.id,
+                        )?;
+                        total.added += counts.added;
+                        total.skipped += counts.skipped;
+                    }
+                    Ok(total)
+                });
+                match result {
+                    Ok(total) => {
+                        log::info!(
+                            "{} statement entries imported, {} skipped",
+                            total.added,
+                            total.skipped
+                        );
+                        let imported = plans
+                            .iter()
+                            .filter_map(|(statement, account)| {
+                                Some(((*account).clone(), statement.ledger_balance?))
+                            })
+                            .collect();
+                        self.reset();
+                        self.imported = imported;
+                        commands.push(Task::perform(async {}, move |_| {
+                            AppMessage::ShowToast(fl!(
+                                "statement-import-success",
+                                added = total.added,
+                                skipped = total.skipped
+                            ))
+                        }));
+                        commands.push(Task::perform(async {}, |_| AppMessage::UpdateAllPages));
+                        commands.push(Task::perform(async {}, |_| AppMessage::CheckDuplicates));
+                    }
+                    Err(e) => {
+                        log::error!("error importing statement: {}", e);
+                        let error = e.to_string();
+                        commands.push(Task::perform(async {}, move |_| {
+                            AppMessage::ShowToast(fl!("import-error-detail", error = error))
+                        }));
+                    }
+                }
+            }
+            StatementImportMessage::Reconcile(index) => {
+                if let Some((account, balance)) = self.imported.get(index) {
+                    let message = AccountsMessage::ReconcileStatement(
+                        account.id,
+                        dates::local_day_end(balance.date).timestamp(),
+                        balance.amount,
+                    );
+                    commands.push(Task::perform(async {}, |_| AppMessage::GoToAccounts));
+                    commands.push(Task::perform(async {}, move |_| {
+                        AppMessage::Accounts(message)
+                    }));
+                }
+            }
+            StatementImportMessage::Cancel => self.reset(),
+        }
+        Task::batch(commands)
+    }
+}
@@ -11,7 +11,12 @@ use cosmic::{
     Element, Task,
 };
 
-use crate::{app::AppMessage, fl, utils::dates::get_month_date_range, STORE};
+use crate::{
+    app::AppMessage,
+    fl,
+    utils::dates::{get_month_date_range, local_date},
+    STORE,
+};
 
 #[derive(Debug, Clone)]
 pub enum StatisticsMessage {
@@ -204,7 +209,17 @@ impl Statistics {
     fn calculate_ratio(&self) -> f32 {
         let mut store = STORE.lock().unwrap();
         let (start_date, end_date) = get_month_date_range(self.view_year, self.view_month);
-        let transactions = store.get_money_transactions_date_range(&start_date, &end_date);
+        let excluded = store
+            .get_statistics_excluded_accounts()
+            .unwrap_or_else(|_| vec![]);
+        let transactions = store
+            .get_money_transactions_date_range(&start_date, &end_date)
+            .map(|transactions| {
+                transactions
+                    .into_iter()
+                    .filter(|t| !excluded.contains(&t.bank_account))
+                    .collect::<Vec<_>>()
+            });
         match transactions {
             Ok(transactions) => {
                 let income_sum: f32 = transactions
@@ -228,7 +243,17 @@ impl Statistics {
     pub fn generate_distribution(&mut self) {
         let mut store = STORE.lock().unwrap();
         let (start_date, end_date) = get_month_date_range(self.view_year, self.view_month);
-        let transactions = store.get_money_transactions_date_range(&start_date, &end_date);
+        let excluded = store
+            .get_statistics_excluded_accounts()
+            .unwrap_or_else(|_| vec![]);
+        let transactions = store
+            .get_money_transactions_date_range(&start_date, &end_date)
+            .map(|transactions| {
+                transactions
+                    .into_iter()
+                    .filter(|t| !excluded.contains(&t.bank_account))
+                    .collect::<Vec<_>>()
+            });
 
         let mut daily_totals: HashMap<NaiveDate, f32> = HashMap::new();
 
@@ -236,7 +261,7 @@ impl Statistics {
             Ok(transactions) => {
                 for transaction in &transactions {
                     if transaction.is_expense {
-                        let date = transaction.transaction_date.date();
+                        let date = local_date(&Local, &transaction.transaction_date);
                         *daily_totals.entry(date).or_insert(0.0) += transaction.amount;
                     }
                 }
@@ -1,6 +1,12 @@
+use std::collections::{HashMap, HashSet};
+
 use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
 use cosmic::{
-    iced::{Alignment, Length, Padding},
+    iced::{
+        alignment::Vertical,
+        widget::scrollable::{self, AbsoluteOffset},
+        Alignment, Length, Padding,
+    },
     widget::{
         self, column,
         segmented_button::{self, SingleSelect},
@@ -12,12 +18,18 @@ use cosmic::{
 use crate::{
     app::AppMessage,
     config::Config,
+    errors::DataStoreError,
     fl,
     models::{
-        Account, AccountTransfer, Category, MoneyTransaction, NewMoneyTransaction,
-        UpdateTransaction,
+        Account, AccountTransfer, BulkEdit, BulkUndo, Category, FilterPeriod, MoneyTransaction,
+        NewMoneyTransaction, NewTransactionTemplate, TransactionCursor, TransactionFilter,
+        TransactionStatus, TransactionTemplate, UpdateTransaction,
+    },
+    store::Store,
+    utils::{
+        classifier::CategoryClassifier,
+        dates::{get_month_date_range, local_date, local_today, period_range},
     },
-    utils::dates::get_month_date_range,
     widget::date_picker::date_picker,
     STORE,
 };
@@ -27,6 +39,46 @@ pub enum ViewItem {
     Transfers,
 }
 
+/// Transactions are fetched a page at a time as the list scrolls.
+const PAGE_SIZE: i64 = 200;
+/// Rows have fixed heights, so the list can tell which of them are visible
+/// without building the others.
+const DAY_HEADER_HEIGHT: f32 = 40.;
+const ROW_HEIGHT: f32 = 100.;
+const ROW_SPACING: f32 = 10.;
+/// Height built above and below the viewport, so fast scrolling doesn't
+/// show gaps.
+const OVERSCAN: f32 = 400.;
+
+enum ListRowKind {
+    Day(NaiveDate),
+    /// Index into the loaded transactions.
+    Transaction(usize),
+}
+
+struct ListRow {
+    top: f32,
+    kind: ListRowKind,
+}
+
+impl ListRow {
+    fn height(&self) -> f32 {
+        match self.kind {
+            ListRowKind::Day(_) => DAY_HEADER_HEIGHT,
+            ListRowKind::Transaction(_) => ROW_HEIGHT + ROW_SPACING,
+        }
+    }
+}
+
+/// Days listed on the page.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum FilterDates {
+    #[default]
+    ViewedMonth,
+    Period(FilterPeriod),
+    Custom,
+}
+
 #[derive(Debug, Clone)]
 pub enum TransactionMessage {
     UpdatePage,
@@ -36,8 +88,10 @@ pub enum TransactionMessage {
     FormTransactionTypeChanged(widget::segmented_button::Entity),
     FormEditTransactionTypeChanged(widget::segmented_button::Entity),
     FormNoteChanged(String),
+    FormPayeeChanged(String),
     FormAmountChanged(String),
     FormDateChanged(i64),
+    UseSuggestedCategory,
     CandellAddTransaction,
     SubmitTransaction,
     PreviousMonth,
@@ -49,9 +103,42 @@ pub enum TransactionMessage {
     FormEditCategoryChanged(usize),
     FormEditBankAccountChanged(usize),
     FormEditNoteChanged(String),
+    FormEditPayeeChanged(String),
     SubmitEditTransaction(i32),
     CancelEditTransaction,
     DeleteTransaction(i32),
+    ListScrolled(f32, f32),
+    ToggleFilterView,
+    FilterAccountChanged(usize),
+    FilterCategoryChanged(usize),
+    FilterKindChanged(usize),
+    FilterMinAmountChanged(String),
+    FilterMaxAmountChanged(String),
+    FilterDatesChanged(usize),
+    FilterDateFromChanged(i64),
+    FilterDateToChanged(i64),
+    FilterTextChanged(String),
+    ClearFilter,
+    FilterNameChanged(String),
+    SaveFilter,
+    ToggleQuickAdd,
+    QuickAdd(i32),
+    DeleteTemplate(i32),
+    TemplateNameChanged(String),
+    SaveAsTemplate(i32),
+    ToggleSelectionMode,
+    ToggleSelected(i32, bool),
+    SelectAll,
+    BulkCategoryChanged(usize),
+    BulkAccountChanged(usize),
+    BulkTagChanged(String),
+    BulkDaysChanged(String),
+    BulkSetCategory,
+    BulkSetAccount,
+    BulkAddTag,
+    BulkShiftDate,
+    BulkDelete,
+    UndoBulkEdit,
 }
 
 pub struct Transactions {
@@ -63,10 +150,23 @@ pub struct Transactions {
     accounts: Vec<Account>,
     form_transaction_type: widget::segmented_button::SingleSelectModel,
     form_selectected_category: Option<usize>,
+    /// Transactions loaded so far, in list order.
     transactions: Vec<MoneyTransaction>,
+    transaction_count: usize,
+    transaction_totals: (f32, f32),
+    /// Tag names of the loaded transactions that have any.
+    transaction_tags: HashMap<i32, Vec<String>>,
+    list_rows: Vec<ListRow>,
+    list_id: scrollable::Id,
+    list_scroll: f32,
+    list_viewport: f32,
     transfers: Vec<AccountTransfer>,
     form_selected_bank_account: Option<usize>,
     form_note: String,
+    form_payee: String,
+    category_classifier: CategoryClassifier,
+    /// Suggested category id for the new transaction, with its confidence.
+    category_suggestion: Option<(i32, f32)>,
     form_amount: String,
     form_date: i64,
     new_transaction_amount: f32,
@@ -81,6 +181,33 @@ pub struct Transactions {
     form_edit_selectected_category: Option<usize>,
     form_edit_selected_bank_account: Option<usize>,
     form_edit_note: String,
+    form_edit_payee: String,
+    form_template_name: String,
+    templates: Vec<TransactionTemplate>,
+    show_quick_add: bool,
+    selection_mode: bool,
+    selected: HashSet<i32>,
+    bulk_category: Option<usize>,
+    bulk_account: Option<usize>,
+    bulk_tag: String,
+    bulk_days: String,
+    /// Undoes the last bulk edit.
+    bulk_undo: Option<BulkUndo>,
+    show_filters: bool,
+    filter_account: Option<i32>,
+    filter_category: Option<i32>,
+    filter_is_expense: Option<bool>,
+    filter_min_amount: String,
+    filter_max_amount: String,
+    filter_dates: FilterDates,
+    filter_date_from: i64,
+    filter_date_to: i64,
+    filter_text: String,
+    filter_account_options: Vec<String>,
+    filter_category_options: Vec<String>,
+    filter_kind_options: Vec<String>,
+    filter_date_options: Vec<String>,
+    filter_name: String,
 }
 
 impl Default for Transactions {
@@ -90,9 +217,6 @@ impl Default for Transactions {
 
         let now = Local::now();
         let (start_date, end_date) = get_month_date_range(now.year(), now.month());
-        let transactions = store
-            .get_money_transactions_date_range(&start_date, &end_date)
-            .unwrap_or_else(|_| vec![]);
         let transfers = store
             .get_transfers_date_range(&start_date, &end_date)
             .unwrap_or_else(|_| vec![]);
@@ -103,7 +227,10 @@ impl Default for Transactions {
             .filter(|c| !c.is_income)
             .cloned()
             .collect();
-        Self {
+        let accounts = store.get_accounts().unwrap_or_else(|_| vec![]);
+        let filter_account_options = filter_options(fl!("all-accounts"), &accounts);
+        let filter_category_options = filter_options(fl!("all-categories"), &all_categories);
+        let mut page = Self {
             month_names: vec![
                 fl!("month-1"),  // January
                 fl!("month-2"),  // February
@@ -122,15 +249,25 @@ impl Default for Transactions {
             add_transaction_view: false,
             all_categories,
             categories,
-            accounts: store.get_accounts().unwrap_or_else(|_| vec![]),
+            accounts,
             form_transaction_type: widget::segmented_button::Model::builder()
                 .insert(|b| b.text(fl!("expense")).data(1u16).activate())
                 .insert(|b| b.text(fl!("income")).data(2u16))
                 .build(),
             form_note: String::default(),
+            form_payee: String::default(),
+            category_classifier: CategoryClassifier::default(),
+            category_suggestion: None,
             form_selectected_category: Some(0),
             form_selected_bank_account: Some(0),
-            transactions,
+            transactions: vec![],
+            transaction_count: 0,
+            transaction_totals: (0., 0.),
+            transaction_tags: HashMap::new(),
+            list_rows: vec![],
+            list_id: scrollable::Id::unique(),
+            list_scroll: 0.,
+            list_viewport: 800.,
             transfers,
             form_amount: String::default(),
             form_date: Utc::now().timestamp(),
@@ -157,19 +294,50 @@ impl Default for Transactions {
             form_edit_selectected_category: Some(0),
             form_edit_selected_bank_account: Some(0),
             form_edit_note: String::default(),
-        }
+            form_edit_payee: String::default(),
+            form_template_name: String::default(),
+            templates: vec![],
+            show_quick_add: false,
+            selection_mode: false,
+            selected: HashSet::new(),
+            bulk_category: None,
+            bulk_account: None,
+            bulk_tag: String::default(),
+            bulk_days: String::default(),
+            bulk_undo: None,
+            show_filters: false,
+            filter_account: None,
+            filter_category: None,
+            filter_is_expense: None,
+            filter_min_amount: String::default(),
+            filter_max_amount: String::default(),
+            filter_dates: FilterDates::default(),
+            filter_date_from: Utc::now().timestamp(),
+            filter_date_to: Utc::now().timestamp(),
+            filter_text: String::default(),
+            filter_account_options,
+            filter_category_options,
+            filter_kind_options: vec![fl!("all-types"), fl!("income"), fl!("expense")],
+            filter_date_options: filter_date_options(),
+            filter_name: String::default(),
+        };
+        page.reload_list(&mut store);
+        page
     }
 }
 
 impl Transactions {
     pub fn view<'a>(&'a self) -> Element<'a, TransactionMessage> {
-        let container = widget::container(if self.add_transaction_view {
-            self.new_transaction_view()
+        if self.add_transaction_view {
+            let container =
+                widget::container(self.new_transaction_view()).padding(Padding::new(15.));
+            widget::scrollable(container).into()
         } else {
-            self.header_view()
-        })
-        .padding(Padding::new(15.));
-        widget::scrollable(container).into()
+            // The lists below the header scroll on their own
+            widget::container(self.header_view())
+                .padding(Padding::new(15.))
+                .into()
+        }
     }
 
     pub fn header_view<'a>(&'a self) -> Element<'a, TransactionMessage> {
@@ -184,9 +352,50 @@ impl Transactions {
                     .push(
                         widget::column()
                             .push(
-                                widget::button::text(fl!("add-transaction"))
-                                    .on_press(TransactionMessage::AddTransaction)
-                                    .class(widget::button::ButtonClass::Suggested),
+                                widget::row()
+                                    .push_maybe((!self.templates.is_empty()).then(|| {
+                                        widget::row()
+                                            .push(
+                                                widget::button::text(fl!("quick-add"))
+                                                    .on_press(TransactionMessage::ToggleQuickAdd)
+                                                    .class(widget::button::ButtonClass::Standard),
+                                            )
+                                            .push(Space::with_width(10))
+                                    }))
+                                    .push_maybe(self.bulk_undo.as_ref().map(|_| {
+                                        widget::row()
+                                            .push(
+                                                widget::button::text(fl!("undo"))
+                                                    .on_press(TransactionMessage::UndoBulkEdit)
+                                                    .class(widget::button::ButtonClass::Standard),
+                                            )
+                                            .push(Space::with_width(10))
+                                    }))
+                                    .push(
+                                        widget::button::text(if self.selection_mode {
+                                            fl!("done")
+                                        } else {
+                                            fl!("select")
+                                        })
+                                        .on_press(TransactionMessage::ToggleSelectionMode)
+                                        .class(widget::button::ButtonClass::Standard),
+                                    )
+                                    .push(Space::with_width(10))
+                                    .push(
+                                        widget::button::text(if self.show_filters {
+                                            fl!("hide-filters")
+                                        } else {
+                                            fl!("filter")
+                                        })
+                                        .on_press(TransactionMessage::ToggleFilterView)
+                                        .class(widget::button::ButtonClass::Standard),
+                                    )
+                                    .push(Space::with_width(10))
+                                    .push(
+                                        widget::button::text(fl!("add-transaction"))
+                                            .on_press(TransactionMessage::AddTransaction)
+                                            .class(widget::button::ButtonClass::Suggested),
+                                    ),
                             )
                             .width(Length::Fill)
                             .align_x(Alignment::End),
@@ -195,7 +404,41 @@ impl Transactions {
             .width(Length::Fill)
             .align_x(Alignment::Start);
 
-        element = element.push(
+        if self.show_quick_add {
+            element = element
+                .push(Space::with_height(10))
+                .push(self.quick_add_view())
+                .push(Space::with_height(10));
+        }
+
+        if self.selection_mode {
+            element = element
+                .push(Space::with_height(10))
+                .push(self.bulk_edit_view())
+                .push(Space::with_height(10));
+        }
+
+        if self.show_filters {
+            element = element
+                .push(Space::with_height(10))
+                .push(self.filter_view())
+                .push(Space::with_height(10));
+        }
+
+        element = element.push(if self.filter_dates != FilterDates::ViewedMonth {
+            let (first, last) = self.view_range();
+            widget::column()
+                .push(
+                    widget::container(widget::text::text(format!(
+                        "{} - {}",
+                        first.format("%d-%m-%Y"),
+                        last.format("%d-%m-%Y")
+                    )))
+                    .padding(Padding::from(7)),
+                )
+                .align_x(Alignment::Center)
+                .width(Length::Fill)
+        } else {
             widget::column()
                 .push(
                     widget::row()
@@ -222,8 +465,9 @@ impl Transactions {
                         ),
                 )
                 .align_x(Alignment::Center)
-                .width(Length::Fill),
-        );
+                .width(Length::Fill)
+        });
+        element = element.push(self.totals_view());
 
         element = element.push(Space::with_height(10));
         element = element.push(
@@ -233,134 +477,610 @@ impl Transactions {
 
         element = element.push(match self.view_selection.active_data() {
             Some(ViewItem::Transactions) => self.transactions_view(),
-            Some(ViewItem::Transfers) => self.transfers_view(),
+            Some(ViewItem::Transfers) => widget::scrollable(self.transfers_view())
+                .height(Length::Fill)
+                .into(),
             _ => self.transactions_view(),
         });
 
         element.into()
     }
 
-    pub fn transactions_view<'a>(&'a self) -> Element<'a, TransactionMessage> {
-        let mut element = widget::column();
-
-        if !self.transactions.is_empty() {
-            let mut last_date: NaiveDateTime = NaiveDate::from_ymd(1970, 1, 1).and_hms(0, 0, 0);
-
-            for t in &self.transactions {
-                let mut date_row: Option<Element<'a, TransactionMessage>> = None;
-                if t.transaction_date.date().ne(&last_date.date()) {
-                    let month = t.transaction_date.month();
+    fn filter_view<'a>(&'a self) -> Element<'a, TransactionMessage> {
+        let selected_account = match self.filter_account {
+            Some(account_id) => self
+                .accounts
+                .iter()
+                .position(|a| a.id == account_id)
+                .map(|p| p + 1),
+            None => Some(0),
+        };
+        let selected_category = match self.filter_category {
+            Some(category_id) => self
+                .all_categories
+                .iter()
+                .position(|c| c.id == category_id)
+                .map(|p| p + 1),
+            None => Some(0),
+        };
+        let selected_dates = match self.filter_dates {
+            FilterDates::ViewedMonth => 0,
+            FilterDates::Period(period) => {
+                1 + FilterPeriod::all()
+                    .iter()
+                    .position(|p| *p == period)
+                    .unwrap_or_default()
+            }
+            FilterDates::Custom => self.filter_date_options.len() - 1,
+        };
+        let selected_kind = match self.filter_is_expense {
+            None => 0,
+            Some(false) => 1,
+            Some(true) => 2,
+        };
 
-                    date_row = Some(
-                        widget::row()
-                            .push(widget::text::title4(format!(
-                                "{} {}",
-                                t.transaction_date.day().to_string(),
-                                self.month_names[month as usize - 1]
-                            )))
-                            .into(),
-                    );
-                    last_date = t.transaction_date.clone();
-                }
-                element = element.push_maybe(date_row);
-                let container = widget::container(
-                    if self.edit_transaction.is_some() && self.edit_transaction.unwrap() == t.id {
-                        widget::row().push(self.edit_transaction_view(&t))
-                    } else {
-                        widget::row()
+        let content = widget::column()
+            .push(
+                widget::row()
+                    .push(
+                        widget::column()
+                            .push(widget::text::text(fl!("bank-account")))
+                            .push(widget::dropdown(
+                                &self.filter_account_options,
+                                selected_account,
+                                TransactionMessage::FilterAccountChanged,
+                            ))
+                            .width(Length::Fill),
+                    )
+                    .push(Space::with_width(10))
+                    .push(
+                        widget::column()
+                            .push(widget::text::text(fl!("category")))
+                            .push(widget::dropdown(
+                                &self.filter_category_options,
+                                selected_category,
+                                TransactionMessage::FilterCategoryChanged,
+                            ))
+                            .width(Length::Fill),
+                    )
+                    .push(Space::with_width(10))
+                    .push(
+                        widget::column()
+                            .push(widget::text::text(fl!("transaction-type")))
+                            .push(widget::dropdown(
+                                &self.filter_kind_options,
+                                Some(selected_kind),
+                                TransactionMessage::FilterKindChanged,
+                            ))
+                            .width(Length::Fill),
+                    ),
+            )
+            .push(Space::with_height(5))
+            .push(
+                widget::row()
+                    .push(
+                        widget::column()
+                            .push(widget::text::text(fl!("min-amount")))
                             .push(
-                                widget::column()
-                                    .push(
-                                        widget::row()
-                                            .push(
-                                                widget::text::text(format!(
-                                                    "{}: {}{} {}",
-                                                    fl!("amount"),
-                                                    if t.is_expense { "-" } else { "+" },
-                                                    t.amount,
-                                                    self.currency_symbol
-                                                ))
-                                                .width(Length::Fill),
-                                            )
-                                            .push(
-                                                widget::text::text(format!(
-                                                    "{}: {}",
-                                                    fl!("category"),
-                                                    self.categories
-                                                        .iter()
-                                                        .find(|c| c.id == t.transaction_category)
-                                                        .map(|c| c.name.clone())
-                                                        .unwrap_or_else(|| fl!("not-found"))
-                                                ))
-                                                .width(Length::Fill),
-                                            )
-                                            .push(
-                                                widget::text::text(format!(
-                                                    "{}: {}",
-                                                    fl!("date"),
-                                                    Local
-                                                        .from_utc_datetime(&t.transaction_date)
-                                                        .format("%d-%m-%Y %H:%M")
-                                                        .to_string()
-                                                ))
-                                                .width(Length::Fill),
-                                            )
-                                            .width(Length::Fill),
-                                    )
-                                    .push(Space::with_height(5))
-                                    .push_maybe(if !t.description.is_empty() {
-                                        Some(widget::row().push(widget::text::text(format!(
-                                            "{}: {}",
-                                            fl!("note"),
-                                            t.description
-                                        ))))
-                                    } else {
-                                        None
-                                    })
-                                    .width(Length::Fill),
+                                text_input("0", &self.filter_min_amount)
+                                    .on_input(TransactionMessage::FilterMinAmountChanged),
+                            )
+                            .width(Length::Fill),
+                    )
+                    .push(Space::with_width(10))
+                    .push(
+                        widget::column()
+                            .push(widget::text::text(fl!("max-amount")))
+                            .push(
+                                text_input("0", &self.filter_max_amount)
+                                    .on_input(TransactionMessage::FilterMaxAmountChanged),
                             )
+                            .width(Length::Fill),
+                    )
+                    .push(Space::with_width(10))
+                    .push(
+                        widget::column()
+                            .push(widget::text::text(fl!("text")))
                             .push(
-                                widget::column().push(
-                                    widget::button::icon(widget::icon::from_name("edit-symbolic"))
-                                        .on_press(TransactionMessage::EditTransaction(t.id)),
-                                ),
+                                text_input(fl!("search-placeholder"), &self.filter_text)
+                                    .on_input(TransactionMessage::FilterTextChanged),
                             )
-                    },
+                            .width(Length::Fill),
+                    ),
+            )
+            .push(Space::with_height(5))
+            .push(widget::text::text(fl!("dates")))
+            .push(widget::dropdown(
+                &self.filter_date_options,
+                Some(selected_dates),
+                TransactionMessage::FilterDatesChanged,
+            ))
+            .push_maybe(if self.filter_dates == FilterDates::Custom {
+                Some(
+                    widget::column()
+                        .push(widget::text::text(fl!("from")))
+                        .push(date_picker(
+                            self.filter_date_from,
+                            TransactionMessage::FilterDateFromChanged,
+                        ))
+                        .push(widget::text::text(fl!("to")))
+                        .push(date_picker(
+                            self.filter_date_to,
+                            TransactionMessage::FilterDateToChanged,
+                        )),
                 )
-                .width(Length::Fill)
-                .padding(Padding::new(10.))
-                .class(cosmic::theme::Container::Card);
+            } else {
+                None
+            })
+            .push(Space::with_height(5))
+            .push(
+                widget::row()
+                    .push(
+                        widget::button::text(fl!("clear-filter"))
+                            .on_press(TransactionMessage::ClearFilter)
+                            .class(widget::button::ButtonClass::Standard),
+                    )
+                    .push(Space::with_width(Length::Fill))
+                    .push(
+                        text_input(fl!("filter-name"), &self.filter_name)
+                            .on_input(TransactionMessage::FilterNameChanged)
+                            .width(Length::Fixed(200.)),
+                    )
+                    .push(Space::with_width(10))
+                    .push(
+                        widget::button::text(fl!("save-filter"))
+                            .on_press_maybe(
+                                (!self.filter_name.trim().is_empty())
+                                    .then_some(TransactionMessage::SaveFilter),
+                            )
+                            .class(widget::button::ButtonClass::Suggested),
+                    )
+                    .align_y(Alignment::Center),
+            );
 
-                element = element.push(container).push(Space::with_height(10))
+        widget::container(content)
+            .width(Length::Fill)
+            .padding(Padding::new(10.))
+            .class(cosmic::theme::Container::Card)
+            .into()
+    }
+
+    /// Income, expense and net of the transactions currently listed, so the
+    /// figures follow the active filter.
+    fn totals_view<'a>(&'a self) -> Element<'a, TransactionMessage> {
+        let (income, expense) = self.transaction_totals;
+
+        widget::row()
+            .push(
+                widget::text::text(format!(
+                    "{}: +{:.2} {}",
+                    fl!("income"),
+                    income,
+                    self.currency_symbol
+                ))
+                .width(Length::Fill),
+            )
+            .push(
+                widget::text::text(format!(
+                    "{}: -{:.2} {}",
+                    fl!("expense"),
+                    expense,
+                    self.currency_symbol
+                ))
+                .width(Length::Fill),
+            )
+            .push(
+                widget::text::text(format!(
+                    "{}: {:.2} {}",
+                    fl!("net"),
+                    income - expense,
+                    self.currency_symbol
+                ))
+                .width(Length::Fill),
+            )
+            .into()
+    }
+
+    pub fn transactions_view<'a>(&'a self) -> Element<'a, TransactionMessage> {
+        let editing = self
+            .edit_transaction
+            .and_then(|transaction_id| self.transactions.iter().find(|t| t.id == transaction_id));
+        if let Some(t) = editing {
+            return widget::scrollable(
+                widget::container(self.edit_transaction_view(t))
+                    .width(Length::Fill)
+                    .padding(Padding::new(10.))
+                    .class(cosmic::theme::Container::Card),
+            )
+            .height(Length::Fill)
+            .into();
+        }
+
+        if self.transactions.is_empty() {
+            return widget::text::text(fl!("no-elements")).into();
+        }
+
+        // Only rows around the viewport are built, the rest of the list is
+        // stood in for by empty space of the same height.
+        let top = (self.list_scroll - OVERSCAN).max(0.);
+        let bottom = self.list_scroll + self.list_viewport + OVERSCAN;
+        let first = self
+            .list_rows
+            .partition_point(|row| row.top + row.height() <= top);
+        let last = self.list_rows.partition_point(|row| row.top < bottom);
+        let loaded_height = self.loaded_height();
+        let unloaded_height = self
+            .transaction_count
+            .saturating_sub(self.transactions.len()) as f32
+            * (ROW_HEIGHT + ROW_SPACING);
+
+        let mut element = widget::column().push(Space::with_height(
+            self.list_rows
+                .get(first)
+                .map(|row| row.top)
+                .unwrap_or(loaded_height),
+        ));
+        for row in &self.list_rows[first..last] {
+            element = element.push(match row.kind {
+                ListRowKind::Day(day) => self.day_header_view(day),
+                ListRowKind::Transaction(index) => {
+                    self.transaction_row_view(&self.transactions[index])
+                }
+            });
+        }
+        let rendered_bottom = self
+            .list_rows
+            .get(last)
+            .map(|row| row.top)
+            .unwrap_or(loaded_height);
+        element = element.push(Space::with_height(
+            loaded_height - rendered_bottom + unloaded_height,
+        ));
+
+        widget::scrollable(element)
+            .id(self.list_id.clone())
+            .on_scroll(|viewport| {
+                TransactionMessage::ListScrolled(
+                    viewport.absolute_offset().y,
+                    viewport.bounds().height,
+                )
+            })
+            .height(Length::Fill)
+            .into()
+    }
+
+    fn day_header_view<'a>(&'a self, day: NaiveDate) -> Element<'a, TransactionMessage> {
+        widget::container(widget::text::title4(format!(
+            "{} {}",
+            day.day().to_string(),
+            self.month_names[day.month() as usize - 1]
+        )))
+        .height(Length::Fixed(DAY_HEADER_HEIGHT))
+        .align_y(Vertical::Center)
+        .into()
+    }
+
+    fn bulk_edit_view<'a>(&'a self) -> Element<'a, TransactionMessage> {
+        let has_selection = !self.selected.is_empty();
+        widget::container(
+            widget::column()
+                .push(
+                    widget::row()
+                        .push(
+                            widget::text::title4(fl!(
+                                "selected-transactions",
+                                count = self.selected.len()
+                            ))
+                            .width(Length::Fill),
+                        )
+                        .push(
+                            widget::button::text(fl!("select-all"))
+                                .on_press(TransactionMessage::SelectAll)
+                                .class(widget::button::ButtonClass::Standard),
+                        )
+                        .align_y(Alignment::Center),
+                )
+                .push(Space::with_height(10))
+                .push(
+                    widget::row()
+                        .push(
+                            widget::column()
+                                .push(widget::text::text(fl!("category")))
+                                .push(
+                                    widget::row()
+                                        .push(widget::dropdown(
+                                            &self.all_categories,
+                                            self.bulk_category,
+                                            TransactionMessage::BulkCategoryChanged,
+                                        ))
+                                        .push(Space::with_width(5))
+                                        .push(
+                                            widget::button::text(fl!("apply")).on_press_maybe(
+                                                (has_selection && self.bulk_category.is_some())
+                                                    .then_some(TransactionMessage::BulkSetCategory),
+                                            ),
+                                        )
+                                        .align_y(Alignment::Center),
+                                )
+                                .width(Length::Fill),
+                        )
+                        .push(Space::with_width(10))
+                        .push(
+                            widget::column()
+                                .push(widget::text::text(fl!("bank-account")))
+                                .push(
+                                    widget::row()
+                                        .push(widget::dropdown(
+                                            &self.accounts,
+                                            self.bulk_account,
+                                            TransactionMessage::BulkAccountChanged,
+                                        ))
+                                        .push(Space::with_width(5))
+                                        .push(
+                                            widget::button::text(fl!("apply")).on_press_maybe(
+                                                (has_selection && self.bulk_account.is_some())
+                                                    .then_some(TransactionMessage::BulkSetAccount),
+                                            ),
+                                        )
+                                        .align_y(Alignment::Center),
+                                )
+                                .width(Length::Fill),
+                        ),
+                )
+                .push(Space::with_height(10))
+                .push(
+                    widget::row()
+                        .push(
+                            widget::column()
+                                .push(widget::text::text(fl!("tag")))
+                                .push(
+                                    widget::row()
+                                        .push(
+                                            text_input(fl!("tag"), &self.bulk_tag)
+                                                .on_input(TransactionMessage::BulkTagChanged),
+                                        )
+                                        .push(Space::with_width(5))
+                                        .push(
+                                            widget::button::text(fl!("add-tag")).on_press_maybe(
+                                                (has_selection && !self.bulk_tag.trim().is_empty())
+                                                    .then_some(TransactionMessage::BulkAddTag),
+                                            ),
+                                        )
+                                        .align_y(Alignment::Center),
+                                )
+                                .width(Length::Fill),
+                        )
+                        .push(Space::with_width(10))
+                        .push(
+                            widget::column()
+                                .push(widget::text::text(fl!("shift-days")))
+                                .push(
+                                    widget::row()
+                                        .push(
+                                            text_input("0", &self.bulk_days)
+                                                .on_input(TransactionMessage::BulkDaysChanged),
+                                        )
+                                        .push(Space::with_width(5))
+                                        .push(
+                                            widget::button::text(fl!("shift-date")).on_press_maybe(
+                                                (has_selection
+                                                    && self.bulk_days.parse::<i64>().is_ok())
+                                                .then_some(TransactionMessage::BulkShiftDate),
+                                            ),
+                                        )
+                                        .align_y(Alignment::Center),
+                                )
+                                .width(Length::Fill),
+                        ),
+                )
+                .push(Space::with_height(10))
+                .push(
+                    widget::button::text(fl!("delete-selected"))
+                        .on_press_maybe(has_selection.then_some(TransactionMessage::BulkDelete))
+                        .class(widget::button::ButtonClass::Destructive),
+                ),
+        )
+        .padding(10)
+        .width(Length::Fill)
+        .class(cosmic::theme::Container::Card)
+        .into()
+    }
+
+    /// Applies a change to the selected transactions, keeping what is needed
+    /// to undo it.
+    fn apply_bulk_edit(&mut self, edit: BulkEdit) -> Task<AppMessage> {
+        let ids: Vec<i32> = self.selected.iter().copied().collect();
+        let mut store = STORE.lock().unwrap();
+        match store.bulk_edit(&ids, edit) {
+            Ok(undo) => {
+                let count = undo.transactions.len();
+                self.bulk_undo = Some(undo);
+                self.selected.clear();
+                Task::batch(vec![
+                    Task::perform(async {}, |_| AppMessage::UpdateAllPages),
+                    Task::perform(async {}, move |_| {
+                        AppMessage::ShowToast(fl!("bulk-edit-done", count = count))
+                    }),
+                ])
+            }
+            Err(e) => {
+                log::error!("error editing transactions: {}", e);
+                Task::perform(async {}, |_| AppMessage::ShowToast(fl!("bulk-edit-error")))
             }
-        } else {
-            element = element.push(widget::text::text(fl!("no-elements")))
         }
+    }
 
-        element.into()
+    fn quick_add_view<'a>(&'a self) -> Element<'a, TransactionMessage> {
+        let mut col = widget::column()
+            .push(widget::text::title4(fl!("quick-add")))
+            .push(Space::with_height(5))
+            .push(widget::text::text(fl!("quick-add-desc")))
+            .push(Space::with_height(10));
+
+        for template in &self.templates {
+            col = col
+                .push(
+                    widget::row()
+                        .push(
+                            widget::button::text(format!(
+                                "{}  {}{} {}",
+                                template.template_name,
+                                if template.is_expense { "-" } else { "+" },
+                                template.amount,
+                                self.currency_symbol
+                            ))
+                            .on_press(TransactionMessage::QuickAdd(template.id))
+                            .class(widget::button::ButtonClass::Suggested),
+                        )
+                        .push(Space::with_width(10))
+                        .push(
+                            widget::button::icon(widget::icon::from_name("edit-delete-symbolic"))
+                                .on_press(TransactionMessage::DeleteTemplate(template.id)),
+                        )
+                        .align_y(Alignment::Center),
+                )
+                .push(Space::with_height(5));
+        }
+
+        widget::container(col)
+            .padding(10)
+            .width(Length::Fill)
+            .class(cosmic::theme::Container::Card)
+            .into()
+    }
+
+    fn transaction_row_view<'a>(
+        &'a self,
+        t: &'a MoneyTransaction,
+    ) -> Element<'a, TransactionMessage> {
+        let container = widget::container(
+            widget::row()
+                .push(
+                    widget::column()
+                        .push(
+                            widget::row()
+                                .push(
+                                    widget::text::text(format!(
+                                        "{}: {}{} {}",
+                                        fl!("amount"),
+                                        if t.is_expense { "-" } else { "+" },
+                                        t.amount,
+                                        self.currency_symbol
+                                    ))
+                                    .width(Length::Fill),
+                                )
+                                .push(
+                                    widget::text::text(format!(
+                                        "{}: {}",
+                                        fl!("category"),
+                                        self.categories
+                                            .iter()
+                                            .find(|c| c.id == t.transaction_category)
+                                            .map(|c| c.name.clone())
+                                            .unwrap_or_else(|| fl!("not-found"))
+                                    ))
+                                    .width(Length::Fill),
+                                )
+                                .push(
+                                    widget::text::text(format!(
+                                        "{}: {}",
+                                        fl!("date"),
+                                        Local
+                                            .from_utc_datetime(&t.transaction_date)
+                                            .format("%d-%m-%Y %H:%M")
+                                            .to_string()
+                                    ))
+                                    .width(Length::Fill),
+                                )
+                                .push(
+                                    widget::text::text(
+                                        if t.transaction_date > Utc::now().naive_utc() {
+                                            fl!("scheduled")
+                                        } else {
+                                            status_title(t.status())
+                                        },
+                                    )
+                                    .width(Length::Fill),
+                                )
+                                .width(Length::Fill),
+                        )
+                        .push(Space::with_height(5))
+                        .push_maybe(t.payee.as_ref().map(|payee| {
+                            widget::row().push(widget::text::text(format!(
+                                "{}: {}",
+                                fl!("payee"),
+                                payee
+                            )))
+                        }))
+                        .push_maybe(if !t.description.is_empty() {
+                            Some(widget::row().push(widget::text::text(format!(
+                                "{}: {}",
+                                fl!("note"),
+                                t.description
+                            ))))
+                        } else {
+                            None
+                        })
+                        .push_maybe(self.transaction_tags.get(&t.id).map(|tags| {
+                            widget::row().push(widget::text::text(format!(
+                                "{}: {}",
+                                fl!("tags"),
+                                tags.join(", ")
+                            )))
+                        }))
+                        .width(Length::Fill),
+                )
+                .push(
+                    widget::column().push(if t.status() == TransactionStatus::Reconciled {
+                        Element::from(widget::icon::from_name("changes-prevent-symbolic").icon())
+                    } else if self.selection_mode {
+                        let id = t.id;
+                        widget::checkbox("", self.selected.contains(&id))
+                            .on_toggle(move |checked| {
+                                TransactionMessage::ToggleSelected(id, checked)
+                            })
+                            .into()
+                    } else {
+                        widget::button::icon(widget::icon::from_name("edit-symbolic"))
+                            .on_press(TransactionMessage::EditTransaction(t.id))
+                            .into()
+                    }),
+                ),
+        )
+        .width(Length::Fill)
+        .padding(Padding::new(10.))
+        .height(Length::Fixed(ROW_HEIGHT))
+        .clip(true)
+        .class(cosmic::theme::Container::Card);
+
+        widget::column()
+            .push(container)
+            .push(Space::with_height(ROW_SPACING))
+            .into()
     }
 
     pub fn transfers_view<'a>(&'a self) -> Element<'a, TransactionMessage> {
         let mut element = widget::column();
 
         if !self.transfers.is_empty() {
-            let mut last_date: NaiveDateTime = NaiveDate::from_ymd(1970, 1, 1).and_hms(0, 0, 0);
+            let mut last_date: Option<NaiveDate> = None;
 
             for t in &self.transfers {
                 let mut date_row: Option<Element<'a, TransactionMessage>> = None;
-                if t.transfer_date.date().ne(&last_date.date()) {
-                    let month = t.transfer_date.month();
+                let day = local_date(&Local, &t.transfer_date);
+                if last_date != Some(day) {
+                    let month = day.month();
 
                     date_row = Some(
                         widget::row()
                             .push(widget::text::title4(format!(
                                 "{} {}",
-                                t.transfer_date.day().to_string(),
+                                day.day().to_string(),
                                 self.month_names[month as usize - 1]
                             )))
                             .into(),
                     );
-                    last_date = t.transfer_date.clone();
+                    last_date = Some(day);
                 }
                 element = element.push_maybe(date_row);
                 let container = widget::container(
@@ -417,6 +1137,15 @@ impl Transactions {
                                                 .to_string()
                                         )),
                                 ))
+                                .push(widget::column().width(Length::Fill).push(
+                                    widget::text::text(
+                                        if t.transfer_date > Utc::now().naive_utc() {
+                                            fl!("scheduled")
+                                        } else {
+                                            transfer_status_title(t, self.filter_account)
+                                        },
+                                    ),
+                                ))
                                 .width(Length::Fill),
                         )
                         .push(Space::with_height(5))
@@ -490,7 +1219,16 @@ impl Transactions {
                                 &self.categories,
                                 self.form_selectected_category,
                                 TransactionMessage::FormCategoryChanged,
-                            )),
+                            ))
+                            .push_maybe(self.suggested_category().map(|(category, confidence)| {
+                                widget::button::text(fl!(
+                                    "suggested-category",
+                                    category = category.name.clone(),
+                                    confidence = (confidence * 100.).round() as i32
+                                ))
+                                .on_press(TransactionMessage::UseSuggestedCategory)
+                                .class(widget::button::ButtonClass::Text)
+                            })),
                     )
                     .push(Space::with_width(Length::from(20)))
                     .push(
@@ -503,6 +1241,13 @@ impl Transactions {
                             )),
                     ),
             )
+            .push(
+                column().push(widget::text::text(fl!("payee"))).push(
+                    text_input(fl!("payee"), &self.form_payee)
+                        .width(Length::Fill)
+                        .on_input(TransactionMessage::FormPayeeChanged),
+                ),
+            )
             .push(
                 column().push(widget::text::text(fl!("note"))).push(
                     text_input(fl!("note"), &self.form_note)
@@ -585,6 +1330,13 @@ impl Transactions {
                             )),
                     ),
             )
+            .push(
+                column().push(widget::text::text(fl!("payee"))).push(
+                    text_input(fl!("payee"), &self.form_edit_payee)
+                        .width(Length::Fill)
+                        .on_input(TransactionMessage::FormEditPayeeChanged),
+                ),
+            )
             .push(
                 column().push(widget::text::text(fl!("note"))).push(
                     text_input(fl!("note"), &self.form_edit_note)
@@ -612,6 +1364,25 @@ impl Transactions {
                             .on_press(TransactionMessage::DeleteTransaction(transaction.id))
                             .class(widget::button::ButtonClass::Destructive),
                     ),
+            )
+            .push(widget::vertical_space().height(Length::from(10)))
+            .push(
+                widget::row()
+                    .push(
+                        text_input(fl!("template-name"), &self.form_template_name)
+                            .width(Length::Fill)
+                            .on_input(TransactionMessage::TemplateNameChanged),
+                    )
+                    .push(widget::horizontal_space().width(Length::from(10)))
+                    .push(
+                        widget::button::text(fl!("save-as-template"))
+                            .on_press_maybe(
+                                (!self.form_template_name.trim().is_empty())
+                                    .then_some(TransactionMessage::SaveAsTemplate(transaction.id)),
+                            )
+                            .class(widget::button::ButtonClass::Standard),
+                    )
+                    .align_y(Alignment::Center),
             );
 
         element.into()
@@ -624,12 +1395,10 @@ impl Transactions {
                 log::info!("updating page");
                 let mut store = STORE.lock().unwrap();
                 let config = Config::load();
-                let (start_date, end_date) = get_month_date_range(self.view_year, self.view_month);
+                let (start_date, end_date) = self.view_range();
 
                 let currency_symbol = store.get_currency_symbol_by_id(config.1.currency_id);
-                self.transactions = store
-                    .get_money_transactions_date_range(&start_date, &end_date)
-                    .unwrap_or_else(|_| vec![]);
+                self.reload_list(&mut store);
 
                 self.transfers = store
                     .get_transfers_date_range(&start_date, &end_date)
@@ -641,13 +1410,29 @@ impl Transactions {
                     .filter(|c| !c.is_income)
                     .cloned()
                     .collect();
+                self.filter_category_options =
+                    filter_options(fl!("all-categories"), &all_categories);
+                self.all_categories = all_categories;
                 self.form_transaction_type.activate_position(0);
 
                 self.accounts = store.get_accounts().unwrap_or_else(|_| vec![]);
+                self.filter_account_options = filter_options(fl!("all-accounts"), &self.accounts);
+                self.templates = store.get_templates().unwrap_or_else(|_| vec![]);
                 self.currency_symbol = currency_symbol.unwrap_or_else(|_| "USD".to_string());
             }
             TransactionMessage::AddTransaction => {
                 self.add_transaction_view = true;
+                let mut store = STORE.lock().unwrap();
+                let examples = store.get_category_examples().unwrap_or_else(|e| {
+                    log::error!("error loading category examples: {}", e);
+                    vec![]
+                });
+                self.category_classifier = CategoryClassifier::train(
+                    examples
+                        .iter()
+                        .map(|(category_id, text, value)| (*category_id, text.as_str(), *value)),
+                );
+                self.update_category_suggestion();
             }
             TransactionMessage::FormCategoryChanged(selected) => {
                 self.form_selectected_category = Some(selected)
@@ -670,22 +1455,29 @@ impl Transactions {
                     .filter(|c| c.is_income == !is_expense)
                     .cloned()
                     .collect();
+                self.update_category_suggestion();
             }
             TransactionMessage::FormBankAccountChanged(selected) => {
                 self.form_selected_bank_account = Some(selected);
             }
             TransactionMessage::FormNoteChanged(note) => {
                 self.form_note = note;
+                self.update_category_suggestion();
+            }
+            TransactionMessage::FormPayeeChanged(payee) => {
+                self.form_payee = payee;
             }
             TransactionMessage::FormAmountChanged(new_amount) => {
                 if new_amount.is_empty() {
                     self.new_transaction_amount = 0.0;
                     self.form_amount = new_amount;
+                    self.update_category_suggestion();
                 } else {
                     match new_amount.parse::<f32>() {
                         Ok(parsed_amount) => {
                             self.new_transaction_amount = parsed_amount;
                             self.form_amount = new_amount;
+                            self.update_category_suggestion();
                         }
                         Err(_) => {
                             eprintln!("Failed to parse the amount: {}", new_amount);
@@ -719,12 +1511,17 @@ impl Transactions {
                     amount: self.new_transaction_amount,
                     transaction_date: NaiveDateTime::from_timestamp(self.form_date, 0),
                     is_expense,
+                    status: TransactionStatus::Uncleared.into(),
+                    payee: non_empty(&self.form_payee),
+                    external_id: None,
                 };
-                let _ = store.create_money_transaction(&new_transaction);
+                let _ = store.create_money_transaction_with_rules(&new_transaction);
                 commands.push(Task::perform(async {}, |_| AppMessage::UpdateAllPages));
                 self.add_transaction_view = false;
                 self.form_amount = "".to_string();
                 self.form_note = "".to_string();
+                self.form_payee = "".to_string();
+                self.category_suggestion = None;
                 self.form_selectected_category = Some(0);
                 self.form_selected_bank_account = Some(0);
             }
@@ -732,9 +1529,17 @@ impl Transactions {
                 self.add_transaction_view = false;
                 self.form_amount = "".to_string();
                 self.form_note = "".to_string();
+                self.form_payee = "".to_string();
+                self.category_suggestion = None;
                 self.form_selectected_category = Some(0);
                 self.form_selected_bank_account = Some(0);
             }
+            TransactionMessage::UseSuggestedCategory => {
+                if let Some((category_id, _)) = self.category_suggestion {
+                    self.form_selectected_category =
+                        self.categories.iter().position(|c| c.id == category_id);
+                }
+            }
             TransactionMessage::FormDateChanged(date) => {
                 log::info!("form date changed: {:?}", date);
                 self.form_date = date;
@@ -746,9 +1551,7 @@ impl Transactions {
                 } else {
                     self.view_month -= 1;
                 }
-                commands.push(Task::perform(async {}, |_| {
-                    AppMessage::Transactions(TransactionMessage::UpdatePage)
-                }));
+                commands.push(self.reload_from_top());
             }
             TransactionMessage::NextMonth => {
                 if self.view_month == 12 {
@@ -757,20 +1560,20 @@ impl Transactions {
                 } else {
                     self.view_month += 1;
                 }
-                commands.push(Task::perform(async {}, |_| {
-                    AppMessage::Transactions(TransactionMessage::UpdatePage)
-                }));
+                commands.push(self.reload_from_top());
             }
             TransactionMessage::ViewChanged(entity) => {
                 self.view_selection.activate(entity);
             }
             TransactionMessage::EditTransaction(transaction_id) => {
                 self.edit_transaction = Some(transaction_id);
+                self.form_template_name = String::default();
                 match self.transactions.iter().find(|t| t.id == transaction_id) {
                     Some(transaction) => {
                         self.form_edit_amount = transaction.amount.to_string();
                         self.form_edit_date = transaction.transaction_date.timestamp();
                         self.form_edit_note = transaction.description.clone();
+                        self.form_edit_payee = transaction.payee.clone().unwrap_or_default();
                         self.form_edit_selectected_category = self
                             .categories
                             .iter()
@@ -817,6 +1620,9 @@ impl Transactions {
             TransactionMessage::FormEditNoteChanged(note) => {
                 self.form_edit_note = note;
             }
+            TransactionMessage::FormEditPayeeChanged(payee) => {
+                self.form_edit_payee = payee;
+            }
             TransactionMessage::SubmitEditTransaction(transaction_id) => {
                 log::info!("submitting edit transaction");
                 let mut is_expense: bool = true;
@@ -845,22 +1651,488 @@ impl Transactions {
                     amount: self.edit_amout,
                     transaction_date: NaiveDateTime::from_timestamp(self.form_edit_date, 0),
                     is_expense,
+                    payee: non_empty(&self.form_edit_payee),
                 };
                 let mut store = STORE.lock().unwrap();
-                let _ = store.update_transaction(&update_transaction);
+                if let Err(e) = store.update_transaction(&update_transaction) {
+                    commands.push(transaction_error_toast(e));
+                }
                 self.edit_transaction = None;
+                commands.push(self.restore_list_scroll());
                 commands.push(Task::perform(async {}, |_| AppMessage::UpdateAllPages));
             }
             TransactionMessage::CancelEditTransaction => {
                 self.edit_transaction = None;
+                commands.push(self.restore_list_scroll());
             }
             TransactionMessage::DeleteTransaction(transaction_id) => {
                 let mut store = STORE.lock().unwrap();
-                let _ = store.delete_transaction(&transaction_id);
+                if let Err(e) = store.delete_transaction(&transaction_id) {
+                    commands.push(transaction_error_toast(e));
+                }
                 self.edit_transaction = None;
+                commands.push(self.restore_list_scroll());
                 commands.push(Task::perform(async {}, |_| AppMessage::UpdateAllPages));
             }
+            TransactionMessage::ListScrolled(offset, height) => {
+                self.list_scroll = offset;
+                self.list_viewport = height;
+                let mut store = STORE.lock().unwrap();
+                self.load_visible_rows(&mut store);
+            }
+            TransactionMessage::ToggleQuickAdd => {
+                self.show_quick_add = !self.show_quick_add;
+            }
+            TransactionMessage::QuickAdd(template_id) => {
+                if let Some(template) = self.templates.iter().find(|t| t.id == template_id) {
+                    let mut store = STORE.lock().unwrap();
+                    let new_transaction = template.new_transaction(Utc::now().naive_utc());
+                    match store.create_money_transaction_with_rules(&new_transaction) {
+                        Ok(_) => {
+                            self.show_quick_add = false;
+                            commands.push(Task::perform(async {}, |_| AppMessage::UpdateAllPages));
+                        }
+                        Err(e) => log::error!("error adding transaction from template: {}", e),
+                    }
+                }
+            }
+            TransactionMessage::DeleteTemplate(template_id) => {
+                let mut store = STORE.lock().unwrap();
+                if let Err(e) = store.delete_template(template_id) {
+                    log::error!("error deleting template: {}", e);
+                }
+                self.templates.retain(|t| t.id != template_id);
+                self.show_quick_add &= !self.templates.is_empty();
+            }
+            TransactionMessage::TemplateNameChanged(name) => {
+                self.form_template_name = name;
+            }
+            TransactionMessage::SaveAsTemplate(transaction_id) => {
+                let Some(transaction) = self.transactions.iter().find(|t| t.id == transaction_id)
+                else {
+                    return Task::none();
+                };
+                let mut store = STORE.lock().unwrap();
+                match store.create_template(&NewTransactionTemplate::from_transaction(
+                    self.form_template_name.trim().to_string(),
+                    transaction,
+                )) {
+                    Ok(template) => {
+                        self.form_template_name = String::default();
+                        self.templates.push(template);
+                        self.templates
+                            .sort_by(|a, b| a.template_name.cmp(&b.template_name));
+                        commands.push(Task::perform(async {}, |_| {
+                            AppMessage::ShowToast(fl!("template-saved"))
+                        }));
+                    }
+                    Err(e) => {
+                        log::error!("error saving template: {}", e);
+                        commands.push(Task::perform(async {}, |_| {
+                            AppMessage::ShowToast(fl!("template-save-error"))
+                        }));
+                    }
+                }
+            }
+            TransactionMessage::ToggleSelectionMode => {
+                self.selection_mode = !self.selection_mode;
+                self.selected.clear();
+            }
+            TransactionMessage::ToggleSelected(transaction_id, checked) => {
+                if checked {
+                    self.selected.insert(transaction_id);
+                } else {
+                    self.selected.remove(&transaction_id);
+                }
+            }
+            TransactionMessage::SelectAll => {
+                self.selected = self
+                    .transactions
+                    .iter()
+                    .filter(|t| t.status() != TransactionStatus::Reconciled)
+                    .map(|t| t.id)
+                    .collect();
+            }
+            TransactionMessage::BulkCategoryChanged(selected) => {
+                self.bulk_category = Some(selected);
+            }
+            TransactionMessage::BulkAccountChanged(selected) => {
+                self.bulk_account = Some(selected);
+            }
+            TransactionMessage::BulkTagChanged(tag) => {
+                self.bulk_tag = tag;
+            }
+            TransactionMessage::BulkDaysChanged(days) => {
+                if days.is_empty() || days == "-" || days.parse::<i64>().is_ok() {
+                    self.bulk_days = days;
+                }
+            }
+            TransactionMessage::BulkSetCategory => {
+                if let Some(category) = self.bulk_category.and_then(|p| self.all_categories.get(p))
+                {
+                    commands.push(self.apply_bulk_edit(BulkEdit::Category(category.id)));
+                }
+            }
+            TransactionMessage::BulkSetAccount => {
+                if let Some(account) = self.bulk_account.and_then(|p| self.accounts.get(p)) {
+                    commands.push(self.apply_bulk_edit(BulkEdit::Account(account.id)));
+                }
+            }
+            TransactionMessage::BulkAddTag => {
+                let tag = STORE
+                    .lock()
+                    .unwrap()
+                    .get_or_create_tag(self.bulk_tag.trim());
+                match tag {
+                    Ok(tag) => {
+                        self.bulk_tag = String::default();
+                        commands.push(self.apply_bulk_edit(BulkEdit::AddTag(tag.id)));
+                    }
+                    Err(e) => log::error!("error creating tag: {}", e),
+                }
+            }
+            TransactionMessage::BulkShiftDate => {
+                if let Ok(days) = self.bulk_days.parse::<i64>() {
+                    commands.push(self.apply_bulk_edit(BulkEdit::ShiftDays(days)));
+                }
+            }
+            TransactionMessage::BulkDelete => {
+                commands.push(self.apply_bulk_edit(BulkEdit::Delete));
+            }
+            TransactionMessage::UndoBulkEdit => {
+                if let Some(undo) = self.bulk_undo.take() {
+                    let mut store = STORE.lock().unwrap();
+                    match store.undo_bulk_edit(&undo) {
+                        Ok(_) => {
+                            commands.push(Task::perform(async {}, |_| AppMessage::UpdateAllPages))
+                        }
+                        // Later changes win over the undo, which is dropped.
+                        Err(DataStoreError::Outdated) => commands
+                            .push(Task::perform(async {}, |_| {
+                                AppMessage::ShowToast(fl!("bulk-undo-outdated"))
+                            })),
+                        Err(e) => {
+                            log::error!("error undoing bulk edit: {}", e);
+                            self.bulk_undo = Some(undo);
+                        }
+                    }
+                }
+            }
+            TransactionMessage::ToggleFilterView => {
+                self.show_filters = !self.show_filters;
+            }
+            TransactionMessage::FilterAccountChanged(selected) => {
+                self.filter_account = selected
+                    .checked_sub(1)
+                    .and_then(|p| self.accounts.get(p))
+                    .map(|a| a.id);
+                commands.push(self.reload_from_top());
+            }
+            TransactionMessage::FilterCategoryChanged(selected) => {
+                self.filter_category = selected
+                    .checked_sub(1)
+                    .and_then(|p| self.all_categories.get(p))
+                    .map(|c| c.id);
+                commands.push(self.reload_from_top());
+            }
+            TransactionMessage::FilterKindChanged(selected) => {
+                self.filter_is_expense = match selected {
+                    1 => Some(false),
+                    2 => Some(true),
+                    _ => None,
+                };
+                commands.push(self.reload_from_top());
+            }
+            TransactionMessage::FilterMinAmountChanged(value) => {
+                if value.parse::<f32>().is_ok() || value.is_empty() {
+                    self.filter_min_amount = value;
+                    commands.push(self.reload_from_top());
+                }
+            }
+            TransactionMessage::FilterMaxAmountChanged(value) => {
+                if value.parse::<f32>().is_ok() || value.is_empty() {
+                    self.filter_max_amount = value;
+                    commands.push(self.reload_from_top());
+                }
+            }
+            TransactionMessage::FilterDatesChanged(selected) => {
+                self.filter_dates = match selected {
+                    0 => FilterDates::ViewedMonth,
+                    p if p <= FilterPeriod::all().len() => {
+                        FilterDates::Period(FilterPeriod::all()[p - 1])
+                    }
+                    _ => FilterDates::Custom,
+                };
+                commands.push(self.reload_from_top());
+            }
+            TransactionMessage::FilterDateFromChanged(date) => {
+                self.filter_date_from = date;
+                commands.push(self.reload_from_top());
+            }
+            TransactionMessage::FilterDateToChanged(date) => {
+                self.filter_date_to = date;
+                commands.push(self.reload_from_top());
+            }
+            TransactionMessage::FilterTextChanged(text) => {
+                self.filter_text = text;
+                commands.push(self.reload_from_top());
+            }
+            TransactionMessage::ClearFilter => {
+                self.filter_account = None;
+                self.filter_category = None;
+                self.filter_is_expense = None;
+                self.filter_min_amount = String::default();
+                self.filter_max_amount = String::default();
+                self.filter_dates = FilterDates::ViewedMonth;
+                self.filter_text = String::default();
+                commands.push(self.reload_from_top());
+            }
+            TransactionMessage::FilterNameChanged(name) => {
+                self.filter_name = name;
+            }
+            TransactionMessage::SaveFilter => {
+                let mut store = STORE.lock().unwrap();
+                match store.create_saved_filter(self.filter_name.trim(), &self.saved_filter()) {
+                    Ok(_) => {
+                        self.filter_name = String::default();
+                        commands.push(Task::perform(async {}, |_| AppMessage::SavedFiltersChanged));
+                        commands.push(Task::perform(async {}, |_| {
+                            AppMessage::ShowToast(fl!("filter-saved"))
+                        }));
+                    }
+                    Err(e) => {
+                        log::error!("error saving filter: {}", e);
+                        commands.push(Task::perform(async {}, |_| {
+                            AppMessage::ShowToast(fl!("filter-save-error"))
+                        }));
+                    }
+                }
+            }
         }
         Task::batch(commands)
     }
 }
+
+impl Transactions {
+    /// The criteria picked in the filter panel, without the date range.
+    fn filter(&self) -> TransactionFilter {
+        TransactionFilter::default()
+            .accounts(self.filter_account.into_iter().collect())
+            .categories(self.filter_category.into_iter().collect())
+            .expenses(self.filter_is_expense)
+            .amount_range(
+                self.filter_min_amount.parse::<f32>().ok(),
+                self.filter_max_amount.parse::<f32>().ok(),
+            )
+            .text(&self.filter_text)
+    }
+
+    /// The panel's criteria as stored for a saved filter. Periods are kept
+    /// relative, and the viewed month isn't saved at all.
+    fn saved_filter(&self) -> TransactionFilter {
+        let filter = self.filter();
+        match self.filter_dates {
+            FilterDates::ViewedMonth => filter,
+            FilterDates::Period(period) => filter.period(Some(period)),
+            FilterDates::Custom => {
+                filter.date_range(self.filter_date_from(), self.filter_date_to())
+            }
+        }
+    }
+
+    /// Suggests a category for the new transaction from its note and amount,
+    /// among the categories of the selected transaction type.
+    fn update_category_suggestion(&mut self) {
+        let candidates: Vec<i32> = self.categories.iter().map(|c| c.id).collect();
+        self.category_suggestion = self.category_classifier.suggest(
+            &self.form_note,
+            self.new_transaction_amount,
+            &candidates,
+        );
+    }
+
+    /// The suggested category with its confidence, unless it is already the
+    /// selected one.
+    fn suggested_category(&self) -> Option<(&Category, f32)> {
+        let (category_id, confidence) = self.category_suggestion?;
+        let index = self.categories.iter().position(|c| c.id == category_id)?;
+        (self.form_selectected_category != Some(index))
+            .then(|| (&self.categories[index], confidence))
+    }
+
+    /// Reloads the list from the first page, fetching enough rows to fill
+    /// the current scroll position.
+    fn reload_list(&mut self, store: &mut Store) {
+        let filter = self.list_filter();
+        self.transaction_count = store
+            .count_filtered_transactions(&filter)
+            .unwrap_or_default() as usize;
+        self.transaction_totals = store.get_filtered_totals(&filter).unwrap_or_default();
+        self.transactions.clear();
+        self.transaction_tags.clear();
+        self.list_rows.clear();
+        self.load_visible_rows(store);
+    }
+
+    /// Fetches pages until the rows down to just below the viewport are loaded.
+    fn load_visible_rows(&mut self, store: &mut Store) {
+        let filter = self.list_filter();
+        while self.transactions.len() < self.transaction_count
+            && self.loaded_height() < self.list_scroll + self.list_viewport + OVERSCAN
+        {
+            let after = self.transactions.last().map(TransactionCursor::from);
+            let page = match store.get_transactions_page(&filter, after, PAGE_SIZE) {
+                Ok(page) if !page.is_empty() => page,
+                Ok(_) => break,
+                Err(e) => {
+                    log::error!("error loading transactions: {}", e);
+                    break;
+                }
+            };
+            let ids: Vec<i32> = page.iter().map(|t| t.id).collect();
+            match store.get_transaction_tags(&ids) {
+                Ok(tags) => self.transaction_tags.extend(tags),
+                Err(e) => log::error!("error loading transaction tags: {}", e),
+            }
+            for t in page {
+                let day = local_date(&Local, &t.transaction_date);
+                let last_day = self
+                    .transactions
+                    .last()
+                    .map(|last| local_date(&Local, &last.transaction_date));
+                if last_day != Some(day) {
+                    self.push_list_row(ListRowKind::Day(day));
+                }
+                self.push_list_row(ListRowKind::Transaction(self.transactions.len()));
+                self.transactions.push(t);
+            }
+        }
+    }
+
+    fn push_list_row(&mut self, kind: ListRowKind) {
+        let top = self.loaded_height();
+        self.list_rows.push(ListRow { top, kind });
+    }
+
+    fn loaded_height(&self) -> f32 {
+        self.list_rows
+            .last()
+            .map(|row| row.top + row.height())
+            .unwrap_or(0.)
+    }
+
+    fn list_filter(&self) -> TransactionFilter {
+        let (start_date, end_date) = self.view_range();
+        self.filter().date_range(start_date, end_date)
+    }
+
+    /// Moves the list back to the top and reloads it, for when the listed
+    /// transactions change altogether.
+    fn reload_from_top(&mut self) -> Task<AppMessage> {
+        self.list_scroll = 0.;
+        Task::batch(vec![
+            scrollable::scroll_to(self.list_id.clone(), AbsoluteOffset { x: 0., y: 0. }),
+            update_page(),
+        ])
+    }
+
+    /// Puts the list back where it was after the edit form replaced it.
+    fn restore_list_scroll(&self) -> Task<AppMessage> {
+        scrollable::scroll_to(
+            self.list_id.clone(),
+            AbsoluteOffset {
+                x: 0.,
+                y: self.list_scroll,
+            },
+        )
+    }
+
+    fn view_range(&self) -> (NaiveDate, NaiveDate) {
+        match self.filter_dates {
+            FilterDates::ViewedMonth => get_month_date_range(self.view_year, self.view_month),
+            FilterDates::Period(period) => period_range(period, local_today()),
+            FilterDates::Custom => (self.filter_date_from(), self.filter_date_to()),
+        }
+    }
+
+    fn filter_date_from(&self) -> NaiveDate {
+        local_date(
+            &Local,
+            &NaiveDateTime::from_timestamp(self.filter_date_from, 0),
+        )
+    }
+
+    fn filter_date_to(&self) -> NaiveDate {
+        local_date(
+            &Local,
+            &NaiveDateTime::from_timestamp(self.filter_date_to, 0),
+        )
+    }
+}
+
+fn update_page() -> Task<AppMessage> {
+    Task::perform(async {}, |_| {
+        AppMessage::Transactions(TransactionMessage::UpdatePage)
+    })
+}
+
+fn period_title(period: FilterPeriod) -> String {
+    match period {
+        FilterPeriod::ThisMonth => fl!("this-month"),
+        FilterPeriod::LastMonth => fl!("last-month"),
+        FilterPeriod::ThisQuarter => fl!("this-quarter"),
+        FilterPeriod::ThisYear => fl!("this-year"),
+        FilterPeriod::Last30Days => fl!("last-30-days"),
+    }
+}
+
+fn filter_date_options() -> Vec<String> {
+    std::iter::once(fl!("viewed-month"))
+        .chain(FilterPeriod::all().iter().map(|p| period_title(*p)))
+        .chain(std::iter::once(fl!("custom-range")))
+        .collect()
+}
+
+fn status_title(status: TransactionStatus) -> String {
+    match status {
+        TransactionStatus::Uncleared => fl!("status-uncleared"),
+        TransactionStatus::Cleared => fl!("status-cleared"),
+        TransactionStatus::Reconciled => fl!("status-reconciled"),
+    }
+}
+
+/// Status of a transfer in the account filtered on, or in both of its
+/// accounts when they differ.
+fn transfer_status_title(transfer: &AccountTransfer, account_id: Option<i32>) -> String {
+    let from = transfer.status_for(transfer.from_account);
+    let to = transfer.status_for(transfer.to_account);
+    match account_id {
+        Some(account_id) => status_title(transfer.status_for(account_id)),
+        None if from == to => status_title(from),
+        None => format!("{} / {}", status_title(from), status_title(to)),
+    }
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Dropdown labels for a filter, with the "any" option first.
+fn filter_options<T: AsRef<str>>(any: String, items: &[T]) -> Vec<String> {
+    std::iter::once(any)
+        .chain(items.iter().map(|item| item.as_ref().to_string()))
+        .collect()
+}
+
+/// Toast telling why a transaction could not be changed.
+fn transaction_error_toast(error: DataStoreError) -> Task<AppMessage> {
+    let message = match error {
+        DataStoreError::Reconciled => fl!("transaction-reconciled-error"),
+        e => {
+            log::error!("error changing transaction: {}", e);
+            fl!("transaction-change-error")
+        }
+    };
+    Task::perform(async {}, move |_| AppMessage::ShowToast(message))
+}
@@ -1,5 +1,6 @@
 use crate::app::AppMessage;
-use crate::models::{NewAccount, NewCategory};
+use crate::models::{AccountType, NewAccount, NewCategory};
+use crate::synchronization::import::ImportMode;
 use crate::{config::Config, fl, models::Currency, STORE};
 use cosmic::iced::alignment::Horizontal;
 use cosmic::iced::{Alignment, Padding};
@@ -504,6 +505,10 @@ impl Welcome {
                     name: self.form_new_account_name.clone(),
                     initial_balance: balance,
                     account_description: self.form_new_account_description.clone(),
+                    account_type: AccountType::Checking.into(),
+                    group_id: None,
+                    exclude_from_total: false,
+                    exclude_from_statistics: false,
                 };
 
                 self.accounts.push(new_account);
@@ -541,7 +546,9 @@ impl Welcome {
                 commands.push(Task::perform(async {}, |_| AppMessage::UpdateAllPages));
             }
             WelcomeMessage::Import => {
-                commands.push(Task::perform(async {}, |_| AppMessage::Import));
+                commands.push(Task::perform(async {}, |_| {
+                    AppMessage::Import(ImportMode::Replace)
+                }));
             }
             WelcomeMessage::ImportCompleted => {
                 let mut config = Config::load();
@@ -626,11 +633,19 @@ impl Welcome {
                     name: fl!("bank-account"),
                     initial_balance: 0.,
                     account_description: "".to_string(),
+                    account_type: AccountType::Checking.into(),
+                    group_id: None,
+                    exclude_from_total: false,
+                    exclude_from_statistics: false,
                 });
                 self.accounts.push(NewAccount {
                     name: fl!("cash"),
                     initial_balance: 0.,
                     account_description: "".to_string(),
+                    account_type: AccountType::Cash.into(),
+                    group_id: None,
+                    exclude_from_total: false,
+                    exclude_from_statistics: false,
                 });
             }
         }
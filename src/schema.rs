@@ -6,6 +6,17 @@ diesel::table! {
         name -> Text,
         account_description -> Text,
         initial_balance -> Float,
+        account_type -> Integer,
+        group_id -> Nullable<Integer>,
+        exclude_from_total -> Bool,
+        exclude_from_statistics -> Bool,
+    }
+}
+
+diesel::table! {
+    account_group (id) {
+        id -> Integer,
+        group_name -> Text,
     }
 }
 
@@ -17,6 +28,8 @@ diesel::table! {
         transfer_date -> Timestamp,
         description -> Nullable<Text>,
         amount -> Float,
+        status -> Integer,
+        to_status -> Integer,
     }
 }
 
@@ -29,6 +42,23 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    credit_card (id) {
+        id -> Integer,
+        account_id -> Integer,
+        closing_day -> Integer,
+        due_day -> Integer,
+    }
+}
+
+diesel::table! {
+    csv_profile (id) {
+        id -> Integer,
+        profile_name -> Text,
+        definition -> Text,
+    }
+}
+
 diesel::table! {
     currency (id) {
         id -> Integer,
@@ -37,6 +67,48 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    dismissed_duplicate (first_id, second_id) {
+        first_id -> Integer,
+        second_id -> Integer,
+    }
+}
+
+diesel::table! {
+    investment_transaction (id) {
+        id -> Integer,
+        account_id -> Integer,
+        security_id -> Integer,
+        kind -> Integer,
+        quantity -> Float,
+        price -> Float,
+        amount -> Float,
+        transaction_date -> Timestamp,
+    }
+}
+
+diesel::table! {
+    loan (id) {
+        id -> Integer,
+        account_id -> Integer,
+        principal -> Float,
+        interest_rate -> Float,
+        term -> Integer,
+        payment_frequency -> Integer,
+        start_date -> Timestamp,
+    }
+}
+
+diesel::table! {
+    loan_payment (id) {
+        id -> Integer,
+        loan_id -> Integer,
+        transfer_id -> Integer,
+        principal_amount -> Float,
+        interest_amount -> Float,
+    }
+}
+
 diesel::table! {
     money_transaction (id) {
         id -> Integer,
@@ -46,16 +118,122 @@ diesel::table! {
         amount -> Float,
         transaction_date -> Timestamp,
         is_expense -> Bool,
+        status -> Integer,
+        payee -> Nullable<Text>,
+        external_id -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    rule (id) {
+        id -> Integer,
+        rule_name -> Text,
+        match_text -> Nullable<Text>,
+        match_regex -> Bool,
+        match_min_amount -> Nullable<Float>,
+        match_max_amount -> Nullable<Float>,
+        match_account -> Nullable<Integer>,
+        set_category -> Nullable<Integer>,
+        set_payee -> Nullable<Text>,
+        set_tag -> Nullable<Integer>,
+        set_note -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    saved_filter (id) {
+        id -> Integer,
+        filter_name -> Text,
+        definition -> Text,
+    }
+}
+
+diesel::table! {
+    security (id) {
+        id -> Integer,
+        symbol -> Text,
+        security_name -> Text,
+    }
+}
+
+diesel::table! {
+    security_price (id) {
+        id -> Integer,
+        security_id -> Integer,
+        price_date -> Timestamp,
+        price -> Float,
+    }
+}
+
+diesel::table! {
+    statement_account (external_account) {
+        external_account -> Text,
+        account_id -> Integer,
+    }
+}
+
+diesel::table! {
+    tag (id) {
+        id -> Integer,
+        tag_name -> Text,
+    }
+}
+
+diesel::table! {
+    transaction_tag (transaction_id, tag_id) {
+        transaction_id -> Integer,
+        tag_id -> Integer,
+    }
+}
+
+diesel::table! {
+    transaction_template (id) {
+        id -> Integer,
+        template_name -> Text,
+        bank_account -> Integer,
+        transaction_category -> Integer,
+        description -> Text,
+        amount -> Float,
+        is_expense -> Bool,
+        payee -> Nullable<Text>,
     }
 }
 
+diesel::joinable!(account -> account_group (group_id));
+diesel::joinable!(credit_card -> account (account_id));
+diesel::joinable!(investment_transaction -> account (account_id));
+diesel::joinable!(investment_transaction -> security (security_id));
+diesel::joinable!(loan -> account (account_id));
+diesel::joinable!(loan_payment -> account_transfer (transfer_id));
+diesel::joinable!(loan_payment -> loan (loan_id));
 diesel::joinable!(money_transaction -> account (bank_account));
 diesel::joinable!(money_transaction -> category (transaction_category));
+diesel::joinable!(security_price -> security (security_id));
+diesel::joinable!(statement_account -> account (account_id));
+diesel::joinable!(transaction_tag -> money_transaction (transaction_id));
+diesel::joinable!(transaction_tag -> tag (tag_id));
+diesel::joinable!(transaction_template -> account (bank_account));
+diesel::joinable!(transaction_template -> category (transaction_category));
 
 diesel::allow_tables_to_appear_in_same_query!(
     account,
+    account_group,
     account_transfer,
     category,
+    credit_card,
+    csv_profile,
     currency,
+    dismissed_duplicate,
+    investment_transaction,
+    loan,
+    loan_payment,
     money_transaction,
+    rule,
+    saved_filter,
+    security,
+    security_price,
+    statement_account,
+    tag,
+    transaction_tag,
+    transaction_template,
 );
@@ -2,9 +2,17 @@ use crate::{
     errors::DataStoreError,
     get_database_url,
     models::{self, Account, NewAccount},
-    schema::{self, account, account_transfer, category, money_transaction},
+    schema::{
+        self, account, account_group, account_transfer, category, credit_card, csv_profile,
+        dismissed_duplicate, investment_transaction, loan, loan_payment, money_transaction, rule,
+        saved_filter, security, security_price, statement_account, tag, transaction_tag,
+        transaction_template,
+    },
+    synchronization::csv::CsvProfile,
+    utils::{dates, duplicates, investments, rules::RuleSet},
 };
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use diesel::connection::{AnsiTransactionManager, TransactionManager};
 use diesel::prelude::*;
 use diesel::result::Error as DieselError;
 use diesel::{Connection, RunQueryDsl, SelectableHelper, SqliteConnection};
@@ -14,6 +22,7 @@ use schema::account_transfer::dsl::*;
 use schema::category::dsl::*;
 use schema::currency::dsl::*;
 use schema::money_transaction::dsl::*;
+use std::collections::{HashMap, HashSet};
 
 pub struct Store {
     connection: SqliteConnection,
@@ -30,6 +39,16 @@ impl Default for Store {
     }
 }
 
+#[cfg(test)]
+impl Store {
+    /// Store over a fresh, fully migrated in-memory database.
+    pub fn in_memory() -> Self {
+        let mut connection = SqliteConnection::establish(":memory:").unwrap();
+        crate::run_migration(&mut connection);
+        Self { connection }
+    }
+}
+
 impl Store {
     pub fn create_account(&mut self, new_account: &NewAccount) -> Result<(), DataStoreError> {
         let res = diesel::insert_into(account::table)
@@ -44,18 +63,23 @@ impl Store {
         Ok(())
     }
 
+    /// Inserts accounts, returning their ids in the same order.
     pub fn create_accounts(
         &mut self,
         new_accounts: &Vec<NewAccount>,
-    ) -> Result<(), DataStoreError> {
+    ) -> Result<Vec<i32>, DataStoreError> {
         self.connection
             .transaction::<_, DieselError, _>(|conn| {
+                let mut ids = Vec::with_capacity(new_accounts.len());
                 for new_account in new_accounts {
-                    diesel::insert_into(account::table)
-                        .values(new_account)
-                        .execute(conn)?;
+                    ids.push(
+                        diesel::insert_into(account::table)
+                            .values(new_account)
+                            .returning(account::id)
+                            .get_result::<i32>(conn)?,
+                    );
                 }
-                Ok(())
+                Ok(ids)
             })
             .map_err(|e| DataStoreError::InsertError(e.to_string()))
     }
@@ -66,6 +90,9 @@ impl Store {
     ) -> Result<(), DataStoreError> {
         self.connection
             .transaction::<_, DieselError, _>(|conn| {
+                if Self::is_reconciled(conn, updated_transaction.id)? {
+                    return Err(DieselError::RollbackTransaction);
+                }
                 diesel::update(money_transaction::table)
                     .filter(money_transaction::id.eq(updated_transaction.id))
                     .set((
@@ -77,24 +104,175 @@ impl Store {
                         money_transaction::transaction_date
                             .eq(updated_transaction.transaction_date),
                         money_transaction::is_expense.eq(updated_transaction.is_expense),
+                        money_transaction::payee.eq(&updated_transaction.payee),
                     ))
                     .execute(conn)?;
 
                 Ok(())
             })
-            .map_err(|e| DataStoreError::UpdateError(e.to_string()))
+            .map_err(|e| match e {
+                DieselError::RollbackTransaction => DataStoreError::Reconciled,
+                e => DataStoreError::UpdateError(e.to_string()),
+            })
     }
 
     pub fn delete_transaction(&mut self, transaction_id: &i32) -> Result<(), DataStoreError> {
         self.connection
             .transaction::<_, DieselError, _>(|conn| {
+                if Self::is_reconciled(conn, *transaction_id)? {
+                    return Err(DieselError::RollbackTransaction);
+                }
                 diesel::delete(money_transaction::table)
                     .filter(money_transaction::id.eq(transaction_id))
                     .execute(conn)?;
 
                 Ok(())
             })
-            .map_err(|e| DataStoreError::DeleteError(e.to_string()))
+            .map_err(|e| match e {
+                DieselError::RollbackTransaction => DataStoreError::Reconciled,
+                e => DataStoreError::DeleteError(e.to_string()),
+            })
+    }
+
+    /// Whether a transaction is reconciled, and so locked against changes.
+    fn is_reconciled(conn: &mut SqliteConnection, transaction_id: i32) -> QueryResult<bool> {
+        let transaction_status: Option<i32> = money_transaction::table
+            .filter(money_transaction::id.eq(transaction_id))
+            .select(money_transaction::status)
+            .first(conn)
+            .optional()?;
+        Ok(transaction_status == Some(i32::from(TransactionStatus::Reconciled)))
+    }
+
+    /// Applies a change to the given transactions in a single database
+    /// transaction, skipping reconciled ones. Returns what is needed to undo
+    /// the change.
+    pub fn bulk_edit(
+        &mut self,
+        transaction_ids: &[i32],
+        edit: BulkEdit,
+    ) -> Result<BulkUndo, DataStoreError> {
+        self.connection
+            .transaction::<_, DieselError, _>(|conn| {
+                let transactions: Vec<MoneyTransaction> = money_transaction::table
+                    .filter(money_transaction::id.eq_any(transaction_ids))
+                    .filter(money_transaction::status.ne(i32::from(TransactionStatus::Reconciled)))
+                    .select(MoneyTransaction::as_select())
+                    .load(conn)?;
+                let ids: Vec<i32> = transactions.iter().map(|t| t.id).collect();
+                let tags: Vec<TransactionTag> = transaction_tag::table
+                    .filter(transaction_tag::transaction_id.eq_any(&ids))
+                    .select(TransactionTag::as_select())
+                    .load(conn)?;
+
+                let edited = money_transaction::table.filter(money_transaction::id.eq_any(&ids));
+                match edit {
+                    BulkEdit::Category(category_id) => {
+                        diesel::update(edited)
+                            .set(money_transaction::transaction_category.eq(category_id))
+                            .execute(conn)?;
+                    }
+                    BulkEdit::Account(account_id) => {
+                        diesel::update(edited)
+                            .set(money_transaction::bank_account.eq(account_id))
+                            .execute(conn)?;
+                    }
+                    BulkEdit::AddTag(tag_id) => {
+                        diesel::insert_or_ignore_into(transaction_tag::table)
+                            .values(
+                                ids.iter()
+                                    .map(|transaction_id| TransactionTag {
+                                        transaction_id: *transaction_id,
+                                        tag_id,
+                                    })
+                                    .collect::<Vec<_>>(),
+                            )
+                            .execute(conn)?;
+                    }
+                    BulkEdit::ShiftDays(days) => {
+                        for t in &transactions {
+                            diesel::update(money_transaction::table)
+                                .filter(money_transaction::id.eq(t.id))
+                                .set(
+                                    money_transaction::transaction_date
+                                        .eq(t.transaction_date + chrono::Duration::days(days)),
+                                )
+                                .execute(conn)?;
+                        }
+                    }
+                    BulkEdit::Delete => {
+                        diesel::delete(
+                            transaction_tag::table
+                                .filter(transaction_tag::transaction_id.eq_any(&ids)),
+                        )
+                        .execute(conn)?;
+                        diesel::delete(edited).execute(conn)?;
+                    }
+                }
+                let (edited, edited_tags) = edited_state(conn, &ids)?;
+                Ok(BulkUndo {
+                    transactions,
+                    tags,
+                    edited,
+                    edited_tags,
+                })
+            })
+            .map_err(|e| DataStoreError::UpdateError(e.to_string()))
+    }
+
+    /// Puts back the transactions and tags saved before a bulk edit. Fails
+    /// with [`DataStoreError::Outdated`] when they changed since the edit,
+    /// were reconciled, or their accounts or categories are gone, rather
+    /// than overwriting the later changes.
+    pub fn undo_bulk_edit(&mut self, undo: &BulkUndo) -> Result<(), DataStoreError> {
+        let ids: Vec<i32> = undo.transactions.iter().map(|t| t.id).collect();
+        self.connection
+            .transaction::<_, DieselError, _>(|conn| {
+                let (current, current_tags) = edited_state(conn, &ids)?;
+                let account_ids: Vec<i32> =
+                    undo.transactions.iter().map(|t| t.bank_account).collect();
+                let category_ids: Vec<i32> = undo
+                    .transactions
+                    .iter()
+                    .map(|t| t.transaction_category)
+                    .collect();
+                let accounts_left: i64 = account::table
+                    .filter(account::id.eq_any(&account_ids))
+                    .count()
+                    .get_result(conn)?;
+                let categories_left: i64 = category::table
+                    .filter(category::id.eq_any(&category_ids))
+                    .count()
+                    .get_result(conn)?;
+                let distinct = |ids: &[i32]| ids.iter().collect::<HashSet<_>>().len() as i64;
+                if current != undo.edited
+                    || current_tags != undo.edited_tags
+                    || current
+                        .iter()
+                        .any(|t| t.status() == TransactionStatus::Reconciled)
+                    || accounts_left != distinct(&account_ids)
+                    || categories_left != distinct(&category_ids)
+                {
+                    return Err(DieselError::RollbackTransaction);
+                }
+                diesel::delete(
+                    transaction_tag::table.filter(transaction_tag::transaction_id.eq_any(&ids)),
+                )
+                .execute(conn)?;
+                diesel::delete(money_transaction::table.filter(money_transaction::id.eq_any(&ids)))
+                    .execute(conn)?;
+                diesel::insert_into(money_transaction::table)
+                    .values(&undo.transactions)
+                    .execute(conn)?;
+                diesel::insert_into(transaction_tag::table)
+                    .values(&undo.tags)
+                    .execute(conn)?;
+                Ok(())
+            })
+            .map_err(|e| match e {
+                DieselError::RollbackTransaction => DataStoreError::Outdated,
+                e => DataStoreError::UpdateError(e.to_string()),
+            })
     }
 
     pub fn update_account(&mut self, update_account: &UpdateAccount) -> Result<(), DataStoreError> {
@@ -105,6 +283,10 @@ impl Store {
                 name.eq(&update_account.name),
                 account_description.eq(&update_account.account_description),
                 initial_balance.eq(&update_account.initial_balance),
+                account_type.eq(&update_account.account_type),
+                group_id.eq(&update_account.group_id),
+                exclude_from_total.eq(&update_account.exclude_from_total),
+                exclude_from_statistics.eq(&update_account.exclude_from_statistics),
             ))
             .execute(&mut self.connection);
 
@@ -115,6 +297,50 @@ impl Store {
         Ok(())
     }
 
+    pub fn get_account_groups(&mut self) -> Result<Vec<AccountGroup>, DataStoreError> {
+        account_group::table
+            .select(AccountGroup::as_select())
+            .order(account_group::group_name.asc())
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
+
+    pub fn create_account_group(
+        &mut self,
+        new_group: &NewAccountGroup,
+    ) -> Result<(), DataStoreError> {
+        diesel::insert_into(account_group::table)
+            .values(new_group)
+            .execute(&mut self.connection)
+            .map(|_| ())
+            .map_err(|e| DataStoreError::InsertError(e.to_string()))
+    }
+
+    /// Deletes a group, moving its accounts out of any group.
+    pub fn delete_account_group(&mut self, target_group: i32) -> Result<(), DataStoreError> {
+        self.connection
+            .transaction::<_, DieselError, _>(|conn| {
+                diesel::update(account::table)
+                    .filter(account::group_id.eq(target_group))
+                    .set(account::group_id.eq(None::<i32>))
+                    .execute(conn)?;
+                diesel::delete(account_group::table)
+                    .filter(account_group::id.eq(target_group))
+                    .execute(conn)?;
+                Ok(())
+            })
+            .map_err(|e| DataStoreError::DeleteError(e.to_string()))
+    }
+
+    /// Ids of the accounts left out of statistics.
+    pub fn get_statistics_excluded_accounts(&mut self) -> Result<Vec<i32>, DataStoreError> {
+        account::table
+            .filter(account::exclude_from_statistics.eq(true))
+            .select(account::id)
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
+
     pub fn get_accounts(&mut self) -> Result<Vec<Account>, DataStoreError> {
         let results = account
             .select(Account::as_select())
@@ -126,14 +352,68 @@ impl Store {
         }
     }
 
+    /// Balance of an account as of now: transactions and transfers dated in the
+    /// future are scheduled and not counted yet.
     pub fn get_account_balance(&mut self, account_id: i32) -> Result<f32, DataStoreError> {
+        self.get_account_balance_at(account_id, Utc::now().naive_utc())
+    }
+
+    /// Balance of an account including everything dated up to `until`.
+    pub fn get_account_balance_at(
+        &mut self,
+        account_id: i32,
+        until: NaiveDateTime,
+    ) -> Result<f32, DataStoreError> {
+        let (initial, movements) = self.get_account_movements(account_id)?;
+        Ok(initial
+            + movements
+                .iter()
+                .filter(|(date, _)| *date <= until)
+                .map(|(_, value)| value)
+                .sum::<f32>())
+    }
+
+    /// First date between now and `until` where the balance of an account,
+    /// projected with its scheduled transactions, falls below zero.
+    pub fn get_first_negative_balance(
+        &mut self,
+        account_id: i32,
+        until: NaiveDateTime,
+    ) -> Result<Option<(NaiveDateTime, f32)>, DataStoreError> {
+        let now = Utc::now().naive_utc();
+        let (initial, movements) = self.get_account_movements(account_id)?;
+
+        let mut balance = initial
+            + movements
+                .iter()
+                .filter(|(date, _)| *date <= now)
+                .map(|(_, value)| value)
+                .sum::<f32>();
+        for (date, value) in movements
+            .iter()
+            .filter(|(date, _)| *date > now && *date <= until)
+        {
+            balance += value;
+            if balance < 0. {
+                return Ok(Some((*date, balance)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Initial balance of an account and every dated movement on it, sorted by date.
+    fn get_account_movements(
+        &mut self,
+        account_id: i32,
+    ) -> Result<(f32, Vec<(NaiveDateTime, f32)>), DataStoreError> {
         // read account initial balance
         let target_initial_balance = account
             .filter(account::id.eq(account_id))
             .select(account::initial_balance)
             .first::<f32>(&mut self.connection);
 
-        let mut total: f32 = target_initial_balance.unwrap_or(0.);
+        let mut movements: Vec<(NaiveDateTime, f32)> = vec![];
 
         // read all transaction on that account
         let transactions = money_transaction
@@ -143,11 +423,8 @@ impl Store {
 
         if let Ok(transactions) = transactions {
             for t in transactions {
-                if t.is_expense {
-                    total -= t.amount;
-                } else {
-                    total += t.amount;
-                }
+                let value = if t.is_expense { -t.amount } else { t.amount };
+                movements.push((t.transaction_date, value));
             }
         }
 
@@ -161,20 +438,502 @@ impl Store {
             .select(AccountTransfer::as_select())
             .load(&mut self.connection);
 
+        // buying and selling securities moves cash in and out of the account
+        let operations = investment_transaction::table
+            .filter(investment_transaction::account_id.eq(account_id))
+            .select(InvestmentTransaction::as_select())
+            .load(&mut self.connection);
+
+        if let Ok(operations) = operations {
+            for o in operations {
+                movements.push((o.transaction_date, investments::cash_effect(&o)));
+            }
+        }
+
+        // the interest part of a loan payment does not reduce the debt
+        let interests = self.get_loan_interests(account_id).unwrap_or_default();
+
         if let Ok(transfers) = transfers {
             for t in transfers {
-                if t.from_account == account_id {
-                    total -= t.amount;
-                }
-                if t.to_account == account_id {
-                    total += t.amount;
-                }
+                movements.push((t.transfer_date, transfer_effect(&t, account_id, &interests)));
             }
         }
 
+        movements.sort_by_key(|(date, _)| *date);
+        Ok((target_initial_balance.unwrap_or(0.), movements))
+    }
+
+    /// Interest part of every payment made to a loan account, by transfer id.
+    pub fn get_loan_interests(
+        &mut self,
+        target_account: i32,
+    ) -> Result<Vec<(i32, f32)>, DataStoreError> {
+        loan_payment::table
+            .inner_join(loan::table)
+            .filter(loan::account_id.eq(target_account))
+            .select((loan_payment::transfer_id, loan_payment::interest_amount))
+            .load::<(i32, f32)>(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
+
+    /// Transactions and transfers of an account dated up to `until` that are
+    /// not reconciled yet.
+    pub fn get_unreconciled(
+        &mut self,
+        target_account: i32,
+        until: NaiveDateTime,
+    ) -> Result<(Vec<MoneyTransaction>, Vec<AccountTransfer>), DataStoreError> {
+        let reconciled = i32::from(TransactionStatus::Reconciled);
+
+        let transactions = money_transaction::table
+            .filter(money_transaction::bank_account.eq(target_account))
+            .filter(money_transaction::status.ne(reconciled))
+            .filter(money_transaction::transaction_date.le(until))
+            .select(MoneyTransaction::as_select())
+            .order(money_transaction::transaction_date.asc())
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))?;
+
+        let transfers = account_transfer::table
+            .filter(
+                account_transfer::from_account
+                    .eq(target_account)
+                    .and(account_transfer::status.ne(reconciled))
+                    .or(account_transfer::to_account
+                        .eq(target_account)
+                        .and(account_transfer::to_status.ne(reconciled))),
+            )
+            .filter(account_transfer::transfer_date.le(until))
+            .select(AccountTransfer::as_select())
+            .order(account_transfer::transfer_date.asc())
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))?;
+
+        Ok((transactions, transfers))
+    }
+
+    /// Balance of an account counting only its reconciled transactions and
+    /// transfers. Buying and selling securities has no status, the cash it
+    /// moved up to `until` counts as settled.
+    pub fn get_reconciled_balance(
+        &mut self,
+        target_account: i32,
+        until: NaiveDateTime,
+    ) -> Result<f32, DataStoreError> {
+        let reconciled = i32::from(TransactionStatus::Reconciled);
+
+        let mut total = account::table
+            .filter(account::id.eq(target_account))
+            .select(account::initial_balance)
+            .first::<f32>(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))?;
+
+        let transactions = money_transaction::table
+            .filter(money_transaction::bank_account.eq(target_account))
+            .filter(money_transaction::status.eq(reconciled))
+            .select(MoneyTransaction::as_select())
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))?;
+        for t in transactions {
+            total += if t.is_expense { -t.amount } else { t.amount };
+        }
+
+        let interests = self.get_loan_interests(target_account)?;
+        let transfers = account_transfer::table
+            .filter(
+                account_transfer::from_account
+                    .eq(target_account)
+                    .and(account_transfer::status.eq(reconciled))
+                    .or(account_transfer::to_account
+                        .eq(target_account)
+                        .and(account_transfer::to_status.eq(reconciled))),
+            )
+            .select(AccountTransfer::as_select())
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))?;
+        for t in transfers {
+            total += transfer_effect(&t, target_account, &interests);
+        }
+
+        let operations = investment_transaction::table
+            .filter(investment_transaction::account_id.eq(target_account))
+            .filter(investment_transaction::transaction_date.le(until))
+            .select(InvestmentTransaction::as_select())
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))?;
+        for o in operations {
+            total += investments::cash_effect(&o);
+        }
+
         Ok(total)
     }
 
+    pub fn set_transaction_status(
+        &mut self,
+        transaction_id: i32,
+        new_status: TransactionStatus,
+    ) -> Result<(), DataStoreError> {
+        diesel::update(money_transaction::table)
+            .filter(money_transaction::id.eq(transaction_id))
+            .filter(money_transaction::status.ne(i32::from(TransactionStatus::Reconciled)))
+            .set(money_transaction::status.eq(i32::from(new_status)))
+            .execute(&mut self.connection)
+            .map(|_| ())
+            .map_err(|e| DataStoreError::UpdateError(e.to_string()))
+    }
+
+    /// Sets the status of a transfer in one of its accounts, unless it is
+    /// reconciled there.
+    pub fn set_transfer_status(
+        &mut self,
+        transfer_id: i32,
+        target_account: i32,
+        new_status: TransactionStatus,
+    ) -> Result<(), DataStoreError> {
+        let reconciled = i32::from(TransactionStatus::Reconciled);
+        let transfer = account_transfer::table.filter(account_transfer::id.eq(transfer_id));
+        self.connection
+            .transaction::<_, DieselError, _>(|conn| {
+                diesel::update(transfer)
+                    .filter(account_transfer::from_account.eq(target_account))
+                    .filter(account_transfer::status.ne(reconciled))
+                    .set(account_transfer::status.eq(i32::from(new_status)))
+                    .execute(conn)?;
+                diesel::update(transfer)
+                    .filter(account_transfer::to_account.eq(target_account))
+                    .filter(account_transfer::to_status.ne(reconciled))
+                    .set(account_transfer::to_status.eq(i32::from(new_status)))
+                    .execute(conn)?;
+                Ok(())
+            })
+            .map_err(|e| DataStoreError::UpdateError(e.to_string()))
+    }
+
+    /// Locks the given transactions and transfers as reconciled in an
+    /// account, leaving the other side of the transfers as it is.
+    pub fn reconcile(
+        &mut self,
+        target_account: i32,
+        transaction_ids: &[i32],
+        transfer_ids: &[i32],
+    ) -> Result<(), DataStoreError> {
+        let reconciled = i32::from(TransactionStatus::Reconciled);
+        self.connection
+            .transaction::<_, DieselError, _>(|conn| {
+                diesel::update(money_transaction::table)
+                    .filter(money_transaction::id.eq_any(transaction_ids))
+                    .set(money_transaction::status.eq(reconciled))
+                    .execute(conn)?;
+                diesel::update(account_transfer::table)
+                    .filter(account_transfer::id.eq_any(transfer_ids))
+                    .filter(account_transfer::from_account.eq(target_account))
+                    .set(account_transfer::status.eq(reconciled))
+                    .execute(conn)?;
+                diesel::update(account_transfer::table)
+                    .filter(account_transfer::id.eq_any(transfer_ids))
+                    .filter(account_transfer::to_account.eq(target_account))
+                    .set(account_transfer::to_status.eq(reconciled))
+                    .execute(conn)?;
+                Ok(())
+            })
+            .map_err(|e| DataStoreError::UpdateError(e.to_string()))
+    }
+
+    pub fn create_loan(
+        &mut self,
+        new_account: &NewAccount,
+        new_loan: &NewLoan,
+    ) -> Result<(), DataStoreError> {
+        self.connection
+            .transaction::<_, DieselError, _>(|conn| {
+                let created = diesel::insert_into(account::table)
+                    .values(new_account)
+                    .returning(Account::as_returning())
+                    .get_result(conn)?;
+
+                diesel::insert_into(loan::table)
+                    .values(&NewLoan {
+                        account_id: created.id,
+                        ..new_loan.clone()
+                    })
+                    .execute(conn)?;
+                Ok(())
+            })
+            .map_err(|e| DataStoreError::InsertError(e.to_string()))
+    }
+
+    pub fn create_credit_card(
+        &mut self,
+        new_account: &NewAccount,
+        new_card: &NewCreditCard,
+    ) -> Result<(), DataStoreError> {
+        self.connection
+            .transaction::<_, DieselError, _>(|conn| {
+                let created = diesel::insert_into(account::table)
+                    .values(new_account)
+                    .returning(Account::as_returning())
+                    .get_result(conn)?;
+
+                diesel::insert_into(credit_card::table)
+                    .values(&NewCreditCard {
+                        account_id: created.id,
+                        ..new_card.clone()
+                    })
+                    .execute(conn)?;
+                Ok(())
+            })
+            .map_err(|e| DataStoreError::InsertError(e.to_string()))
+    }
+
+    pub fn create_credit_cards(
+        &mut self,
+        new_cards: &Vec<NewCreditCard>,
+    ) -> Result<(), DataStoreError> {
+        diesel::insert_into(credit_card::table)
+            .values(new_cards)
+            .execute(&mut self.connection)
+            .map(|_| ())
+            .map_err(|e| DataStoreError::InsertError(e.to_string()))
+    }
+
+    pub fn get_credit_cards(&mut self) -> Result<Vec<CreditCard>, DataStoreError> {
+        credit_card::table
+            .select(CreditCard::as_select())
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
+
+    /// Total transferred into an account in the period (`after`, `until`].
+    pub fn get_received_transfers(
+        &mut self,
+        target_account: i32,
+        after: NaiveDateTime,
+        until: NaiveDateTime,
+    ) -> Result<f32, DataStoreError> {
+        account_transfer::table
+            .filter(account_transfer::to_account.eq(target_account))
+            .filter(account_transfer::transfer_date.gt(after))
+            .filter(account_transfer::transfer_date.le(until))
+            .select(account_transfer::amount)
+            .load::<f32>(&mut self.connection)
+            .map(|amounts| amounts.iter().sum())
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
+
+    pub fn get_loans(&mut self) -> Result<Vec<Loan>, DataStoreError> {
+        loan::table
+            .select(Loan::as_select())
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
+
+    /// Inserts loans, returning their ids in the same order.
+    pub fn create_loans(&mut self, new_loans: &Vec<NewLoan>) -> Result<Vec<i32>, DataStoreError> {
+        self.connection
+            .transaction::<_, DieselError, _>(|conn| {
+                let mut ids = Vec::with_capacity(new_loans.len());
+                for new_loan in new_loans {
+                    ids.push(
+                        diesel::insert_into(loan::table)
+                            .values(new_loan)
+                            .returning(loan::id)
+                            .get_result::<i32>(conn)?,
+                    );
+                }
+                Ok(ids)
+            })
+            .map_err(|e| DataStoreError::InsertError(e.to_string()))
+    }
+
+    /// Payments of every loan.
+    pub fn get_all_loan_payments(&mut self) -> Result<Vec<LoanPayment>, DataStoreError> {
+        loan_payment::table
+            .select(LoanPayment::as_select())
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
+
+    /// Records the principal/interest split of existing payment transfers.
+    pub fn create_loan_payments(
+        &mut self,
+        new_payments: &Vec<NewLoanPayment>,
+    ) -> Result<(), DataStoreError> {
+        diesel::insert_into(loan_payment::table)
+            .values(new_payments)
+            .execute(&mut self.connection)
+            .map(|_| ())
+            .map_err(|e| DataStoreError::InsertError(e.to_string()))
+    }
+
+    pub fn get_loan_payments(
+        &mut self,
+        target_loan: i32,
+    ) -> Result<Vec<LoanPayment>, DataStoreError> {
+        loan_payment::table
+            .filter(loan_payment::loan_id.eq(target_loan))
+            .select(LoanPayment::as_select())
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
+
+    /// Records a payment transfer to a loan account together with its
+    /// principal/interest split.
+    pub fn create_loan_payment(
+        &mut self,
+        new_account_transfer: &NewAccountTransfer,
+        target_loan: i32,
+        principal_amount: f32,
+        interest_amount: f32,
+    ) -> Result<(), DataStoreError> {
+        self.connection
+            .transaction::<_, DieselError, _>(|conn| {
+                let transfer = diesel::insert_into(account_transfer::table)
+                    .values(new_account_transfer)
+                    .returning(AccountTransfer::as_returning())
+                    .get_result(conn)?;
+
+                diesel::insert_into(loan_payment::table)
+                    .values(&NewLoanPayment {
+                        loan_id: target_loan,
+                        transfer_id: transfer.id,
+                        principal_amount,
+                        interest_amount,
+                    })
+                    .execute(conn)?;
+                Ok(())
+            })
+            .map_err(|e| DataStoreError::InsertError(e.to_string()))
+    }
+
+    pub fn get_securities(&mut self) -> Result<Vec<Security>, DataStoreError> {
+        security::table
+            .select(Security::as_select())
+            .order(security::symbol.asc())
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
+
+    /// Returns the security with the given symbol, creating it when missing.
+    pub fn get_or_create_security(
+        &mut self,
+        security_symbol: &str,
+    ) -> Result<Security, DataStoreError> {
+        let existing = security::table
+            .filter(security::symbol.eq(security_symbol))
+            .select(Security::as_select())
+            .first(&mut self.connection)
+            .optional()
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))?;
+
+        match existing {
+            Some(existing) => Ok(existing),
+            None => diesel::insert_into(security::table)
+                .values(&NewSecurity {
+                    symbol: security_symbol.to_string(),
+                    security_name: String::default(),
+                })
+                .returning(Security::as_returning())
+                .get_result(&mut self.connection)
+                .map_err(|e| DataStoreError::InsertError(e.to_string())),
+        }
+    }
+
+    /// Returns the ids of the securities with the given symbols, in the same
+    /// order, creating the missing ones.
+    pub fn get_or_create_securities(
+        &mut self,
+        new_securities: &Vec<NewSecurity>,
+    ) -> Result<Vec<i32>, DataStoreError> {
+        self.connection
+            .transaction::<_, DieselError, _>(|conn| {
+                let mut ids = Vec::with_capacity(new_securities.len());
+                for new_security in new_securities {
+                    diesel::insert_or_ignore_into(security::table)
+                        .values(new_security)
+                        .execute(conn)?;
+                    ids.push(
+                        security::table
+                            .filter(security::symbol.eq(&new_security.symbol))
+                            .select(security::id)
+                            .first::<i32>(conn)?,
+                    );
+                }
+                Ok(ids)
+            })
+            .map_err(|e| DataStoreError::InsertError(e.to_string()))
+    }
+
+    pub fn get_security_prices(&mut self) -> Result<Vec<SecurityPrice>, DataStoreError> {
+        security_price::table
+            .select(SecurityPrice::as_select())
+            .order(security_price::price_date.desc())
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
+
+    /// Stores the given quotes, replacing any price already recorded for the
+    /// same security and date.
+    pub fn create_security_prices(
+        &mut self,
+        new_prices: &Vec<NewSecurityPrice>,
+    ) -> Result<(), DataStoreError> {
+        self.connection
+            .transaction::<_, DieselError, _>(|conn| {
+                for new_price in new_prices {
+                    diesel::replace_into(security_price::table)
+                        .values(new_price)
+                        .execute(conn)?;
+                }
+                Ok(())
+            })
+            .map_err(|e| DataStoreError::InsertError(e.to_string()))
+    }
+
+    pub fn get_investment_transactions(
+        &mut self,
+        target_account: i32,
+    ) -> Result<Vec<InvestmentTransaction>, DataStoreError> {
+        investment_transaction::table
+            .filter(investment_transaction::account_id.eq(target_account))
+            .select(InvestmentTransaction::as_select())
+            .order(investment_transaction::transaction_date.desc())
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
+
+    /// Operations of every investment account.
+    pub fn get_all_investment_transactions(
+        &mut self,
+    ) -> Result<Vec<InvestmentTransaction>, DataStoreError> {
+        investment_transaction::table
+            .select(InvestmentTransaction::as_select())
+            .order(investment_transaction::transaction_date.asc())
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
+
+    pub fn create_investment_transactions(
+        &mut self,
+        new_investment_transactions: &Vec<NewInvestmentTransaction>,
+    ) -> Result<(), DataStoreError> {
+        diesel::insert_into(investment_transaction::table)
+            .values(new_investment_transactions)
+            .execute(&mut self.connection)
+            .map(|_| ())
+            .map_err(|e| DataStoreError::InsertError(e.to_string()))
+    }
+
+    pub fn create_investment_transaction(
+        &mut self,
+        new_investment_transaction: &NewInvestmentTransaction,
+    ) -> Result<(), DataStoreError> {
+        diesel::insert_into(investment_transaction::table)
+            .values(new_investment_transaction)
+            .execute(&mut self.connection)
+            .map(|_| ())
+            .map_err(|e| DataStoreError::InsertError(e.to_string()))
+    }
+
     pub fn get_categories(&mut self) -> Result<Vec<Category>, DataStoreError> {
         let results = category
             .select(Category::as_select())
@@ -195,12 +954,15 @@ impl Store {
         use diesel::dsl::sum;
         use schema::money_transaction::dsl::*;
 
+        let (range_start, range_end) = dates::local_day_range(*start_date, *end_date);
+        let excluded = self.get_statistics_excluded_accounts()?;
+
         let total_expense = money_transaction
             .filter(transaction_category.eq(category_id))
-            .filter(
-                transaction_date.between(start_date.and_hms(0, 0, 0), end_date.and_hms(23, 59, 59)),
-            )
+            .filter(transaction_date.ge(range_start))
+            .filter(transaction_date.lt(range_end))
             .filter(is_expense.eq(true))
+            .filter(bank_account.ne_all(excluded))
             .select(sum(amount))
             .first::<Option<f32>>(&mut self.connection);
 
@@ -220,12 +982,15 @@ impl Store {
         use diesel::dsl::sum;
         use schema::money_transaction::dsl::*;
 
+        let (range_start, range_end) = dates::local_day_range(*start_date, *end_date);
+        let excluded = self.get_statistics_excluded_accounts()?;
+
         let total_expense = money_transaction
             .filter(transaction_category.eq(category_id))
-            .filter(
-                transaction_date.between(start_date.and_hms(0, 0, 0), end_date.and_hms(23, 59, 59)),
-            )
+            .filter(transaction_date.ge(range_start))
+            .filter(transaction_date.lt(range_end))
             .filter(is_expense.eq(false))
+            .filter(bank_account.ne_all(excluded))
             .select(sum(amount))
             .first::<Option<f32>>(&mut self.connection);
 
@@ -249,18 +1014,23 @@ impl Store {
         Ok(())
     }
 
+    /// Inserts categories, returning their ids in the same order.
     pub fn create_categories(
         &mut self,
         new_categories: &Vec<NewCategory>,
-    ) -> Result<(), DataStoreError> {
+    ) -> Result<Vec<i32>, DataStoreError> {
         self.connection
             .transaction::<_, DieselError, _>(|conn| {
+                let mut ids = Vec::with_capacity(new_categories.len());
                 for new_category in new_categories {
-                    diesel::insert_into(category::table)
-                        .values(new_category)
-                        .execute(conn)?;
+                    ids.push(
+                        diesel::insert_into(category::table)
+                            .values(new_category)
+                            .returning(category::id)
+                            .get_result::<i32>(conn)?,
+                    );
                 }
-                Ok(())
+                Ok(ids)
             })
             .map_err(|e| DataStoreError::InsertError(e.to_string()))
     }
@@ -286,27 +1056,15 @@ impl Store {
         Ok(())
     }
 
-    pub fn get_money_transactions(&mut self) -> Result<Vec<MoneyTransaction>, DataStoreError> {
-        let results = money_transaction
-            .select(MoneyTransaction::as_select())
-            .order(transaction_date.desc())
-            .load(&mut self.connection);
-
-        match results {
-            Ok(results) => return Ok(results),
-            Err(e) => return Err(DataStoreError::QueryError(e.to_string())),
-        }
-    }
-
     pub fn get_money_transactions_date_range(
         &mut self,
         start_date: &NaiveDate,
         end_date: &NaiveDate,
     ) -> Result<Vec<MoneyTransaction>, DataStoreError> {
+        let (range_start, range_end) = dates::local_day_range(*start_date, *end_date);
         let results = money_transaction
-            .filter(
-                transaction_date.between(start_date.and_hms(0, 0, 0), end_date.and_hms(23, 59, 59)),
-            )
+            .filter(transaction_date.ge(range_start))
+            .filter(transaction_date.lt(range_end))
             .select(MoneyTransaction::as_select())
             .order(transaction_date.desc())
             .load(&mut self.connection);
@@ -317,22 +1075,382 @@ impl Store {
         }
     }
 
-    pub fn create_money_transaction(
+    /// Transactions whose description, payee, category or account name match
+    /// every word of `text`, most recent first. Amounts and dates narrow the
+    /// results when given.
+    pub fn search_transactions(
         &mut self,
-        new_money_transaction: &NewMoneyTransaction,
-    ) -> Result<(), DataStoreError> {
-        let res = diesel::insert_into(money_transaction::table)
-            .values(new_money_transaction)
-            .returning(MoneyTransaction::as_returning())
-            .get_result(&mut self.connection);
+        text: &str,
+        amount_range: (Option<f32>, Option<f32>),
+        date_range: Option<(NaiveDate, NaiveDate)>,
+    ) -> Result<Vec<MoneyTransaction>, DataStoreError> {
+        if text.trim().is_empty() {
+            return Ok(vec![]);
+        }
+        let mut filter = TransactionFilter::default()
+            .text(text)
+            .amount_range(amount_range.0, amount_range.1);
+        if let Some((first, last)) = date_range {
+            filter = filter.date_range(first, last);
+        }
+        self.get_filtered_transactions(&filter)
+    }
 
-        if let Err(e) = res {
-            return Err(DataStoreError::InsertError(e.to_string()));
+    /// Transactions matching `filter`, most recent first.
+    pub fn get_filtered_transactions(
+        &mut self,
+        filter: &TransactionFilter,
+    ) -> Result<Vec<MoneyTransaction>, DataStoreError> {
+        self.filtered_transactions_query(filter)?
+            .select(MoneyTransaction::as_select())
+            .order(money_transaction::transaction_date.desc())
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
+
+    /// Up to `limit` transactions matching `filter`, most recent first,
+    /// starting right after `after`. Pages are keyed on (date, id) rather
+    /// than an offset, so fetching a page costs the same anywhere in the ledger.
+    pub fn get_transactions_page(
+        &mut self,
+        filter: &TransactionFilter,
+        after: Option<TransactionCursor>,
+        limit: i64,
+    ) -> Result<Vec<MoneyTransaction>, DataStoreError> {
+        let mut query = self.filtered_transactions_query(filter)?;
+        if let Some(cursor) = after {
+            query = query.filter(
+                money_transaction::transaction_date
+                    .lt(cursor.transaction_date)
+                    .or(money_transaction::transaction_date
+                        .eq(cursor.transaction_date)
+                        .and(money_transaction::id.lt(cursor.id))),
+            );
+        }
+        query
+            .select(MoneyTransaction::as_select())
+            .order((
+                money_transaction::transaction_date.desc(),
+                money_transaction::id.desc(),
+            ))
+            .limit(limit)
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
+
+    /// Every transaction matching the filter, in pages of the given size
+    /// read one after the other.
+    pub fn transaction_pages<'a>(
+        &'a mut self,
+        filter: &'a TransactionFilter,
+        size: i64,
+    ) -> TransactionPages<'a> {
+        TransactionPages {
+            store: self,
+            filter,
+            size,
+            cursor: None,
+            done: false,
         }
+    }
+
+    pub fn count_filtered_transactions(
+        &mut self,
+        filter: &TransactionFilter,
+    ) -> Result<i64, DataStoreError> {
+        self.filtered_transactions_query(filter)?
+            .count()
+            .get_result(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
 
+    /// Income and expense totals of the transactions matching `filter`.
+    pub fn get_filtered_totals(
+        &mut self,
+        filter: &TransactionFilter,
+    ) -> Result<(f32, f32), DataStoreError> {
+        let mut totals = (0., 0.);
+        for expense in [false, true] {
+            let sum: Option<f32> = self
+                .filtered_transactions_query(filter)?
+                .filter(money_transaction::is_expense.eq(expense))
+                .select(diesel::dsl::sum(money_transaction::amount))
+                .first(&mut self.connection)
+                .map_err(|e| DataStoreError::QueryError(e.to_string()))?;
+            if expense {
+                totals.1 = sum.unwrap_or_default();
+            } else {
+                totals.0 = sum.unwrap_or_default();
+            }
+        }
+        Ok(totals)
+    }
+
+    /// Builds the query for `filter` one criterion at a time. The query is
+    /// left without ordering so callers can add their own on top.
+    fn filtered_transactions_query(
+        &mut self,
+        filter: &TransactionFilter,
+    ) -> Result<money_transaction::BoxedQuery<'static, diesel::sqlite::Sqlite>, DataStoreError>
+    {
+        let mut query = money_transaction::table.into_boxed();
+        if let Some(text) = &filter.text {
+            // Matches are filtered inside SQLite, as a broad term can match
+            // more rows than a statement can bind.
+            query = match fts_query(text) {
+                Some(terms) => query.filter(
+                    diesel::dsl::sql::<diesel::sql_types::Bool>(
+                        "money_transaction.id IN (SELECT rowid FROM transaction_search \
+                         WHERE transaction_search MATCH ",
+                    )
+                    .bind::<diesel::sql_types::Text, _>(terms)
+                    .sql(")"),
+                ),
+                None => query.filter(money_transaction::id.eq_any(Vec::<i32>::new())),
+            };
+        }
+        if !filter.accounts.is_empty() {
+            query = query.filter(money_transaction::bank_account.eq_any(filter.accounts.clone()));
+        }
+        if !filter.categories.is_empty() {
+            query = query
+                .filter(money_transaction::transaction_category.eq_any(filter.categories.clone()));
+        }
+        if let Some(expense) = filter.is_expense {
+            query = query.filter(money_transaction::is_expense.eq(expense));
+        }
+        if let Some(min) = filter.min_amount {
+            query = query.filter(money_transaction::amount.ge(min));
+        }
+        if let Some(max) = filter.max_amount {
+            query = query.filter(money_transaction::amount.le(max));
+        }
+        let date_range = filter.date_range.or_else(|| {
+            filter
+                .period
+                .map(|period| dates::period_range(period, dates::local_today()))
+        });
+        if let Some((first, last)) = date_range {
+            let (range_start, range_end) = dates::local_day_range(first, last);
+            query = query
+                .filter(money_transaction::transaction_date.ge(range_start))
+                .filter(money_transaction::transaction_date.lt(range_end));
+        }
+        Ok(query)
+    }
+
+    pub fn get_saved_filters(&mut self) -> Result<Vec<SavedFilter>, DataStoreError> {
+        saved_filter::table
+            .select(SavedFilter::as_select())
+            .order(saved_filter::filter_name.asc())
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
+
+    pub fn create_saved_filter(
+        &mut self,
+        new_filter_name: &str,
+        filter: &TransactionFilter,
+    ) -> Result<SavedFilter, DataStoreError> {
+        let definition = serde_json::to_string(filter)
+            .map_err(|e| DataStoreError::InsertError(e.to_string()))?;
+        diesel::insert_into(saved_filter::table)
+            .values(&NewSavedFilter {
+                filter_name: new_filter_name.to_string(),
+                definition,
+            })
+            .returning(SavedFilter::as_returning())
+            .get_result(&mut self.connection)
+            .map_err(|e| DataStoreError::InsertError(e.to_string()))
+    }
+
+    pub fn delete_saved_filter(&mut self, filter_id: i32) -> Result<(), DataStoreError> {
+        diesel::delete(saved_filter::table.filter(saved_filter::id.eq(filter_id)))
+            .execute(&mut self.connection)
+            .map_err(|e| DataStoreError::DeleteError(e.to_string()))?;
+        Ok(())
+    }
+
+    pub fn get_csv_profiles(&mut self) -> Result<Vec<SavedCsvProfile>, DataStoreError> {
+        csv_profile::table
+            .select(SavedCsvProfile::as_select())
+            .order(csv_profile::profile_name.asc())
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
+
+    /// Saves a CSV profile under a name, replacing the profile already saved
+    /// with that name.
+    pub fn save_csv_profile(
+        &mut self,
+        new_profile_name: &str,
+        profile: &CsvProfile,
+    ) -> Result<SavedCsvProfile, DataStoreError> {
+        let definition = serde_json::to_string(profile)
+            .map_err(|e| DataStoreError::InsertError(e.to_string()))?;
+        diesel::insert_into(csv_profile::table)
+            .values(&NewCsvProfile {
+                profile_name: new_profile_name.to_string(),
+                definition: definition.clone(),
+            })
+            .on_conflict(csv_profile::profile_name)
+            .do_update()
+            .set(csv_profile::definition.eq(definition))
+            .returning(SavedCsvProfile::as_returning())
+            .get_result(&mut self.connection)
+            .map_err(|e| DataStoreError::InsertError(e.to_string()))
+    }
+
+    pub fn delete_csv_profile(&mut self, profile_id: i32) -> Result<(), DataStoreError> {
+        diesel::delete(csv_profile::table.filter(csv_profile::id.eq(profile_id)))
+            .execute(&mut self.connection)
+            .map_err(|e| DataStoreError::DeleteError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Ids from bank statements of the transactions already in an account.
+    pub fn get_external_ids(
+        &mut self,
+        target_account: i32,
+    ) -> Result<HashSet<String>, DataStoreError> {
+        money_transaction::table
+            .filter(money_transaction::bank_account.eq(target_account))
+            .filter(money_transaction::external_id.is_not_null())
+            .select(money_transaction::external_id.assume_not_null())
+            .load::<String>(&mut self.connection)
+            .map(|ids| ids.into_iter().collect())
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
+
+    pub fn get_statement_accounts(&mut self) -> Result<Vec<StatementAccount>, DataStoreError> {
+        statement_account::table
+            .select(StatementAccount::as_select())
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
+
+    /// Remembers the app account that transactions of an account found in
+    /// statement files are imported into.
+    pub fn set_statement_account(
+        &mut self,
+        external_account: &str,
+        target_account: i32,
+    ) -> Result<(), DataStoreError> {
+        diesel::insert_into(statement_account::table)
+            .values(&StatementAccount {
+                external_account: external_account.to_string(),
+                account_id: target_account,
+            })
+            .on_conflict(statement_account::external_account)
+            .do_update()
+            .set(statement_account::account_id.eq(target_account))
+            .execute(&mut self.connection)
+            .map_err(|e| DataStoreError::InsertError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Likely duplicate transactions, as (older, newer) pairs, leaving out
+    /// the pairs the user already dismissed.
+    pub fn find_duplicate_transactions(
+        &mut self,
+        window_days: u32,
+    ) -> Result<Vec<(MoneyTransaction, MoneyTransaction)>, DataStoreError> {
+        let transactions: Vec<MoneyTransaction> = money_transaction::table
+            .select(MoneyTransaction::as_select())
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))?;
+        let dismissed: HashSet<(i32, i32)> = dismissed_duplicate::table
+            .select((
+                dismissed_duplicate::first_id,
+                dismissed_duplicate::second_id,
+            ))
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))?
+            .into_iter()
+            .collect();
+
+        let by_id: HashMap<i32, &MoneyTransaction> =
+            transactions.iter().map(|t| (t.id, t)).collect();
+        Ok(
+            duplicates::find_duplicates(&transactions, window_days, &dismissed)
+                .into_iter()
+                .filter_map(|(first, second)| {
+                    Some((
+                        (*by_id.get(&first)?).clone(),
+                        (*by_id.get(&second)?).clone(),
+                    ))
+                })
+                .collect(),
+        )
+    }
+
+    /// Marks two transactions as not being duplicates of each other.
+    pub fn dismiss_duplicate(&mut self, first: i32, second: i32) -> Result<(), DataStoreError> {
+        diesel::insert_or_ignore_into(dismissed_duplicate::table)
+            .values(&NewDismissedDuplicate {
+                first_id: first.min(second),
+                second_id: first.max(second),
+            })
+            .execute(&mut self.connection)
+            .map_err(|e| DataStoreError::InsertError(e.to_string()))?;
         Ok(())
     }
 
+    /// Merges a duplicate into the transaction that is kept: the kept one
+    /// takes over the payee, note and cleared status it lacks, then the
+    /// duplicate is deleted. Reconciled duplicates can't be removed.
+    pub fn merge_duplicate(&mut self, keep: i32, remove: i32) -> Result<(), DataStoreError> {
+        self.connection
+            .transaction::<_, DieselError, _>(|conn| {
+                let kept: MoneyTransaction = money_transaction::table
+                    .filter(money_transaction::id.eq(keep))
+                    .select(MoneyTransaction::as_select())
+                    .first(conn)?;
+                let removed: MoneyTransaction = money_transaction::table
+                    .filter(money_transaction::id.eq(remove))
+                    .select(MoneyTransaction::as_select())
+                    .first(conn)?;
+                if removed.status() == TransactionStatus::Reconciled {
+                    return Err(DieselError::RollbackTransaction);
+                }
+
+                diesel::update(money_transaction::table)
+                    .filter(money_transaction::id.eq(keep))
+                    .set((
+                        money_transaction::payee.eq(kept.payee.or(removed.payee)),
+                        money_transaction::description.eq(if kept.description.is_empty() {
+                            removed.description
+                        } else {
+                            kept.description
+                        }),
+                        money_transaction::status.eq(kept.status.max(removed.status)),
+                        money_transaction::external_id.eq(kept.external_id.or(removed.external_id)),
+                    ))
+                    .execute(conn)?;
+                // The kept transaction takes the tags of the removed one.
+                let removed_tags =
+                    transaction_tag::table.filter(transaction_tag::transaction_id.eq(remove));
+                let tag_ids: Vec<i32> = removed_tags.select(transaction_tag::tag_id).load(conn)?;
+                diesel::insert_or_ignore_into(transaction_tag::table)
+                    .values(
+                        tag_ids
+                            .into_iter()
+                            .map(|tag_id| TransactionTag {
+                                transaction_id: keep,
+                                tag_id,
+                            })
+                            .collect::<Vec<_>>(),
+                    )
+                    .execute(conn)?;
+                diesel::delete(removed_tags).execute(conn)?;
+                diesel::delete(money_transaction::table)
+                    .filter(money_transaction::id.eq(remove))
+                    .execute(conn)?;
+                Ok(())
+            })
+            .map_err(|e| DataStoreError::DeleteError(e.to_string()))
+    }
+
     pub fn create_money_transactions(
         &mut self,
         new_money_transactions: &Vec<NewMoneyTransaction>,
@@ -348,6 +1466,229 @@ impl Store {
         Ok(())
     }
 
+    /// Inserts a transaction after applying the first rule matching it.
+    pub fn create_money_transaction_with_rules(
+        &mut self,
+        new_money_transaction: &NewMoneyTransaction,
+    ) -> Result<(), DataStoreError> {
+        self.create_money_transactions_with_rules(vec![new_money_transaction.clone()])
+    }
+
+    /// Inserts transactions after applying to each one the first rule
+    /// matching it, tagging them as the rules say.
+    pub fn create_money_transactions_with_rules(
+        &mut self,
+        mut new_money_transactions: Vec<NewMoneyTransaction>,
+    ) -> Result<(), DataStoreError> {
+        self.connection
+            .transaction::<_, DieselError, _>(|conn| {
+                let rules = RuleSet::new(
+                    rule::table
+                        .select(Rule::as_select())
+                        .order(rule::id.asc())
+                        .load(conn)?,
+                );
+                for new_money_transaction in new_money_transactions.iter_mut() {
+                    let rule_tag = rules.apply(new_money_transaction);
+                    let transaction_id = diesel::insert_into(money_transaction::table)
+                        .values(&*new_money_transaction)
+                        .returning(money_transaction::id)
+                        .get_result::<i32>(conn)?;
+                    if let Some(tag_id) = rule_tag {
+                        diesel::insert_or_ignore_into(transaction_tag::table)
+                            .values(&TransactionTag {
+                                transaction_id,
+                                tag_id,
+                            })
+                            .execute(conn)?;
+                    }
+                }
+                Ok(())
+            })
+            .map_err(|e| DataStoreError::InsertError(e.to_string()))
+    }
+
+    /// Category, description and amount of every transaction, used to train
+    /// the category suggestions.
+    pub fn get_category_examples(&mut self) -> Result<Vec<(i32, String, f32)>, DataStoreError> {
+        money_transaction::table
+            .select((
+                money_transaction::transaction_category,
+                money_transaction::description,
+                money_transaction::amount,
+            ))
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
+
+    pub fn get_templates(&mut self) -> Result<Vec<TransactionTemplate>, DataStoreError> {
+        transaction_template::table
+            .select(TransactionTemplate::as_select())
+            .order(transaction_template::template_name.asc())
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
+
+    pub fn create_template(
+        &mut self,
+        new_template: &NewTransactionTemplate,
+    ) -> Result<TransactionTemplate, DataStoreError> {
+        diesel::insert_into(transaction_template::table)
+            .values(new_template)
+            .returning(TransactionTemplate::as_returning())
+            .get_result(&mut self.connection)
+            .map_err(|e| DataStoreError::InsertError(e.to_string()))
+    }
+
+    pub fn delete_template(&mut self, template_id: i32) -> Result<(), DataStoreError> {
+        diesel::delete(
+            transaction_template::table.filter(transaction_template::id.eq(template_id)),
+        )
+        .execute(&mut self.connection)
+        .map_err(|e| DataStoreError::DeleteError(e.to_string()))?;
+        Ok(())
+    }
+
+    pub fn get_rules(&mut self) -> Result<Vec<Rule>, DataStoreError> {
+        rule::table
+            .select(Rule::as_select())
+            .order(rule::id.asc())
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
+
+    pub fn create_rule(&mut self, new_rule: &NewRule) -> Result<Rule, DataStoreError> {
+        diesel::insert_into(rule::table)
+            .values(new_rule)
+            .returning(Rule::as_returning())
+            .get_result(&mut self.connection)
+            .map_err(|e| DataStoreError::InsertError(e.to_string()))
+    }
+
+    pub fn delete_rule(&mut self, rule_id: i32) -> Result<(), DataStoreError> {
+        diesel::delete(rule::table.filter(rule::id.eq(rule_id)))
+            .execute(&mut self.connection)
+            .map_err(|e| DataStoreError::DeleteError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Existing transactions a rule matches, which applying it would change.
+    /// Reconciled transactions are left out, as they can't be modified.
+    pub fn preview_rule(&mut self, rule_id: i32) -> Result<Vec<MoneyTransaction>, DataStoreError> {
+        Self::rule_matches(&mut self.connection, rule_id)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
+
+    /// Applies a rule to every existing transaction it matches, returning
+    /// how many were changed.
+    pub fn apply_rule(&mut self, rule_id: i32) -> Result<usize, DataStoreError> {
+        self.connection
+            .transaction::<_, DieselError, _>(|conn| {
+                let applied: Rule = rule::table
+                    .filter(rule::id.eq(rule_id))
+                    .select(Rule::as_select())
+                    .first(conn)?;
+                let matches = Self::rule_matches(conn, rule_id)?;
+                for t in &matches {
+                    diesel::update(money_transaction::table)
+                        .filter(money_transaction::id.eq(t.id))
+                        .set((
+                            money_transaction::transaction_category
+                                .eq(applied.set_category.unwrap_or(t.transaction_category)),
+                            money_transaction::payee
+                                .eq(applied.set_payee.clone().or(t.payee.clone())),
+                            money_transaction::description
+                                .eq(applied.set_note.clone().unwrap_or(t.description.clone())),
+                        ))
+                        .execute(conn)?;
+                    if let Some(tag_id) = applied.set_tag {
+                        diesel::insert_or_ignore_into(transaction_tag::table)
+                            .values(&TransactionTag {
+                                transaction_id: t.id,
+                                tag_id,
+                            })
+                            .execute(conn)?;
+                    }
+                }
+                Ok(matches.len())
+            })
+            .map_err(|e| DataStoreError::UpdateError(e.to_string()))
+    }
+
+    fn rule_matches(
+        conn: &mut SqliteConnection,
+        rule_id: i32,
+    ) -> Result<Vec<MoneyTransaction>, DieselError> {
+        let matched: Rule = rule::table
+            .filter(rule::id.eq(rule_id))
+            .select(Rule::as_select())
+            .first(conn)?;
+        let mut query = money_transaction::table
+            .filter(money_transaction::status.ne(i32::from(TransactionStatus::Reconciled)))
+            .select(MoneyTransaction::as_select())
+            .order((
+                money_transaction::transaction_date.desc(),
+                money_transaction::id.desc(),
+            ))
+            .into_boxed();
+        if let Some(account_id) = matched.match_account {
+            query = query.filter(money_transaction::bank_account.eq(account_id));
+        }
+        let transactions = query.load(conn)?;
+
+        let rules = RuleSet::new(vec![matched]);
+        Ok(transactions
+            .into_iter()
+            .filter(|t| {
+                rules
+                    .find(t.bank_account, t.amount, &t.description)
+                    .is_some()
+            })
+            .collect())
+    }
+
+    pub fn get_tags(&mut self) -> Result<Vec<Tag>, DataStoreError> {
+        tag::table
+            .select(Tag::as_select())
+            .order(tag::tag_name.asc())
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
+
+    /// The tag with the given name, created if it doesn't exist yet.
+    pub fn get_or_create_tag(&mut self, new_tag_name: &str) -> Result<Tag, DataStoreError> {
+        diesel::insert_or_ignore_into(tag::table)
+            .values(&NewTag {
+                tag_name: new_tag_name.to_string(),
+            })
+            .execute(&mut self.connection)
+            .map_err(|e| DataStoreError::InsertError(e.to_string()))?;
+        tag::table
+            .filter(tag::tag_name.eq(new_tag_name))
+            .select(Tag::as_select())
+            .first(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))
+    }
+
+    /// Tag names of each of the given transactions that has any.
+    pub fn get_transaction_tags(
+        &mut self,
+        transaction_ids: &[i32],
+    ) -> Result<HashMap<i32, Vec<String>>, DataStoreError> {
+        let rows: Vec<(i32, String)> = transaction_tag::table
+            .inner_join(tag::table)
+            .filter(transaction_tag::transaction_id.eq_any(transaction_ids))
+            .select((transaction_tag::transaction_id, tag::tag_name))
+            .order(tag::tag_name.asc())
+            .load(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))?;
+        let mut tags: HashMap<i32, Vec<String>> = HashMap::new();
+        for (transaction_id, tag_name) in rows {
+            tags.entry(transaction_id).or_default().push(tag_name);
+        }
+        Ok(tags)
+    }
+
     pub fn get_currencies(&mut self) -> Result<Vec<Currency>, DataStoreError> {
         let results = currency
             .select(Currency::as_select())
@@ -392,10 +1733,10 @@ impl Store {
         start_date: &NaiveDate,
         end_date: &NaiveDate,
     ) -> Result<Vec<AccountTransfer>, DataStoreError> {
+        let (range_start, range_end) = dates::local_day_range(*start_date, *end_date);
         let results = account_transfer
-            .filter(
-                transfer_date.between(start_date.and_hms(0, 0, 0), end_date.and_hms(23, 59, 59)),
-            )
+            .filter(transfer_date.ge(range_start))
+            .filter(transfer_date.lt(range_end))
             .select(AccountTransfer::as_select())
             .order(transfer_date.desc())
             .load(&mut self.connection);
@@ -421,12 +1762,270 @@ impl Store {
         Ok(())
     }
 
+    /// Inserts transfers, returning their ids in the same order.
+    pub fn create_account_transfers(
+        &mut self,
+        new_account_transfers: &Vec<NewAccountTransfer>,
+    ) -> Result<Vec<i32>, DataStoreError> {
+        self.connection
+            .transaction::<_, DieselError, _>(|conn| {
+                let mut ids = Vec::with_capacity(new_account_transfers.len());
+                for new_account_transfer in new_account_transfers {
+                    ids.push(
+                        diesel::insert_into(account_transfer::table)
+                            .values(new_account_transfer)
+                            .returning(account_transfer::id)
+                            .get_result::<i32>(conn)?,
+                    );
+                }
+                Ok(ids)
+            })
+            .map_err(|e| DataStoreError::InsertError(e.to_string()))
+    }
+
+    /// Runs `f` in one database transaction, committed if it succeeds and
+    /// rolled back if it fails. Store methods called by `f` that open their
+    /// own transaction run as savepoints inside this one.
+    pub fn atomically<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, DataStoreError>,
+    ) -> Result<T, DataStoreError> {
+        AnsiTransactionManager::begin_transaction(&mut self.connection)
+            .map_err(|e| DataStoreError::QueryError(e.to_string()))?;
+        match f(self) {
+            Ok(value) => {
+                AnsiTransactionManager::commit_transaction(&mut self.connection)
+                    .map_err(|e| DataStoreError::QueryError(e.to_string()))?;
+                Ok(value)
+            }
+            Err(e) => {
+                if let Err(rollback_error) =
+                    AnsiTransactionManager::rollback_transaction(&mut self.connection)
+                {
+                    log::error!("Rollback failed: {}", rollback_error);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Points rules, templates, saved filters and CSV profiles at new account
+    /// and category ids after the ledger was replaced, given the new id of
+    /// every old one still there. Templates missing their account or
+    /// category, rules matching an account or setting a category that are
+    /// gone, and saved filters left without any of their accounts or
+    /// categories are deleted. CSV profiles whose account is gone ask for one
+    /// again.
+    pub fn remap_rules_and_templates(
+        &mut self,
+        account_ids: &HashMap<i32, i32>,
+        category_ids: &HashMap<i32, i32>,
+    ) -> Result<(), DataStoreError> {
+        self.connection
+            .transaction::<_, DieselError, _>(|conn| {
+                let templates: Vec<TransactionTemplate> = transaction_template::table
+                    .select(TransactionTemplate::as_select())
+                    .load(conn)?;
+                for template in templates {
+                    let edited = transaction_template::table
+                        .filter(transaction_template::id.eq(template.id));
+                    match (
+                        account_ids.get(&template.bank_account),
+                        category_ids.get(&template.transaction_category),
+                    ) {
+                        (Some(account_id), Some(category_id)) => {
+                            diesel::update(edited)
+                                .set((
+                                    transaction_template::bank_account.eq(account_id),
+                                    transaction_template::transaction_category.eq(category_id),
+                                ))
+                                .execute(conn)?;
+                        }
+                        _ => {
+                            diesel::delete(edited).execute(conn)?;
+                        }
+                    }
+                }
+
+                let rules: Vec<Rule> = rule::table.select(Rule::as_select()).load(conn)?;
+                for r in rules {
+                    let edited = rule::table.filter(rule::id.eq(r.id));
+                    let match_account = r.match_account.map(|a| account_ids.get(&a).copied());
+                    let set_category = r.set_category.map(|c| category_ids.get(&c).copied());
+                    match (match_account, set_category) {
+                        (Some(None), _) | (_, Some(None)) => {
+                            diesel::delete(edited).execute(conn)?;
+                        }
+                        (match_account, set_category) => {
+                            diesel::update(edited)
+                                .set((
+                                    rule::match_account.eq(match_account.flatten()),
+                                    rule::set_category.eq(set_category.flatten()),
+                                ))
+                                .execute(conn)?;
+                        }
+                    }
+                }
+
+                let remap = |ids: &[i32], new_ids: &HashMap<i32, i32>| -> Option<Vec<i32>> {
+                    let remapped: Vec<i32> = ids
+                        .iter()
+                        .filter_map(|id| new_ids.get(id).copied())
+                        .collect();
+                    (ids.is_empty() || !remapped.is_empty()).then_some(remapped)
+                };
+                let saved_filters: Vec<SavedFilter> = saved_filter::table
+                    .select(SavedFilter::as_select())
+                    .load(conn)?;
+                for saved in saved_filters {
+                    let edited = saved_filter::table.filter(saved_filter::id.eq(saved.id));
+                    // Definitions that can't be read are reported when opened.
+                    let Ok(mut filter) = saved.filter() else {
+                        continue;
+                    };
+                    match (
+                        remap(&filter.accounts, account_ids),
+                        remap(&filter.categories, category_ids),
+                    ) {
+                        (Some(accounts), Some(categories)) => {
+                            filter.accounts = accounts;
+                            filter.categories = categories;
+                            let definition = serde_json::to_string(&filter)
+                                .map_err(|e| DieselError::SerializationError(Box::new(e)))?;
+                            diesel::update(edited)
+                                .set(saved_filter::definition.eq(definition))
+                                .execute(conn)?;
+                        }
+                        _ => {
+                            diesel::delete(edited).execute(conn)?;
+                        }
+                    }
+                }
+
+                let profiles: Vec<SavedCsvProfile> = csv_profile::table
+                    .select(SavedCsvProfile::as_select())
+                    .load(conn)?;
+                for saved in profiles {
+                    let Some(mut profile) = saved.profile() else {
+                        continue;
+                    };
+                    profile.account_id = profile
+                        .account_id
+                        .and_then(|id| account_ids.get(&id).copied());
+                    let definition = serde_json::to_string(&profile)
+                        .map_err(|e| DieselError::SerializationError(Box::new(e)))?;
+                    diesel::update(csv_profile::table.filter(csv_profile::id.eq(saved.id)))
+                        .set(csv_profile::definition.eq(definition))
+                        .execute(conn)?;
+                }
+                Ok(())
+            })
+            .map_err(|e| DataStoreError::UpdateError(e.to_string()))
+    }
+
     pub fn drop_all(&mut self) -> Result<(), DataStoreError> {
         log::info!("Deleting all tables...");
-        let _ = diesel::delete(account).execute(&mut self.connection);
-        let _ = diesel::delete(money_transaction).execute(&mut self.connection);
-        let _ = diesel::delete(category).execute(&mut self.connection);
+        self.connection
+            .transaction::<_, DieselError, _>(|conn| {
+                diesel::delete(transaction_tag::table).execute(conn)?;
+                diesel::delete(dismissed_duplicate::table).execute(conn)?;
+                diesel::delete(money_transaction).execute(conn)?;
+                diesel::delete(loan_payment::table).execute(conn)?;
+                diesel::delete(account_transfer::table).execute(conn)?;
+                diesel::delete(loan::table).execute(conn)?;
+                diesel::delete(investment_transaction::table).execute(conn)?;
+                diesel::delete(credit_card::table).execute(conn)?;
+                diesel::delete(statement_account::table).execute(conn)?;
+                diesel::delete(account).execute(conn)?;
+                diesel::delete(category).execute(conn)?;
+                Ok(())
+            })
+            .map_err(|e| DataStoreError::DeleteError(e.to_string()))?;
         log::info!("All tables deleted.");
         Ok(())
     }
 }
+
+/// Pages of transactions returned by [`Store::transaction_pages`]. A page
+/// shorter than the size asked for is the last one.
+pub struct TransactionPages<'a> {
+    store: &'a mut Store,
+    filter: &'a TransactionFilter,
+    size: i64,
+    cursor: Option<TransactionCursor>,
+    done: bool,
+}
+
+impl Iterator for TransactionPages<'_> {
+    type Item = Result<Vec<MoneyTransaction>, DataStoreError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let page = self
+            .store
+            .get_transactions_page(self.filter, self.cursor, self.size);
+        match &page {
+            Ok(page) => {
+                self.done = (page.len() as i64) < self.size;
+                self.cursor = page.last().map(TransactionCursor::from);
+            }
+            Err(_) => self.done = true,
+        }
+        Some(page)
+    }
+}
+
+/// The given transactions and their tags, in a stable order to compare them.
+fn edited_state(
+    conn: &mut SqliteConnection,
+    ids: &[i32],
+) -> Result<(Vec<MoneyTransaction>, Vec<TransactionTag>), DieselError> {
+    let transactions = money_transaction::table
+        .filter(money_transaction::id.eq_any(ids))
+        .order(money_transaction::id.asc())
+        .select(MoneyTransaction::as_select())
+        .load(conn)?;
+    let tags = transaction_tag::table
+        .filter(transaction_tag::transaction_id.eq_any(ids))
+        .order((
+            transaction_tag::transaction_id.asc(),
+            transaction_tag::tag_id.asc(),
+        ))
+        .select(TransactionTag::as_select())
+        .load(conn)?;
+    Ok((transactions, tags))
+}
+
+/// Turns free text into an FTS5 query matching every word as a prefix.
+/// Words are quoted, so operators typed by the user are searched literally.
+fn fts_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split_whitespace()
+        .map(|word| word.replace('"', ""))
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("\"{}\"*", word))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+/// Signed amount a transfer adds to `target_account`, leaving out the
+/// interest part of loan payments.
+pub fn transfer_effect(
+    transfer: &AccountTransfer,
+    target_account: i32,
+    interests: &[(i32, f32)],
+) -> f32 {
+    let mut effect = 0.;
+    if transfer.from_account == target_account {
+        effect -= transfer.amount;
+    }
+    if transfer.to_account == target_account {
+        effect += transfer.amount;
+        if let Some((_, interest)) = interests.iter().find(|(tid, _)| *tid == transfer.id) {
+            effect -= interest;
+        }
+    }
+    effect
+}
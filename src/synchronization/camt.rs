@@ -0,0 +1,426 @@
+use chrono::NaiveDate;
+
+use crate::fl;
+
+use super::{
+    csv::parse_amount,
+    markup::{self, Event},
+    statement::{LedgerBalance, Statement, StatementEntry},
+};
+
+/// Whether the text is an ISO 20022 bank to customer statement (camt.053)
+/// or account report (camt.052).
+pub fn is_camt(text: &str) -> bool {
+    text.contains("BkToCstmrStmt") || text.contains("BkToCstmrAcctRpt")
+}
+
+/// One Ntry of the file.
+#[derive(Default)]
+struct CamtEntry {
+    amount: Option<String>,
+    credit_debit: Option<String>,
+    status: Option<String>,
+    booking_date: Option<String>,
+    entry_reference: Option<String>,
+    servicer_reference: Option<String>,
+    debtor: Option<String>,
+    debtor_iban: Option<String>,
+    creditor: Option<String>,
+    creditor_iban: Option<String>,
+    remittance: Vec<String>,
+    creditor_reference: Option<String>,
+    additional_info: Option<String>,
+}
+
+impl CamtEntry {
+    /// Entries that are pending or only informative are not booked yet.
+    fn is_booked(&self) -> bool {
+        self.status.as_deref().is_none_or(|status| status == "BOOK")
+    }
+
+    fn into_entry(self, number: usize) -> Result<StatementEntry, String> {
+        let invalid = |field: &str| {
+            fl!(
+                "statement-invalid-entry",
+                entry = number,
+                field = field.to_string()
+            )
+        };
+        let date = self
+            .booking_date
+            .as_deref()
+            .and_then(parse_date)
+            .ok_or_else(|| invalid("BookgDt"))?;
+        let amount = self
+            .amount
+            .as_deref()
+            .and_then(|amount| parse_amount(amount, false))
+            .ok_or_else(|| invalid("Amt"))?;
+        let is_debit = match self.credit_debit.as_deref() {
+            Some("DBIT") => true,
+            Some("CRDT") => false,
+            _ => return Err(invalid("CdtDbtInd")),
+        };
+        // The counterparty is who received a debit or sent a credit.
+        let (name, iban) = if is_debit {
+            (self.creditor, self.creditor_iban)
+        } else {
+            (self.debtor, self.debtor_iban)
+        };
+        let payee = name.filter(|name| !name.is_empty()).or(iban);
+        let remittance = self.remittance.join(" ");
+        Ok(StatementEntry {
+            date,
+            amount: if is_debit { -amount } else { amount },
+            description: Some(remittance)
+                .filter(|remittance| !remittance.is_empty())
+                .or(self.creditor_reference)
+                .or(self.additional_info)
+                .or_else(|| payee.clone())
+                .unwrap_or_default(),
+            payee,
+            external_id: self
+                .entry_reference
+                .or(self.servicer_reference)
+                .filter(|id| !id.is_empty()),
+        })
+    }
+}
+
+/// Balance being read, kept when it is the booked balance at the end of
+/// the statement or, in intraday reports, at the time of the report.
+#[derive(Default)]
+struct CamtBalance {
+    code: Option<String>,
+    amount: Option<f32>,
+    credit_debit: Option<String>,
+    date: Option<NaiveDate>,
+}
+
+/// Reads the booked entries of the statements and reports of a CAMT file.
+pub fn parse(text: &str) -> Result<Vec<Statement>, String> {
+    let mut statements = vec![];
+    let mut path: Vec<String> = vec![];
+    let mut statement: Option<Statement> = None;
+    let mut entry: Option<CamtEntry> = None;
+    let mut balance: Option<CamtBalance> = None;
+    let mut number = 0;
+
+    for event in markup::events(text) {
+        match event {
+            Event::Start(name) => {
+                match name.as_str() {
+                    "Stmt" | "Rpt" => statement = Some(Statement::default()),
+                    "Ntry" => entry = Some(CamtEntry::default()),
+                    "Bal" => balance = Some(CamtBalance::default()),
+                    _ => {}
+                }
+                path.push(name);
+            }
+            Event::End(name) => {
+                if let Some(position) = path.iter().rposition(|open| *open == name) {
+                    path.truncate(position);
+                }
+                match name.as_str() {
+                    "Ntry" => {
+                        number += 1;
+                        if let (Some(statement), Some(entry)) = (statement.as_mut(), entry.take()) {
+                            if entry.is_booked() {
+                                statement.entries.push(entry.into_entry(number)?);
+                            }
+                        }
+                    }
+                    "Bal" => {
+                        if let (Some(statement), Some(balance)) =
+                            (statement.as_mut(), balance.take())
+                        {
+                            read_balance(statement, balance);
+                        }
+                    }
+                    "Stmt" | "Rpt" => statements.extend(statement.take()),
+                    _ => {}
+                }
+            }
+            Event::Value {
+                name,
+                text,
+                attributes,
+            } => {
+                if let Some(entry) = entry.as_mut() {
+                    read_entry_value(entry, &path, &name, text);
+                } else if let Some(balance) = balance.as_mut() {
+                    match name.as_str() {
+                        "Cd" if path.last().is_some_and(|open| open == "CdOrPrtry") => {
+                            balance.code = Some(text)
+                        }
+                        "Amt" => balance.amount = parse_amount(&text, false),
+                        "CdtDbtInd" => balance.credit_debit = Some(text),
+                        "Dt" | "DtTm" => balance.date = parse_date(&text),
+                        _ => {}
+                    }
+                } else if let Some(statement) = statement.as_mut() {
+                    let in_account = path.iter().any(|open| open == "Acct");
+                    match name.as_str() {
+                        "IBAN" if in_account => statement.account = Some(text),
+                        "Id" if in_account && path.last().is_some_and(|open| open == "Othr") => {
+                            statement.account.get_or_insert(text);
+                        }
+                        "Ccy" if in_account => statement.currency = Some(text),
+                        _ => {}
+                    }
+                }
+                // The currency of the amounts stands in for the one of the
+                // account when the file leaves it out.
+                if name == "Amt" {
+                    if let Some(statement) = statement.as_mut() {
+                        let currency = attributes.into_iter().find(|(key, _)| key == "Ccy");
+                        if let (None, Some((_, currency))) = (&statement.currency, currency) {
+                            statement.currency = Some(currency);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    // Files cut short still give the statement read so far.
+    statements.extend(statement);
+
+    if statements.is_empty() {
+        return Err(fl!("statement-empty"));
+    }
+    Ok(statements)
+}
+
+fn read_entry_value(entry: &mut CamtEntry, path: &[String], name: &str, text: String) {
+    let parent = path.last().map(String::as_str);
+    // Parties and their accounts hold names and ids nested at varying
+    // depths across versions, so the closest of them tells whose they are.
+    let party = path.iter().rev().find_map(|open| match open.as_str() {
+        "Dbtr" | "Cdtr" | "DbtrAcct" | "CdtrAcct" | "UltmtDbtr" | "UltmtCdtr" => {
+            Some(open.as_str())
+        }
+        _ => None,
+    });
+    match (parent, name) {
+        // Details of batch entries repeat amounts and references of their
+        // own, only the ones of the entry count.
+        (Some("Ntry"), "Amt") => entry.amount = Some(text),
+        (Some("Ntry"), "CdtDbtInd") => entry.credit_debit = Some(text),
+        (Some("Ntry"), "Sts") | (Some("Sts"), "Cd") => entry.status = Some(text),
+        (Some("Ntry"), "NtryRef") => entry.entry_reference = Some(text),
+        (Some("Ntry"), "AcctSvcrRef") => entry.servicer_reference = Some(text),
+        (Some("Ntry"), "AddtlNtryInf") => entry.additional_info = Some(text),
+        (Some("BookgDt"), "Dt" | "DtTm") => entry.booking_date = Some(text),
+        (_, "Ustrd") => entry.remittance.push(text),
+        (Some("CdtrRefInf"), "Ref") => {
+            entry.creditor_reference.get_or_insert(text);
+        }
+        (_, "Nm") => match party {
+            Some("Dbtr") => {
+                entry.debtor.get_or_insert(text);
+            }
+            Some("Cdtr") => {
+                entry.creditor.get_or_insert(text);
+            }
+            _ => {}
+        },
+        (_, "IBAN") => match party {
+            Some("DbtrAcct") => {
+                entry.debtor_iban.get_or_insert(text);
+            }
+            Some("CdtrAcct") => {
+                entry.creditor_iban.get_or_insert(text);
+            }
+            _ => {}
+        },
+        _ => {}
+    }
+}
+
+fn read_balance(statement: &mut Statement, balance: CamtBalance) {
+    let (Some(code), Some(amount), Some(date)) = (balance.code, balance.amount, balance.date)
+    else {
+        return;
+    };
+    let amount = if balance.credit_debit.as_deref() == Some("DBIT") {
+        -amount
+    } else {
+        amount
+    };
+    match code.as_str() {
+        "CLBD" => statement.ledger_balance = Some(LedgerBalance { amount, date }),
+        "ITBD" if statement.ledger_balance.is_none() => {
+            statement.ledger_balance = Some(LedgerBalance { amount, date })
+        }
+        _ => {}
+    }
+}
+
+/// Reads the day of an ISO date such as `2025-01-31` or
+/// `2025-01-31T10:00:00+01:00`.
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(text.get(..10)?, "%Y-%m-%d").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn reads_booked_entries_of_a_statement() {
+        let text = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.02">
+  <BkToCstmrStmt>
+    <GrpHdr><MsgId>M1</MsgId></GrpHdr>
+    <Stmt>
+      <Id>S1</Id>
+      <Acct>
+        <Id><IBAN>DE89370400440532013000</IBAN></Id>
+        <Ccy>EUR</Ccy>
+      </Acct>
+      <Bal>
+        <Tp><CdOrPrtry><Cd>OPBD</Cd></CdOrPrtry></Tp>
+        <Amt Ccy="EUR">100.00</Amt>
+        <CdtDbtInd>CRDT</CdtDbtInd>
+        <Dt><Dt>2025-01-01</Dt></Dt>
+      </Bal>
+      <Bal>
+        <Tp><CdOrPrtry><Cd>CLBD</Cd></CdOrPrtry></Tp>
+        <Amt Ccy="EUR">12.50</Amt>
+        <CdtDbtInd>DBIT</CdtDbtInd>
+        <Dt><Dt>2025-01-03</Dt></Dt>
+      </Bal>
+      <Ntry>
+        <NtryRef>E1</NtryRef>
+        <Amt Ccy="EUR">162.50</Amt>
+        <CdtDbtInd>DBIT</CdtDbtInd>
+        <Sts>BOOK</Sts>
+        <BookgDt><Dt>2025-01-02</Dt></BookgDt>
+        <NtryDtls><TxDtls>
+          <Amt Ccy="EUR">162.50</Amt>
+          <RltdPties>
+            <Dbtr><Nm>Me</Nm></Dbtr>
+            <Cdtr><Nm>Landlord Ltd</Nm></Cdtr>
+            <CdtrAcct><Id><IBAN>IT60X0542811101000000123456</IBAN></Id></CdtrAcct>
+          </RltdPties>
+          <RmtInf><Ustrd>Rent</Ustrd><Ustrd>January</Ustrd></RmtInf>
+        </TxDtls></NtryDtls>
+      </Ntry>
+      <Ntry>
+        <Amt Ccy="EUR">50.00</Amt>
+        <CdtDbtInd>CRDT</CdtDbtInd>
+        <Sts><Cd>BOOK</Cd></Sts>
+        <BookgDt><DtTm>2025-01-03T09:30:00+01:00</DtTm></BookgDt>
+        <AcctSvcrRef>REF2</AcctSvcrRef>
+        <NtryDtls><TxDtls><RltdPties>
+          <DbtrAcct><Id><IBAN>FR7630006000011234567890189</IBAN></Id></DbtrAcct>
+        </RltdPties></TxDtls></NtryDtls>
+      </Ntry>
+      <Ntry>
+        <Amt Ccy="EUR">9.99</Amt>
+        <CdtDbtInd>DBIT</CdtDbtInd>
+        <Sts>PDNG</Sts>
+        <BookgDt><Dt>2025-01-04</Dt></BookgDt>
+      </Ntry>
+    </Stmt>
+  </BkToCstmrStmt>
+</Document>"#;
+        assert!(is_camt(text));
+        let statements = parse(text).unwrap();
+        assert_eq!(statements.len(), 1);
+        let statement = &statements[0];
+        assert_eq!(statement.account.as_deref(), Some("DE89370400440532013000"));
+        assert_eq!(statement.currency.as_deref(), Some("EUR"));
+        assert_eq!(
+            statement.ledger_balance,
+            Some(LedgerBalance {
+                amount: -12.5,
+                date: date(2025, 1, 3)
+            })
+        );
+        assert_eq!(
+            statement.entries,
+            vec![
+                StatementEntry {
+                    date: date(2025, 1, 2),
+                    amount: -162.5,
+                    description: "Rent January".to_string(),
+                    payee: Some("Landlord Ltd".to_string()),
+                    external_id: Some("E1".to_string()),
+                },
+                StatementEntry {
+                    date: date(2025, 1, 3),
+                    amount: 50.,
+                    description: "FR7630006000011234567890189".to_string(),
+                    payee: Some("FR7630006000011234567890189".to_string()),
+                    external_id: Some("REF2".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_an_intraday_report() {
+        let text = r#"<Document><BkToCstmrAcctRpt><Rpt>
+<Acct><Id><Othr><Id>12345678</Id></Othr></Id></Acct>
+<Bal>
+  <Tp><CdOrPrtry><Cd>ITBD</Cd></CdOrPrtry></Tp>
+  <Amt Ccy="CHF">1000</Amt>
+  <CdtDbtInd>CRDT</CdtDbtInd>
+  <Dt><DtTm>2025-02-10T12:00:00</DtTm></Dt>
+</Bal>
+<Ntry>
+  <Amt Ccy="CHF">20.00</Amt>
+  <CdtDbtInd>DBIT</CdtDbtInd>
+  <BookgDt><Dt>2025-02-10</Dt></BookgDt>
+  <AddtlNtryInf>Card payment</AddtlNtryInf>
+</Ntry>
+</Rpt></BkToCstmrAcctRpt></Document>"#;
+        assert!(is_camt(text));
+        let statements = parse(text).unwrap();
+        let statement = &statements[0];
+        assert_eq!(statement.account.as_deref(), Some("12345678"));
+        // taken from the amounts, as the account has no currency
+        assert_eq!(statement.currency.as_deref(), Some("CHF"));
+        assert_eq!(
+            statement.ledger_balance,
+            Some(LedgerBalance {
+                amount: 1000.,
+                date: date(2025, 2, 10)
+            })
+        );
+        assert_eq!(
+            statement.entries,
+            vec![StatementEntry {
+                date: date(2025, 2, 10),
+                amount: -20.,
+                description: "Card payment".to_string(),
+                payee: None,
+                external_id: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn refuses_entries_without_a_credit_debit_indicator() {
+        let entry = |indicator: &str| {
+            format!(
+                "<Document><BkToCstmrStmt><Stmt><Ntry>\
+<Amt>5.00</Amt>{}<BookgDt><Dt>2025-01-02</Dt></BookgDt>\
+</Ntry></Stmt></BkToCstmrStmt></Document>",
+                indicator
+            )
+        };
+
+        assert!(parse(&entry("")).is_err());
+        assert!(parse(&entry("<CdtDbtInd>RVSL</CdtDbtInd>")).is_err());
+        let credit = parse(&entry("<CdtDbtInd>CRDT</CdtDbtInd>")).unwrap();
+        assert_eq!(credit[0].entries[0].amount, 5.);
+        let debit = parse(&entry("<CdtDbtInd>DBIT</CdtDbtInd>")).unwrap();
+        assert_eq!(debit[0].entries[0].amount, -5.);
+    }
+}
@@ -0,0 +1,604 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Separators tried when detecting the delimiter of a file.
+pub const DELIMITERS: [char; 4] = [',', ';', '\t', '|'];
+
+/// Date formats tried when detecting the format of the date column. When
+/// several fit, the first one wins, so day-first formats come before
+/// month-first ones.
+pub const DATE_FORMATS: [&str; 9] = [
+    "%Y-%m-%d", "%d/%m/%Y", "%m/%d/%Y", "%d.%m.%Y", "%d-%m-%Y", "%Y/%m/%d", "%d/%m/%y", "%d.%m.%y",
+    "%Y%m%d",
+];
+
+/// Number of records looked at when detecting the format of a file.
+const SAMPLE_RECORDS: usize = 50;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextEncoding {
+    #[default]
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    /// Windows-1252, a superset of Latin-1 used by many older bank exports.
+    Windows1252,
+}
+
+impl TextEncoding {
+    pub const ALL: [TextEncoding; 4] = [
+        TextEncoding::Utf8,
+        TextEncoding::Utf16Le,
+        TextEncoding::Utf16Be,
+        TextEncoding::Windows1252,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Utf16Le => "UTF-16 LE",
+            TextEncoding::Utf16Be => "UTF-16 BE",
+            TextEncoding::Windows1252 => "Windows-1252",
+        }
+    }
+
+    /// Guesses the encoding from the byte order mark, falling back to
+    /// Windows-1252 when the bytes are not valid UTF-8.
+    pub fn detect(bytes: &[u8]) -> Self {
+        if bytes.starts_with(&[0xFF, 0xFE]) {
+            TextEncoding::Utf16Le
+        } else if bytes.starts_with(&[0xFE, 0xFF]) {
+            TextEncoding::Utf16Be
+        } else if std::str::from_utf8(bytes).is_ok() {
+            TextEncoding::Utf8
+        } else {
+            TextEncoding::Windows1252
+        }
+    }
+
+    /// Decodes the bytes of a file, dropping the byte order mark. Invalid
+    /// sequences become replacement characters.
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        let text = match self {
+            TextEncoding::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+            TextEncoding::Utf16Le | TextEncoding::Utf16Be => {
+                let units: Vec<u16> = bytes
+                    .chunks_exact(2)
+                    .map(|pair| match self {
+                        TextEncoding::Utf16Le => u16::from_le_bytes([pair[0], pair[1]]),
+                        _ => u16::from_be_bytes([pair[0], pair[1]]),
+                    })
+                    .collect();
+                String::from_utf16_lossy(&units)
+            }
+            TextEncoding::Windows1252 => bytes.iter().map(|&b| windows_1252_char(b)).collect(),
+        };
+        text.strip_prefix('\u{feff}')
+            .map(str::to_string)
+            .unwrap_or(text)
+    }
+}
+
+/// Characters of Windows-1252 from 0x80 to 0x9F, where it differs from
+/// Latin-1. Unassigned bytes map to the replacement character.
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '€', '\u{fffd}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{fffd}', 'Ž',
+    '\u{fffd}', '\u{fffd}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{fffd}',
+    'ž', 'Ÿ',
+];
+
+fn windows_1252_char(byte: u8) -> char {
+    match byte {
+        0x80..=0x9F => WINDOWS_1252_HIGH[(byte - 0x80) as usize],
+        _ => byte as char,
+    }
+}
+
+/// How to read the CSV export of a bank and which columns hold what.
+/// Columns are indexes into the records of the file.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CsvProfile {
+    pub encoding: TextEncoding,
+    pub delimiter: char,
+    pub has_header: bool,
+    pub date_format: String,
+    /// Amounts are written like 1.234,56 rather than 1,234.56.
+    pub decimal_comma: bool,
+    pub date_column: Option<usize>,
+    /// Signed amount, negative for expenses.
+    pub amount_column: Option<usize>,
+    /// Money going out, used with `credit_column` instead of a signed amount.
+    pub debit_column: Option<usize>,
+    pub credit_column: Option<usize>,
+    pub description_column: Option<usize>,
+    pub category_column: Option<usize>,
+    /// Account the transactions are imported into.
+    pub account_id: Option<i32>,
+}
+
+impl Default for CsvProfile {
+    fn default() -> Self {
+        Self {
+            encoding: TextEncoding::Utf8,
+            delimiter: ',',
+            has_header: true,
+            date_format: DATE_FORMATS[0].to_string(),
+            decimal_comma: false,
+            date_column: None,
+            amount_column: None,
+            debit_column: None,
+            credit_column: None,
+            description_column: None,
+            category_column: None,
+            account_id: None,
+        }
+    }
+}
+
+impl CsvProfile {
+    /// Guesses the format of a file and the meaning of its columns, from
+    /// the header names when there is a header and from the values
+    /// otherwise.
+    pub fn detect(bytes: &[u8]) -> Self {
+        let encoding = TextEncoding::detect(bytes);
+        let text = encoding.decode(bytes);
+        let delimiter = detect_delimiter(&text);
+        let records = parse_records(&text, delimiter);
+        let sample: Vec<&Vec<String>> = records.iter().take(SAMPLE_RECORDS).collect();
+
+        let has_header = sample.len() > 1
+            && !sample[0].iter().any(|field| is_date(field))
+            && sample[1].iter().any(|field| is_date(field));
+        let rows: Vec<&Vec<String>> = sample.iter().skip(has_header as usize).copied().collect();
+        let column = |index: usize| rows.iter().filter_map(move |row| row.get(index));
+        let columns = sample.first().map(|record| record.len()).unwrap_or(0);
+
+        let mut profile = CsvProfile {
+            encoding,
+            delimiter,
+            has_header,
+            ..Default::default()
+        };
+
+        if has_header {
+            let headers: Vec<String> = sample[0].iter().map(|h| h.to_lowercase()).collect();
+            let find = |names: &[&str]| {
+                headers
+                    .iter()
+                    .position(|header| names.iter().any(|name| header.contains(name)))
+            };
+            profile.date_column = find(&["date", "data", "datum", "fecha"]);
+            profile.debit_column = find(&["debit", "addebit", "uscit", "soll", "withdraw"]);
+            profile.credit_column = find(&["credit", "accredit", "entrat", "haben", "deposit"]);
+            // Headers like "Debit amount" name the debit column, not a signed amount.
+            profile.amount_column = find(&["amount", "importo", "betrag", "belopp", "montant"])
+                .filter(|&i| Some(i) != profile.debit_column && Some(i) != profile.credit_column);
+            profile.description_column = find(&[
+                "description",
+                "descrizione",
+                "causale",
+                "memo",
+                "verwendungszweck",
+                "text",
+                "payee",
+            ]);
+            profile.category_column = find(&["category", "categoria", "kategori"]);
+        }
+
+        if profile.date_column.is_none() {
+            profile.date_column = (0..columns)
+                .find(|&i| column(i).all(|value| is_date(value)) && column(i).count() > 0);
+        }
+        if profile.amount_column.is_none() && profile.debit_column.is_none() {
+            profile.amount_column = (0..columns).find(|&i| {
+                Some(i) != profile.date_column
+                    && column(i).count() > 0
+                    && column(i).all(|value| looks_like_amount(value))
+            });
+        }
+        if profile.description_column.is_none() {
+            profile.description_column = (0..columns)
+                .filter(|&i| {
+                    Some(i) != profile.date_column
+                        && Some(i) != profile.amount_column
+                        && !column(i).all(|value| looks_like_amount(value))
+                })
+                .max_by_key(|&i| column(i).map(|value| value.len()).sum::<usize>());
+        }
+
+        if let Some(date_column) = profile.date_column {
+            if let Some(format) = detect_date_format(column(date_column).map(String::as_str)) {
+                profile.date_format = format.to_string();
+            }
+        }
+        let amount_columns = [
+            profile.amount_column,
+            profile.debit_column,
+            profile.credit_column,
+        ];
+        profile.decimal_comma = detect_decimal_comma(
+            amount_columns
+                .iter()
+                .flatten()
+                .flat_map(|&i| column(i))
+                .map(String::as_str),
+        );
+        profile
+    }
+
+    /// Decodes and splits a file with the format of this profile.
+    pub fn read_records(&self, bytes: &[u8]) -> Vec<Vec<String>> {
+        parse_records(&self.encoding.decode(bytes), self.delimiter)
+    }
+
+    /// Turns the records of a file into rows, with an error naming the line
+    /// for each record that can't be read. The header, if any, is skipped.
+    pub fn parse_rows(&self, records: &[Vec<String>]) -> Vec<Result<CsvRow, String>> {
+        records
+            .iter()
+            .enumerate()
+            .skip(self.has_header as usize)
+            .map(|(index, record)| {
+                self.parse_row(record)
+                    .map_err(|e| format!("Line {}: {}", index + 1, e))
+            })
+            .collect()
+    }
+
+    fn parse_row(&self, record: &[String]) -> Result<CsvRow, String> {
+        let field = |column: Option<usize>| {
+            column
+                .and_then(|i| record.get(i))
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+        };
+
+        let date_text = field(self.date_column).ok_or("missing date")?;
+        let date = NaiveDate::parse_from_str(date_text, &self.date_format)
+            .map_err(|_| format!("invalid date '{}'", date_text))?;
+
+        let read_amount = |column: Option<usize>| -> Result<Option<f32>, String> {
+            field(column)
+                .map(|text| {
+                    parse_amount(text, self.decimal_comma)
+                        .ok_or_else(|| format!("invalid amount '{}'", text))
+                })
+                .transpose()
+        };
+        let amount = match (self.amount_column, self.debit_column, self.credit_column) {
+            (Some(_), _, _) => read_amount(self.amount_column)?.ok_or("missing amount")?,
+            (None, None, None) => return Err("no amount column".to_string()),
+            _ => {
+                let debit = read_amount(self.debit_column)?.map(f32::abs);
+                let credit = read_amount(self.credit_column)?.map(f32::abs);
+                match (debit, credit) {
+                    (None, None) => return Err("missing amount".to_string()),
+                    (debit, credit) => credit.unwrap_or(0.) - debit.unwrap_or(0.),
+                }
+            }
+        };
+
+        Ok(CsvRow {
+            date,
+            amount,
+            description: field(self.description_column)
+                .unwrap_or_default()
+                .to_string(),
+            category: field(self.category_column).map(str::to_string),
+        })
+    }
+}
+
+/// A transaction read from a CSV file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CsvRow {
+    pub date: NaiveDate,
+    /// Negative for expenses.
+    pub amount: f32,
+    pub description: String,
+    pub category: Option<String>,
+}
+
+/// The separator giving the same number of fields, more than one, on the
+/// most lines.
+pub fn detect_delimiter(text: &str) -> char {
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .take(SAMPLE_RECORDS)
+        .collect();
+    DELIMITERS
+        .iter()
+        .copied()
+        .max_by_key(|&delimiter| {
+            let counts: Vec<usize> = lines
+                .iter()
+                .map(|line| parse_records(line, delimiter).first().map_or(0, Vec::len))
+                .collect();
+            let first = counts.first().copied().unwrap_or(0);
+            if first < 2 {
+                0
+            } else {
+                counts.iter().filter(|&&count| count == first).count()
+            }
+        })
+        .unwrap_or(',')
+}
+
+/// Splits CSV text into records. Fields may be quoted with double quotes,
+/// doubled inside a quoted field, and quoted fields may span lines. Blank
+/// lines are skipped.
+pub fn parse_records(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                in_quotes = true;
+            }
+            '\r' => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|f| !f.trim().is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                } else {
+                    record.clear();
+                }
+            }
+            c if c == delimiter => record.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    record.push(field);
+    if record.iter().any(|f| !f.trim().is_empty()) {
+        records.push(record);
+    }
+    records
+}
+
+/// Joins fields into a CSV line, quoting the fields that hold the
+/// delimiter, quotes or line breaks.
+pub fn format_record<S: AsRef<str>>(fields: &[S], delimiter: char) -> String {
+    fields
+        .iter()
+        .map(|field| {
+            let field = field.as_ref();
+            if field.contains([delimiter, '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(&delimiter.to_string())
+}
+
+/// A date format written the way users read it, like DD/MM/YYYY.
+pub fn date_format_label(format: &str) -> String {
+    format
+        .replace("%Y", "YYYY")
+        .replace("%y", "YY")
+        .replace("%m", "MM")
+        .replace("%d", "DD")
+}
+
+/// The first format that reads every non-empty value.
+pub fn detect_date_format<'a>(
+    values: impl Iterator<Item = &'a str> + Clone,
+) -> Option<&'static str> {
+    DATE_FORMATS.iter().copied().find(|format| {
+        let mut values = values
+            .clone()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .peekable();
+        values.peek().is_some()
+            && values.all(|value| NaiveDate::parse_from_str(value, format).is_ok())
+    })
+}
+
+fn is_date(value: &str) -> bool {
+    let value = value.trim();
+    // Plain numbers like 20240131 would also be amounts.
+    value.contains(|c: char| !c.is_ascii_digit())
+        && DATE_FORMATS
+            .iter()
+            .any(|format| NaiveDate::parse_from_str(value, format).is_ok())
+}
+
+fn looks_like_amount(value: &str) -> bool {
+    let value = value.trim();
+    value.is_empty() || (parse_amount(value, false).is_some() && !is_date(value))
+}
+
+/// Whether the amounts use a comma before the decimals, judged by the
+/// last separator of the values having both or a comma followed by one or
+/// two digits.
+pub fn detect_decimal_comma<'a>(values: impl Iterator<Item = &'a str>) -> bool {
+    let mut comma = 0;
+    let mut dot = 0;
+    for value in values {
+        let last = value.rfind(['.', ',']);
+        match last {
+            Some(i) if value[i..].starts_with(',') => {
+                let decimals = value[i + 1..].chars().filter(char::is_ascii_digit).count();
+                if value.contains('.') || decimals <= 2 {
+                    comma += 1;
+                }
+            }
+            Some(_) => dot += 1,
+            None => {}
+        }
+    }
+    comma > dot
+}
+
+/// Reads an amount such as `-1.234,56`, `(12.00)`, `€ 5` or `5 EUR`.
+/// Thousands separators, spaces and currency symbols are ignored, and
+/// parentheses mean a negative amount.
+pub fn parse_amount(text: &str, decimal_comma: bool) -> Option<f32> {
+    let text = text.trim();
+    // Letters are only allowed in a currency code before or after the number.
+    let number = text.trim_matches(|c: char| c.is_alphabetic() || c.is_whitespace());
+    if number.contains(char::is_alphabetic)
+        || text.chars().filter(|c| c.is_alphabetic()).count() > 3
+    {
+        return None;
+    }
+    let negative_parentheses = text.starts_with('(') && text.ends_with(')');
+    let (decimal, thousands) = if decimal_comma {
+        (',', '.')
+    } else {
+        ('.', ',')
+    };
+    let normalized: String = text
+        .chars()
+        .filter(|&c| c != thousands)
+        .filter_map(|c| match c {
+            c if c == decimal => Some('.'),
+            '0'..='9' | '-' | '+' => Some(c),
+            _ => None,
+        })
+        .collect();
+    let amount: f32 = normalized.parse().ok()?;
+    if !amount.is_finite() {
+        return None;
+    }
+    Some(if negative_parentheses {
+        -amount
+    } else {
+        amount
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    const ITALIAN: &str = "\
+Data;Descrizione;Importo
+31/01/2024;\"Supermercato; Roma\";-1.234,56
+01/02/2024;Stipendio;2.500,00
+";
+
+    const DEBIT_CREDIT: &str = "\
+Date,Description,Debit,Credit
+2024-03-05,Coffee,3.50,
+2024-03-06,\"Refund \"\"shop\"\"\",,12.00
+";
+
+    #[test]
+    fn detects_a_semicolon_file_with_decimal_commas() {
+        let profile = CsvProfile::detect(ITALIAN.as_bytes());
+
+        assert_eq!(profile.delimiter, ';');
+        assert!(profile.has_header);
+        assert!(profile.decimal_comma);
+        assert_eq!(profile.date_format, "%d/%m/%Y");
+        assert_eq!(profile.date_column, Some(0));
+        assert_eq!(profile.description_column, Some(1));
+        assert_eq!(profile.amount_column, Some(2));
+
+        let rows: Vec<CsvRow> = profile
+            .parse_rows(&profile.read_records(ITALIAN.as_bytes()))
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                CsvRow {
+                    date: date(2024, 1, 31),
+                    amount: -1234.56,
+                    description: "Supermercato; Roma".to_string(),
+                    category: None,
+                },
+                CsvRow {
+                    date: date(2024, 2, 1),
+                    amount: 2500.,
+                    description: "Stipendio".to_string(),
+                    category: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn debit_and_credit_columns() {
+        let profile = CsvProfile::detect(DEBIT_CREDIT.as_bytes());
+
+        assert_eq!(profile.delimiter, ',');
+        assert!(!profile.decimal_comma);
+        assert_eq!(profile.amount_column, None);
+        assert_eq!(profile.debit_column, Some(2));
+        assert_eq!(profile.credit_column, Some(3));
+
+        let rows = profile.parse_rows(&profile.read_records(DEBIT_CREDIT.as_bytes()));
+        let rows: Vec<CsvRow> = rows.into_iter().map(Result::unwrap).collect();
+        assert_eq!(rows[0].amount, -3.5);
+        assert_eq!(rows[1].amount, 12.);
+        assert_eq!(rows[1].description, "Refund \"shop\"");
+    }
+
+    #[test]
+    fn bad_rows_name_their_line() {
+        let profile = CsvProfile::detect(ITALIAN.as_bytes());
+        let records = parse_records("Data;Descrizione;Importo\n32/01/2024;x;1\n", ';');
+
+        assert_eq!(
+            profile.parse_rows(&records),
+            vec![Err("Line 2: invalid date '32/01/2024'".to_string())]
+        );
+    }
+
+    #[test]
+    fn decimal_comma_guess() {
+        assert!(detect_decimal_comma(["1.234,56", "-3,5"].into_iter()));
+        assert!(!detect_decimal_comma(["1,234.56", "-3.50"].into_iter()));
+        // three digits after a lone comma are thousands
+        assert!(!detect_decimal_comma(["1,234", "12.50"].into_iter()));
+        assert!(!detect_decimal_comma(["100", "200"].into_iter()));
+    }
+
+    #[test]
+    fn amounts() {
+        assert_eq!(parse_amount("-1.234,56", true), Some(-1234.56));
+        assert_eq!(parse_amount("1,234.56", false), Some(1234.56));
+        assert_eq!(parse_amount("(12.00)", false), Some(-12.));
+        assert_eq!(parse_amount("€ 5", false), Some(5.));
+        assert_eq!(parse_amount("5 EUR", false), Some(5.));
+        assert_eq!(parse_amount("abc", false), None);
+        assert_eq!(parse_amount("12 apples", false), None);
+    }
+
+    #[test]
+    fn quoted_fields_round_trip() {
+        let fields = ["a,b", "say \"hi\"", "two\nlines", "plain"];
+        let line = format_record(&fields, ',');
+
+        assert_eq!(
+            parse_records(&line, ','),
+            vec![fields.map(String::from).to_vec()]
+        );
+    }
+}
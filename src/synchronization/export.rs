@@ -1,17 +1,92 @@
-use std::{fs::File, io::Write};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
 
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone};
 use cosmic::dialog::ashpd::url::Url;
+use serde::{ser::Error, ser::SerializeSeq, Serialize, Serializer};
 
-use crate::{config::Config, STORE};
+use crate::{
+    config::Config,
+    fl,
+    models::{
+        Account, AccountGroup, AccountTransfer, Category, CreditCard, InvestmentTransaction, Loan,
+        LoanPayment, Security, SecurityPrice, TransactionFilter, TransactionStatus,
+    },
+    store::Store,
+    STORE,
+};
 
-use super::model::SyncModel;
+use super::{csv::format_record, model::FORMAT_VERSION, qif};
+
+const EXPORT_PAGE_SIZE: i64 = 1000;
+
+/// Same layout as `SyncModel`, with the transactions written straight from
+/// the database a page at a time instead of being loaded all at once.
+#[derive(Serialize)]
+struct ExportModel<'a> {
+    format_version: u32,
+    app_version: &'static str,
+    groups: Vec<AccountGroup>,
+    accounts: Vec<Account>,
+    categories: Vec<Category>,
+    transactions: PagedTransactions<'a>,
+    transfers: Vec<AccountTransfer>,
+    loans: Vec<Loan>,
+    loan_payments: Vec<LoanPayment>,
+    credit_cards: Vec<CreditCard>,
+    securities: Vec<Security>,
+    security_prices: Vec<SecurityPrice>,
+    investment_transactions: Vec<InvestmentTransaction>,
+    currency: String,
+}
+
+impl<'a> ExportModel<'a> {
+    /// Everything a replace import needs to rebuild the ledger, with the
+    /// transactions read from the store while the model is written.
+    fn new(store: &'a mut Store, currency: String) -> Self {
+        Self {
+            format_version: FORMAT_VERSION,
+            app_version: env!("CARGO_PKG_VERSION"),
+            groups: store.get_account_groups().unwrap_or(vec![]),
+            accounts: store.get_accounts().unwrap_or(vec![]),
+            categories: store.get_categories().unwrap_or(vec![]),
+            transfers: store.get_transfers().unwrap_or(vec![]),
+            loans: store.get_loans().unwrap_or(vec![]),
+            loan_payments: store.get_all_loan_payments().unwrap_or(vec![]),
+            credit_cards: store.get_credit_cards().unwrap_or(vec![]),
+            securities: store.get_securities().unwrap_or(vec![]),
+            security_prices: store.get_security_prices().unwrap_or(vec![]),
+            investment_transactions: store.get_all_investment_transactions().unwrap_or(vec![]),
+            currency,
+            transactions: PagedTransactions(RefCell::new(store)),
+        }
+    }
+}
+
+struct PagedTransactions<'a>(RefCell<&'a mut Store>);
+
+impl Serialize for PagedTransactions<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut store = self.0.borrow_mut();
+        let filter = TransactionFilter::default();
+        let mut seq = serializer.serialize_seq(None)?;
+        for page in store.transaction_pages(&filter, EXPORT_PAGE_SIZE) {
+            for transaction in &page.map_err(S::Error::custom)? {
+                seq.serialize_element(transaction)?;
+            }
+        }
+        seq.end()
+    }
+}
 
 pub fn export_to_folder(url: Url) -> Result<(), String> {
     let mut store = STORE.lock().unwrap();
     let config = Config::load();
-    let accounts = store.get_accounts();
-    let categories = store.get_categories();
-    let transactions = store.get_money_transactions();
     let currencies = store.get_currencies();
 
     let currency = if let Ok(currencies) = currencies {
@@ -23,30 +98,367 @@ pub fn export_to_folder(url: Url) -> Result<(), String> {
         "USD".to_string()
     };
 
-    let sync_model = SyncModel {
-        accounts: accounts.unwrap_or(vec![]),
-        categories: categories.unwrap_or(vec![]),
-        transactions: transactions.unwrap_or(vec![]),
-        currency,
+    let export_model = ExportModel::new(&mut store, currency);
+
+    let Ok(path) = url.to_file_path() else {
+        return Err("Failed to read destination folder".to_string());
+    };
+    let target = path.join("exported.json");
+    let Ok(file) = File::create(&target) else {
+        return Err("Failed to create file".to_string());
+    };
+    let mut writer = BufWriter::new(file);
+    match serde_json::to_writer(&mut writer, &export_model) {
+        Ok(_) => match writer.flush() {
+            Ok(_) => {
+                log::info!("file exported");
+                Ok(())
+            }
+            Err(_) => Err("Failed to create file".to_string()),
+        },
+        Err(_) => Err("Failed to serialize files".to_string()),
+    }
+}
+
+/// What a CSV export writes and how.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CsvExportOptions {
+    pub delimiter: char,
+    pub date_format: String,
+    /// Only the transactions and transfers between these local dates.
+    pub date_range: Option<(NaiveDate, NaiveDate)>,
+    /// Only the transactions of this account and the transfers touching it.
+    pub account_id: Option<i32>,
+}
+
+/// Writes `transactions.csv` and `transfers.csv` to a folder, naming
+/// accounts and categories instead of using their ids. Returns how many
+/// transactions and transfers were written.
+pub fn export_csv_to_folder(
+    url: Url,
+    options: &CsvExportOptions,
+) -> Result<(usize, usize), String> {
+    let path = url
+        .to_file_path()
+        .map_err(|_| "Failed to read destination folder".to_string())?;
+    let mut store = STORE.lock().unwrap();
+    let account_names: HashMap<i32, String> = store
+        .get_accounts()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|a| (a.id, a.name))
+        .collect();
+    let category_names: HashMap<i32, String> = store
+        .get_categories()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|c| (c.id, c.name))
+        .collect();
+    let name = |names: &HashMap<i32, String>, id: i32| names.get(&id).cloned().unwrap_or_default();
+    let date = |date: &NaiveDateTime| {
+        Local
+            .from_utc_datetime(date)
+            .format(&options.date_format)
+            .to_string()
     };
 
-    match serde_json::to_string(&sync_model) {
-        Ok(serialized) => {
-            if let Ok(path) = url.to_file_path() {
-                let target = path.join("exported.json");
-                match File::create(&target)
-                    .and_then(|mut file| file.write_all(serialized.as_bytes()))
-                {
-                    Ok(_) => {
-                        log::info!("file exported");
-                        Ok(())
-                    }
-                    Err(_) => Err("Failed to create file".to_string()),
-                }
-            } else {
-                Err("Failed to read destination folder".to_string())
+    let mut filter =
+        TransactionFilter::default().accounts(options.account_id.into_iter().collect());
+    filter.date_range = options.date_range;
+    let mut transactions = 0;
+    write_file(&path.join("transactions.csv"), |writer| {
+        let header = [
+            fl!("date"),
+            fl!("bank-account"),
+            fl!("category"),
+            fl!("description"),
+            fl!("payee"),
+            fl!("amount"),
+            fl!("status"),
+        ];
+        writeln!(writer, "{}", format_record(&header, options.delimiter))?;
+        for page in store.transaction_pages(&filter, EXPORT_PAGE_SIZE) {
+            let page = page.map_err(std::io::Error::other)?;
+            for t in &page {
+                let amount = if t.is_expense { -t.amount } else { t.amount };
+                let record = [
+                    date(&t.transaction_date),
+                    name(&account_names, t.bank_account),
+                    name(&category_names, t.transaction_category),
+                    t.description.clone(),
+                    t.payee.clone().unwrap_or_default(),
+                    format!("{:.2}", amount),
+                    status_name(t.status()),
+                ];
+                writeln!(writer, "{}", format_record(&record, options.delimiter))?;
             }
+            transactions += page.len();
         }
-        Err(_) => Err("Failed to serialize files".to_string()),
+        Ok(())
+    })?;
+
+    let transfers = match options.date_range {
+        Some((first, last)) => store.get_transfers_date_range(&first, &last),
+        None => store.get_transfers(),
+    }
+    .map_err(|e| e.to_string())?;
+    let transfers: Vec<AccountTransfer> = transfers
+        .into_iter()
+        .filter(|t| {
+            options
+                .account_id
+                .is_none_or(|id| t.from_account == id || t.to_account == id)
+        })
+        .collect();
+    write_file(&path.join("transfers.csv"), |writer| {
+        let header = [
+            fl!("date"),
+            fl!("from-account"),
+            fl!("to-account"),
+            fl!("description"),
+            fl!("amount"),
+            fl!("from-account-status"),
+            fl!("to-account-status"),
+        ];
+        writeln!(writer, "{}", format_record(&header, options.delimiter))?;
+        for t in &transfers {
+            let record = [
+                date(&t.transfer_date),
+                name(&account_names, t.from_account),
+                name(&account_names, t.to_account),
+                t.description.clone().unwrap_or_default(),
+                format!("{:.2}", t.amount),
+                status_name(t.status_for(t.from_account)),
+                status_name(t.status_for(t.to_account)),
+            ];
+            writeln!(writer, "{}", format_record(&record, options.delimiter))?;
+        }
+        Ok(())
+    })?;
+
+    log::info!(
+        "{} transactions and {} transfers exported to csv",
+        transactions,
+        transfers.len()
+    );
+    Ok((transactions, transfers.len()))
+}
+
+/// Writes every account to `exported.qif`, after the list of accounts and
+/// categories, with transfers written in both accounts they touch. Returns
+/// how many accounts were written.
+pub fn export_qif_to_folder(url: Url) -> Result<usize, String> {
+    let path = url
+        .to_file_path()
+        .map_err(|_| "Failed to read destination folder".to_string())?;
+    let mut store = STORE.lock().unwrap();
+    let accounts = store.get_accounts().map_err(|e| e.to_string())?;
+    let categories = store.get_categories().map_err(|e| e.to_string())?;
+    let transfers = store.get_transfers().map_err(|e| e.to_string())?;
+    let account_names: HashMap<i32, String> =
+        accounts.iter().map(|a| (a.id, a.name.clone())).collect();
+    let category_names: HashMap<i32, String> =
+        categories.iter().map(|c| (c.id, c.name.clone())).collect();
+
+    write_file(&path.join("exported.qif"), |writer| {
+        qif::write_lists(writer, &accounts, &categories)?;
+        for account in &accounts {
+            let filter = TransactionFilter::default().accounts(vec![account.id]);
+            let mut transactions = vec![];
+            for page in store.transaction_pages(&filter, EXPORT_PAGE_SIZE) {
+                transactions.extend(page.map_err(std::io::Error::other)?);
+            }
+            let account_transfers: Vec<AccountTransfer> = transfers
+                .iter()
+                .filter(|t| t.from_account == account.id || t.to_account == account.id)
+                .cloned()
+                .collect();
+            qif::write_account(
+                writer,
+                account,
+                &transactions,
+                &account_transfers,
+                &account_names,
+                &category_names,
+            )?;
+        }
+        Ok(())
+    })?;
+
+    log::info!("{} accounts exported to qif", accounts.len());
+    Ok(accounts.len())
+}
+
+fn write_file(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> std::io::Result<()>,
+) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| format!("Failed to create file: {}", e))?;
+    let mut writer = BufWriter::new(file);
+    write(&mut writer)
+        .and_then(|_| writer.flush())
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn status_name(status: TransactionStatus) -> String {
+    match status {
+        TransactionStatus::Uncleared => fl!("status-uncleared"),
+        TransactionStatus::Cleared => fl!("status-cleared"),
+        TransactionStatus::Reconciled => fl!("status-reconciled"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::{
+            AccountType, InvestmentKind, NewAccount, NewAccountTransfer, NewCategory,
+            NewCreditCard, NewInvestmentTransaction, NewLoan, NewMoneyTransaction,
+        },
+        synchronization::{merge::replace_with, model::SyncModel},
+        utils::amortization::PaymentFrequency,
+    };
+
+    use super::*;
+
+    fn date(day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 3, day)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+    }
+
+    fn new_account(name: &str, initial_balance: f32, account_type: AccountType) -> NewAccount {
+        NewAccount {
+            name: name.to_string(),
+            initial_balance,
+            account_description: String::new(),
+            account_type: account_type.into(),
+            group_id: None,
+            exclude_from_total: false,
+            exclude_from_statistics: false,
+        }
+    }
+
+    fn new_transfer(from_account: i32, to_account: i32, amount: f32) -> NewAccountTransfer {
+        NewAccountTransfer {
+            from_account,
+            to_account,
+            transfer_date: date(10),
+            amount,
+            description: None,
+            status: TransactionStatus::Cleared.into(),
+            to_status: TransactionStatus::Cleared.into(),
+        }
+    }
+
+    fn balances(store: &mut Store) -> HashMap<String, f32> {
+        store
+            .get_accounts()
+            .unwrap()
+            .into_iter()
+            .map(|a| (a.name, store.get_account_balance(a.id).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn replace_import_keeps_every_balance() {
+        let mut store = Store::in_memory();
+        let ids = store
+            .create_accounts(&vec![
+                new_account("Checking", 2000., AccountType::Checking),
+                new_account("Broker", 0., AccountType::Investment),
+            ])
+            .unwrap();
+        let (checking, broker) = (ids[0], ids[1]);
+        store
+            .create_loan(
+                &new_account("Mortgage", -10000., AccountType::Loan),
+                &NewLoan {
+                    account_id: 0,
+                    principal: 10000.,
+                    interest_rate: 3.,
+                    term: 120,
+                    payment_frequency: PaymentFrequency::Monthly.payments_per_year(),
+                    start_date: date(1),
+                },
+            )
+            .unwrap();
+        store
+            .create_credit_card(
+                &new_account("Card", 0., AccountType::Credit),
+                &NewCreditCard {
+                    account_id: 0,
+                    closing_day: 31,
+                    due_day: 15,
+                },
+            )
+            .unwrap();
+        let accounts = store.get_accounts().unwrap();
+        let id_of = |name: &str| accounts.iter().find(|a| a.name == name).unwrap().id;
+        let (mortgage, card) = (id_of("Mortgage"), id_of("Card"));
+
+        let categories = store
+            .create_categories(&vec![NewCategory {
+                name: "Groceries".to_string(),
+                is_income: false,
+                category_description: String::new(),
+            }])
+            .unwrap();
+        store
+            .create_money_transactions(&vec![NewMoneyTransaction {
+                bank_account: card,
+                transaction_category: categories[0],
+                description: "Market".to_string(),
+                amount: 120.5,
+                transaction_date: date(5),
+                is_expense: true,
+                status: TransactionStatus::Cleared.into(),
+                payee: None,
+                external_id: None,
+            }])
+            .unwrap();
+        store
+            .create_account_transfer(&new_transfer(checking, card, 120.5))
+            .unwrap();
+        store
+            .create_account_transfer(&new_transfer(checking, broker, 1000.))
+            .unwrap();
+        let loan = store.get_loans().unwrap()[0].id;
+        store
+            .create_loan_payment(&new_transfer(checking, mortgage, 100.), loan, 75., 25.)
+            .unwrap();
+
+        let acme = store.get_or_create_security("ACME").unwrap().id;
+        for (kind, quantity, amount, day) in [
+            (InvestmentKind::Buy, 10., 500., 12),
+            (InvestmentKind::Sell, 4., 240., 20),
+            (InvestmentKind::Dividend, 0., 12.25, 25),
+        ] {
+            store
+                .create_investment_transaction(&NewInvestmentTransaction {
+                    account_id: broker,
+                    security_id: acme,
+                    kind: kind.into(),
+                    quantity,
+                    price: if quantity > 0. { amount / quantity } else { 0. },
+                    amount,
+                    transaction_date: date(day),
+                })
+                .unwrap();
+        }
+
+        let before = balances(&mut store);
+        let value = serde_json::to_value(ExportModel::new(&mut store, "EUR".to_string())).unwrap();
+        let data = SyncModel::from_json(value).unwrap();
+        assert!(data.preview().problems.is_empty());
+
+        replace_with(&mut store, &data).unwrap();
+
+        assert_eq!(balances(&mut store), before);
+        assert_eq!(store.get_loans().unwrap().len(), 1);
+        assert_eq!(store.get_all_loan_payments().unwrap().len(), 1);
+        assert_eq!(store.get_credit_cards().unwrap().len(), 1);
+        assert_eq!(store.get_all_investment_transactions().unwrap().len(), 3);
     }
 }
@@ -2,88 +2,124 @@ use std::{fs::File, io::BufReader};
 
 use cosmic::dialog::ashpd::url::Url;
 
-use crate::{
-    config::Config,
-    models::{NewAccount, NewCategory, NewMoneyTransaction},
-    STORE,
+use crate::{config::Config, STORE};
+
+use super::{
+    csv::TextEncoding,
+    merge::{merge_into, replace_with, ImportReport},
+    model::SyncModel,
+    qif::read_qif,
 };
 
-use super::model::SyncModel;
+/// What an import does with the data already in the database.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImportMode {
+    /// Wipes the database and loads the file in its place.
+    Replace,
+    /// Adds what the file has and the database lacks, keeping the rest.
+    Merge,
+}
 
-pub fn import_from_json(url: &Url) -> Result<(), String> {
-    log::info!("Starting import from JSON at URL: {:?}", url);
+/// Reads and upgrades an exported file without touching the database.
+pub fn read_import_file(url: &Url) -> Result<SyncModel, String> {
+    log::info!("Reading import file at URL: {:?}", url);
 
-    match File::open(url.to_file_path().unwrap()) {
-        Ok(file) => {
-            log::info!("File opened successfully.");
-            let reader = BufReader::new(file);
+    let path = url
+        .to_file_path()
+        .map_err(|_| format!("{} is not a local file.", url))?;
+    let file = File::open(&path).map_err(|e| {
+        log::error!("Failed to open file at URL: {:?}, error: {:?}", url, e);
+        format!("Failed to open file: {}", e)
+    })?;
 
-            let deserialized: SyncModel = if let Ok(data) = serde_json::from_reader(reader) {
-                log::info!("Deserialization successful.");
-                data
-            } else {
-                log::warn!("Failed to deserialize JSON, using default SyncModel.");
-                SyncModel::default()
-            };
+    let data = serde_json::from_reader(BufReader::new(file))
+        .map_err(|e| e.to_string())
+        .and_then(SyncModel::from_json)
+        .map_err(|e| {
+            log::error!("Failed to deserialize JSON: {}", e);
+            format!("Invalid data file: {}", e)
+        })?;
+    log::info!(
+        "Deserialization successful, file written by version {:?}.",
+        data.app_version
+    );
+    Ok(data)
+}
 
-            let mut config = Config::load();
-            log::info!("Config loaded successfully.");
+/// Reads a QIF file into the shape of an export, to be merged into the
+/// database. Entries of a file without account headers go to an account
+/// named after the file.
+pub fn read_qif_file(url: &Url) -> Result<SyncModel, String> {
+    log::info!("Reading QIF file at URL: {:?}", url);
 
-            let mut store = STORE.lock().unwrap();
-            log::info!("STORE lock acquired.");
+    let path = url
+        .to_file_path()
+        .map_err(|_| format!("{} is not a local file.", url))?;
+    let bytes = std::fs::read(&path).map_err(|e| format!("Failed to open file: {}", e))?;
+    // Older programs write QIF files in their platform encoding rather than
+    // UTF-8.
+    let text = TextEncoding::detect(&bytes).decode(&bytes);
+    let default_account = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
 
-            let _ = store.drop_all();
+    let mut store = STORE.lock().unwrap();
+    let accounts = store.get_accounts().map_err(|e| e.to_string())?;
+    let categories = store.get_categories().map_err(|e| e.to_string())?;
+    drop(store);
 
-            let _ = store.create_accounts(
-                &deserialized
-                    .accounts
-                    .iter()
-                    .map(|a| NewAccount::from(a))
-                    .collect(),
-            );
-            log::info!("Accounts imported.");
+    let data = read_qif(&text, &default_account, &accounts, &categories)?;
+    log::info!(
+        "QIF file read: {} accounts, {} transactions, {} transfers.",
+        data.accounts.len(),
+        data.transactions.len(),
+        data.transfers.len()
+    );
+    Ok(data)
+}
 
-            let _ = store.create_categories(
-                &deserialized
-                    .categories
-                    .iter()
-                    .map(|c| NewCategory::from(c))
-                    .collect(),
-            );
-            log::info!("Categories imported.");
+/// Writes the data of an import file to the database in a single
+/// transaction, so that a failure leaves the database as it was.
+pub fn import_data(data: &SyncModel, mode: ImportMode) -> Result<ImportReport, String> {
+    if let Some(problem) = data.preview().problems.first() {
+        return Err(problem.clone());
+    }
 
-            let _ = store.create_money_transactions(
-                &deserialized
-                    .transactions
-                    .iter()
-                    .map(|t| NewMoneyTransaction::from(t))
-                    .collect(),
-            );
-            log::info!("Transactions imported.");
+    let mut store = STORE.lock().unwrap();
+    log::info!("STORE lock acquired.");
 
-            match store.get_currencies() {
-                Ok(list) => {
-                    let selected = list.iter().find(|c| c.symbol == deserialized.currency);
-                    if let Some(currency) = selected {
-                        let _ = config.1.set_currency_id(&config.0.unwrap(), currency.id);
-                        log::info!("Currency set to ID: {}", currency.id);
-                    } else {
-                        let _ = config.1.set_currency_id(&config.0.unwrap(), 0);
-                        log::warn!("Currency not found in list, setting ID to 0.");
-                    }
-                }
-                Err(e) => {
-                    log::error!("Error retrieving currencies: {:?}", e);
+    let report = store
+        .atomically(|store| match mode {
+            ImportMode::Replace => replace_with(store, data),
+            ImportMode::Merge => merge_into(store, data),
+        })
+        .map_err(|e| {
+            log::error!("Import failed, changes rolled back: {:?}", e);
+            e.to_string()
+        })?;
+    log::info!("Import completed: {:?}", report);
+
+    // A merge keeps the currency of the ledger.
+    if mode == ImportMode::Replace {
+        let mut config = Config::load();
+        match store.get_currencies() {
+            Ok(list) => {
+                let selected = list.iter().find(|c| c.symbol == data.currency);
+                if let Some(currency) = selected {
+                    let _ = config.1.set_currency_id(&config.0.unwrap(), currency.id);
+                    log::info!("Currency set to ID: {}", currency.id);
+                } else {
                     let _ = config.1.set_currency_id(&config.0.unwrap(), 0);
-                    log::warn!("Setting currency ID to 0 due to error.");
+                    log::warn!("Currency not found in list, setting ID to 0.");
                 }
             }
-            log::info!("Import from JSON completed successfully.");
-            Ok(())
-        }
-        Err(e) => {
-            log::error!("Failed to open file at URL: {:?}, error: {:?}", url, e);
-            Err("Failed to open file.".to_string())
+            Err(e) => {
+                log::error!("Error retrieving currencies: {:?}", e);
+                let _ = config.1.set_currency_id(&config.0.unwrap(), 0);
+                log::warn!("Setting currency ID to 0 due to error.");
+            }
         }
     }
+    Ok(report)
 }
@@ -0,0 +1,188 @@
+/// Element of a tagged document, read leniently so that both XML and the
+/// SGML of older formats, where leaf tags are never closed, give the same
+/// events. Names lose their namespace prefix.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    /// Start of an element holding other elements.
+    Start(String),
+    End(String),
+    /// Element holding only text.
+    Value {
+        name: String,
+        text: String,
+        attributes: Vec<(String, String)>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Open {
+        name: String,
+        attributes: Vec<(String, String)>,
+        closed: bool,
+    },
+    Close(String),
+    Text(String),
+}
+
+/// Reads the elements of a document. Text outside elements, comments,
+/// declarations and processing instructions are left out.
+pub fn events(text: &str) -> Vec<Event> {
+    let tokens = tokens(text);
+    let mut events = vec![];
+    let mut index = 0;
+    while index < tokens.len() {
+        match &tokens[index] {
+            Token::Open {
+                name,
+                attributes,
+                closed: true,
+            } => events.push(Event::Value {
+                name: name.clone(),
+                text: String::new(),
+                attributes: attributes.clone(),
+            }),
+            Token::Open {
+                name, attributes, ..
+            } => match tokens.get(index + 1) {
+                Some(Token::Text(value)) => {
+                    events.push(Event::Value {
+                        name: name.clone(),
+                        text: value.clone(),
+                        attributes: attributes.clone(),
+                    });
+                    index += 1;
+                    if tokens.get(index + 1) == Some(&Token::Close(name.clone())) {
+                        index += 1;
+                    }
+                }
+                Some(Token::Close(closing)) if closing == name => {
+                    events.push(Event::Value {
+                        name: name.clone(),
+                        text: String::new(),
+                        attributes: attributes.clone(),
+                    });
+                    index += 1;
+                }
+                _ => events.push(Event::Start(name.clone())),
+            },
+            Token::Close(name) => events.push(Event::End(name.clone())),
+            Token::Text(_) => {}
+        }
+        index += 1;
+    }
+    events
+}
+
+fn tokens(text: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        push_text(&mut tokens, &rest[..start]);
+        rest = &rest[start..];
+        if let Some(body) = rest.strip_prefix("<![CDATA[") {
+            let end = body.find("]]>").unwrap_or(body.len());
+            if !body[..end].trim().is_empty() {
+                tokens.push(Token::Text(body[..end].trim().to_string()));
+            }
+            rest = body.get(end + 3..).unwrap_or_default();
+            continue;
+        }
+        let terminator = if rest.starts_with("<!--") { "-->" } else { ">" };
+        let Some(end) = rest.find(terminator) else {
+            break;
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + terminator.len()..];
+        if tag.starts_with('!') || tag.starts_with('?') {
+            continue;
+        }
+        if let Some(name) = tag.strip_prefix('/') {
+            tokens.push(Token::Close(local_name(name.trim())));
+            continue;
+        }
+        let closed = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/').trim();
+        let (name, attributes) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+        tokens.push(Token::Open {
+            name: local_name(name),
+            attributes: parse_attributes(attributes),
+            closed,
+        });
+    }
+    push_text(&mut tokens, rest);
+    tokens
+}
+
+fn push_text(tokens: &mut Vec<Token>, text: &str) {
+    let text = text.trim();
+    if !text.is_empty() {
+        tokens.push(Token::Text(decode_entities(text)));
+    }
+}
+
+fn local_name(name: &str) -> String {
+    name.rsplit(':').next().unwrap_or(name).to_string()
+}
+
+fn parse_attributes(text: &str) -> Vec<(String, String)> {
+    let mut attributes = vec![];
+    let mut rest = text.trim();
+    while let Some((key, value)) = rest.split_once('=') {
+        let value = value.trim_start();
+        let Some(quote) = value.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            break;
+        };
+        let Some(end) = value[1..].find(quote) else {
+            break;
+        };
+        attributes.push((local_name(key.trim()), decode_entities(&value[1..end + 1])));
+        rest = value[end + 2..].trim_start();
+    }
+    attributes
+}
+
+/// Replaces the predefined and numeric character references. Unknown ones
+/// are kept as they are.
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| Some((end, entity_char(&rest[1..end])?)));
+        match entity {
+            Some((end, c)) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn entity_char(entity: &str) -> Option<char> {
+    match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some('\u{a0}'),
+        _ => {
+            let code = match entity.strip_prefix("#x").or(entity.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => entity.strip_prefix('#')?.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
@@ -0,0 +1,601 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+
+use crate::{
+    errors::DataStoreError,
+    models::{
+        Account, InvestmentTransaction, Loan, MoneyTransaction, NewAccount, NewAccountGroup,
+        NewAccountTransfer, NewCategory, NewCreditCard, NewInvestmentTransaction, NewLoan,
+        NewLoanPayment, NewMoneyTransaction, NewSecurity, NewSecurityPrice, TransactionFilter,
+        TransactionStatus, UpdateAccount, UpdateCategory, UpdateTransaction,
+    },
+    store::Store,
+};
+
+use super::model::SyncModel;
+
+/// How many records of one kind an import added, updated or left as they
+/// were.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RecordCounts {
+    pub added: usize,
+    pub updated: usize,
+    pub skipped: usize,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ImportReport {
+    pub accounts: RecordCounts,
+    pub categories: RecordCounts,
+    pub transactions: RecordCounts,
+    pub transfers: RecordCounts,
+}
+
+impl ImportReport {
+    /// Counts over every kind of record.
+    pub fn total(&self) -> RecordCounts {
+        [
+            self.accounts,
+            self.categories,
+            self.transactions,
+            self.transfers,
+        ]
+        .iter()
+        .fold(RecordCounts::default(), |total, counts| RecordCounts {
+            added: total.added + counts.added,
+            updated: total.updated + counts.updated,
+            skipped: total.skipped + counts.skipped,
+        })
+    }
+}
+
+/// Replaces the whole ledger with exported data. Accounts and categories
+/// get new ids, the references to them in the file are mapped to those.
+pub fn replace_with(store: &mut Store, data: &SyncModel) -> Result<ImportReport, DataStoreError> {
+    let old_accounts = store.get_accounts()?;
+    let old_categories = store.get_categories()?;
+    store.drop_all()?;
+
+    let group_ids = group_ids(store, data)?;
+    let account_ids: HashMap<i32, i32> = data
+        .accounts
+        .iter()
+        .map(|a| a.id)
+        .zip(
+            store.create_accounts(
+                &data
+                    .accounts
+                    .iter()
+                    .map(|a| new_account(a, &group_ids))
+                    .collect(),
+            )?,
+        )
+        .collect();
+    let account_id = |id: i32| account_ids.get(&id).copied().unwrap_or(id);
+
+    let category_ids: HashMap<i32, i32> = data
+        .categories
+        .iter()
+        .map(|c| c.id)
+        .zip(store.create_categories(&data.categories.iter().map(NewCategory::from).collect())?)
+        .collect();
+    let category_id = |id: i32| category_ids.get(&id).copied().unwrap_or(id);
+
+    // Rules, templates, saved filters and CSV profiles are kept, following
+    // accounts and categories by name to their new ids.
+    let new_account_ids: HashMap<i32, i32> = old_accounts
+        .iter()
+        .filter_map(|old| {
+            let new = data.accounts.iter().find(|a| a.name == old.name)?;
+            Some((old.id, account_id(new.id)))
+        })
+        .collect();
+    let new_category_ids: HashMap<i32, i32> = old_categories
+        .iter()
+        .filter_map(|old| {
+            let new = data
+                .categories
+                .iter()
+                .find(|c| c.name == old.name && c.is_income == old.is_income)?;
+            Some((old.id, category_id(new.id)))
+        })
+        .collect();
+    store.remap_rules_and_templates(&new_account_ids, &new_category_ids)?;
+
+    // A backup is restored as it was written, without applying rules to it.
+    store.create_money_transactions(
+        &data
+            .transactions
+            .iter()
+            .map(|t| NewMoneyTransaction {
+                bank_account: account_id(t.bank_account),
+                transaction_category: category_id(t.transaction_category),
+                ..NewMoneyTransaction::from(t)
+            })
+            .collect(),
+    )?;
+
+    let transfer_ids: HashMap<i32, i32> = data
+        .transfers
+        .iter()
+        .map(|t| t.id)
+        .zip(
+            store.create_account_transfers(
+                &data
+                    .transfers
+                    .iter()
+                    .map(|t| NewAccountTransfer {
+                        from_account: account_id(t.from_account),
+                        to_account: account_id(t.to_account),
+                        transfer_date: t.transfer_date,
+                        amount: t.amount,
+                        description: t.description.clone(),
+                        status: t.status,
+                        to_status: t.to_status,
+                    })
+                    .collect(),
+            )?,
+        )
+        .collect();
+
+    // Loans, cards and trades follow their accounts to the new ids, the
+    // payments of the loans their transfers.
+    store.create_credit_cards(
+        &data
+            .credit_cards
+            .iter()
+            .map(|c| NewCreditCard {
+                account_id: account_id(c.account_id),
+                closing_day: c.closing_day,
+                due_day: c.due_day,
+            })
+            .collect(),
+    )?;
+    let loan_ids: HashMap<i32, i32> = data
+        .loans
+        .iter()
+        .map(|l| l.id)
+        .zip(
+            store.create_loans(
+                &data
+                    .loans
+                    .iter()
+                    .map(|l| new_loan(l, account_id(l.account_id)))
+                    .collect(),
+            )?,
+        )
+        .collect();
+    store.create_loan_payments(&new_loan_payments(data, &loan_ids, &transfer_ids))?;
+    let security_ids = import_securities(store, data)?;
+    let security_id = |id: i32| security_ids.get(&id).copied().unwrap_or(id);
+    store.create_investment_transactions(
+        &data
+            .investment_transactions
+            .iter()
+            .map(|t| {
+                new_investment_transaction(t, account_id(t.account_id), security_id(t.security_id))
+            })
+            .collect(),
+    )?;
+
+    let added = |count: usize| RecordCounts {
+        added: count,
+        ..Default::default()
+    };
+    Ok(ImportReport {
+        accounts: added(data.accounts.len()),
+        categories: added(data.categories.len()),
+        transactions: added(data.transactions.len()),
+        transfers: added(data.transfers.len()),
+    })
+}
+
+/// Ids of the groups of the file in the database, where groups are kept
+/// across imports: the ones with the same name, or new ones.
+fn group_ids(store: &mut Store, data: &SyncModel) -> Result<HashMap<i32, i32>, DataStoreError> {
+    let existing = store.get_account_groups()?;
+    for g in &data.groups {
+        if !existing.iter().any(|e| e.group_name == g.group_name) {
+            store.create_account_group(&NewAccountGroup {
+                group_name: g.group_name.clone(),
+            })?;
+        }
+    }
+    let groups = store.get_account_groups()?;
+    Ok(data
+        .groups
+        .iter()
+        .filter_map(|g| {
+            let group = groups.iter().find(|e| e.group_name == g.group_name)?;
+            Some((g.id, group.id))
+        })
+        .collect())
+}
+
+/// Account of the file to add, in the group its own maps to.
+fn new_account(a: &Account, group_ids: &HashMap<i32, i32>) -> NewAccount {
+    NewAccount {
+        group_id: a.group_id.and_then(|id| group_ids.get(&id).copied()),
+        ..NewAccount::from(a)
+    }
+}
+
+fn new_loan(l: &Loan, account_id: i32) -> NewLoan {
+    NewLoan {
+        account_id,
+        principal: l.principal,
+        interest_rate: l.interest_rate,
+        term: l.term,
+        payment_frequency: l.payment_frequency,
+        start_date: l.start_date,
+    }
+}
+
+/// Payments of the file whose loan and transfer are in the database.
+fn new_loan_payments(
+    data: &SyncModel,
+    loan_ids: &HashMap<i32, i32>,
+    transfer_ids: &HashMap<i32, i32>,
+) -> Vec<NewLoanPayment> {
+    data.loan_payments
+        .iter()
+        .filter_map(|p| {
+            Some(NewLoanPayment {
+                loan_id: *loan_ids.get(&p.loan_id)?,
+                transfer_id: *transfer_ids.get(&p.transfer_id)?,
+                principal_amount: p.principal_amount,
+                interest_amount: p.interest_amount,
+            })
+        })
+        .collect()
+}
+
+/// Adds the securities of the file missing from the database, matched by
+/// symbol, and records their prices. Returns the ids of the securities of
+/// the file in the database.
+fn import_securities(
+    store: &mut Store,
+    data: &SyncModel,
+) -> Result<HashMap<i32, i32>, DataStoreError> {
+    let security_ids: HashMap<i32, i32> = data
+        .securities
+        .iter()
+        .map(|s| s.id)
+        .zip(
+            store.get_or_create_securities(
+                &data
+                    .securities
+                    .iter()
+                    .map(|s| NewSecurity {
+                        symbol: s.symbol.clone(),
+                        security_name: s.security_name.clone(),
+                    })
+                    .collect(),
+            )?,
+        )
+        .collect();
+    store.create_security_prices(
+        &data
+            .security_prices
+            .iter()
+            .filter_map(|p| {
+                Some(NewSecurityPrice {
+                    security_id: *security_ids.get(&p.security_id)?,
+                    price_date: p.price_date,
+                    price: p.price,
+                })
+            })
+            .collect(),
+    )?;
+    Ok(security_ids)
+}
+
+fn new_investment_transaction(
+    t: &InvestmentTransaction,
+    account_id: i32,
+    security_id: i32,
+) -> NewInvestmentTransaction {
+    NewInvestmentTransaction {
+        account_id,
+        security_id,
+        kind: t.kind,
+        quantity: t.quantity,
+        price: t.price,
+        amount: t.amount,
+        transaction_date: t.transaction_date,
+    }
+}
+
+/// Identity of a transaction: the same movement on the same account.
+type TransactionKey = (i32, NaiveDateTime, u32, bool, String);
+/// Identity of a transfer: the same amount between the same accounts.
+type TransferKey = (i32, i32, NaiveDateTime, u32);
+/// Identity of a trade: the same operation on the same security and
+/// account.
+type InvestmentKey = (i32, i32, i32, NaiveDateTime, u32, u32);
+
+fn investment_key(t: &InvestmentTransaction, account_id: i32, security_id: i32) -> InvestmentKey {
+    (
+        account_id,
+        security_id,
+        t.kind,
+        t.transaction_date,
+        t.quantity.to_bits(),
+        t.amount.to_bits(),
+    )
+}
+
+fn transaction_key(t: &MoneyTransaction, bank_account: i32) -> TransactionKey {
+    (
+        bank_account,
+        t.transaction_date,
+        t.amount.to_bits(),
+        t.is_expense,
+        t.description.clone(),
+    )
+}
+
+/// Merges exported data into the existing ledger. Accounts are matched by
+/// name, categories by name and type, transactions by account, date, amount,
+/// direction and note, transfers by accounts, date and amount. Matching
+/// records take the details that differ in the file, unless reconciled, and
+/// the others are added. Loans and credit cards are matched by account and
+/// trades by account, security, kind, date, quantity and amount.
+pub fn merge_into(store: &mut Store, data: &SyncModel) -> Result<ImportReport, DataStoreError> {
+    let mut report = ImportReport::default();
+
+    let group_ids = group_ids(store, data)?;
+    let existing_accounts = store.get_accounts()?;
+    let mut account_ids: HashMap<i32, i32> = HashMap::new();
+    for a in &data.accounts {
+        match existing_accounts.iter().find(|e| e.name == a.name) {
+            Some(existing) => {
+                account_ids.insert(a.id, existing.id);
+                if existing.account_description == a.account_description
+                    && existing.initial_balance == a.initial_balance
+                    && existing.account_type == a.account_type
+                    && existing.exclude_from_total == a.exclude_from_total
+                    && existing.exclude_from_statistics == a.exclude_from_statistics
+                {
+                    report.accounts.skipped += 1;
+                } else {
+                    store.update_account(&UpdateAccount {
+                        id: existing.id,
+                        name: existing.name.clone(),
+                        initial_balance: a.initial_balance,
+                        account_description: a.account_description.clone(),
+                        account_type: a.account_type,
+                        group_id: existing.group_id,
+                        exclude_from_total: a.exclude_from_total,
+                        exclude_from_statistics: a.exclude_from_statistics,
+                    })?;
+                    report.accounts.updated += 1;
+                }
+            }
+            None => {
+                let ids = store.create_accounts(&vec![new_account(a, &group_ids)])?;
+                account_ids.insert(a.id, ids[0]);
+                report.accounts.added += 1;
+            }
+        }
+    }
+    let account_id = |id: i32| account_ids.get(&id).copied().unwrap_or(id);
+
+    let existing_categories = store.get_categories()?;
+    let mut category_ids: HashMap<i32, i32> = HashMap::new();
+    for c in &data.categories {
+        match existing_categories
+            .iter()
+            .find(|e| e.name == c.name && e.is_income == c.is_income)
+        {
+            Some(existing) => {
+                category_ids.insert(c.id, existing.id);
+                if existing.category_description == c.category_description {
+                    report.categories.skipped += 1;
+                } else {
+                    store.update_category(&UpdateCategory {
+                        id: existing.id,
+                        name: &existing.name,
+                        is_income: existing.is_income,
+                        category_description: c.category_description.clone(),
+                    })?;
+                    report.categories.updated += 1;
+                }
+            }
+            None => {
+                let ids = store.create_categories(&vec![NewCategory::from(c)])?;
+                category_ids.insert(c.id, ids[0]);
+                report.categories.added += 1;
+            }
+        }
+    }
+    let category_id = |id: i32| category_ids.get(&id).copied().unwrap_or(id);
+
+    // Several identical transactions may exist, each one in the file
+    // matches a different one.
+    let mut existing_transactions: HashMap<TransactionKey, Vec<MoneyTransaction>> = HashMap::new();
+    for t in store.get_filtered_transactions(&TransactionFilter::default())? {
+        existing_transactions
+            .entry(transaction_key(&t, t.bank_account))
+            .or_default()
+            .push(t);
+    }
+    let mut new_transactions = vec![];
+    for t in &data.transactions {
+        let bank_account = account_id(t.bank_account);
+        let transaction_category = category_id(t.transaction_category);
+        let existing = existing_transactions
+            .get_mut(&transaction_key(t, bank_account))
+            .and_then(|matches| matches.pop());
+        match existing {
+            Some(existing) => {
+                if existing.status() == TransactionStatus::Reconciled
+                    || (existing.transaction_category == transaction_category
+                        && existing.payee == t.payee
+                        && existing.status == t.status)
+                {
+                    report.transactions.skipped += 1;
+                } else {
+                    store.update_transaction(&UpdateTransaction {
+                        id: existing.id,
+                        bank_account,
+                        transaction_category,
+                        description: existing.description.clone(),
+                        amount: existing.amount,
+                        transaction_date: existing.transaction_date,
+                        is_expense: existing.is_expense,
+                        payee: t.payee.clone(),
+                    })?;
+                    store.set_transaction_status(existing.id, t.status())?;
+                    report.transactions.updated += 1;
+                }
+            }
+            None => new_transactions.push(NewMoneyTransaction {
+                bank_account,
+                transaction_category,
+                ..NewMoneyTransaction::from(t)
+            }),
+        }
+    }
+    report.transactions.added = new_transactions.len();
+    store.create_money_transactions(&new_transactions)?;
+
+    let mut existing_transfers: HashMap<TransferKey, Vec<_>> = HashMap::new();
+    for t in store.get_transfers()? {
+        existing_transfers
+            .entry((
+                t.from_account,
+                t.to_account,
+                t.transfer_date,
+                t.amount.to_bits(),
+            ))
+            .or_default()
+            .push(t);
+    }
+    // Ids of the transfers of the file in the database, for their loan
+    // payments.
+    let mut transfer_ids: HashMap<i32, i32> = HashMap::new();
+    let mut new_transfers = vec![];
+    let mut new_transfer_ids = vec![];
+    for t in &data.transfers {
+        let from_account = account_id(t.from_account);
+        let to_account = account_id(t.to_account);
+        let existing = existing_transfers
+            .get_mut(&(
+                from_account,
+                to_account,
+                t.transfer_date,
+                t.amount.to_bits(),
+            ))
+            .and_then(|matches| matches.pop());
+        match existing {
+            Some(existing) => {
+                transfer_ids.insert(t.id, existing.id);
+                // Each side keeps its own status, reconciled ones stay as
+                // they are.
+                let mut updated = false;
+                for (account, status) in [(from_account, t.status), (to_account, t.to_status)] {
+                    let current = existing.status_for(account);
+                    if current != TransactionStatus::Reconciled && i32::from(current) != status {
+                        store.set_transfer_status(existing.id, account, status.into())?;
+                        updated = true;
+                    }
+                }
+                if updated {
+                    report.transfers.updated += 1;
+                } else {
+                    report.transfers.skipped += 1;
+                }
+            }
+            None => {
+                new_transfer_ids.push(t.id);
+                new_transfers.push(NewAccountTransfer {
+                    from_account,
+                    to_account,
+                    transfer_date: t.transfer_date,
+                    amount: t.amount,
+                    description: t.description.clone(),
+                    status: t.status,
+                    to_status: t.to_status,
+                });
+            }
+        }
+    }
+    report.transfers.added = new_transfers.len();
+    transfer_ids.extend(
+        new_transfer_ids
+            .into_iter()
+            .zip(store.create_account_transfers(&new_transfers)?),
+    );
+
+    // An account keeps its own card and loan settings, the file only adds
+    // the missing ones.
+    let existing_cards = store.get_credit_cards()?;
+    store.create_credit_cards(
+        &data
+            .credit_cards
+            .iter()
+            .map(|c| NewCreditCard {
+                account_id: account_id(c.account_id),
+                closing_day: c.closing_day,
+                due_day: c.due_day,
+            })
+            .filter(|c| !existing_cards.iter().any(|e| e.account_id == c.account_id))
+            .collect(),
+    )?;
+    let existing_loans = store.get_loans()?;
+    let mut loan_ids: HashMap<i32, i32> = HashMap::new();
+    let mut new_loans = vec![];
+    let mut new_loan_ids = vec![];
+    for l in &data.loans {
+        let loan_account = account_id(l.account_id);
+        match existing_loans.iter().find(|e| e.account_id == loan_account) {
+            Some(existing) => {
+                loan_ids.insert(l.id, existing.id);
+            }
+            None => {
+                new_loan_ids.push(l.id);
+                new_loans.push(new_loan(l, loan_account));
+            }
+        }
+    }
+    loan_ids.extend(
+        new_loan_ids
+            .into_iter()
+            .zip(store.create_loans(&new_loans)?),
+    );
+    let paid_transfers: Vec<i32> = store
+        .get_all_loan_payments()?
+        .iter()
+        .map(|p| p.transfer_id)
+        .collect();
+    store.create_loan_payments(
+        &new_loan_payments(data, &loan_ids, &transfer_ids)
+            .into_iter()
+            .filter(|p| !paid_transfers.contains(&p.transfer_id))
+            .collect(),
+    )?;
+
+    // Trades have no details to update, the ones already there are skipped.
+    let security_ids = import_securities(store, data)?;
+    let security_id = |id: i32| security_ids.get(&id).copied().unwrap_or(id);
+    let mut existing_trades: HashMap<InvestmentKey, usize> = HashMap::new();
+    for t in store.get_all_investment_transactions()? {
+        *existing_trades
+            .entry(investment_key(&t, t.account_id, t.security_id))
+            .or_default() += 1;
+    }
+    let mut new_trades = vec![];
+    for t in &data.investment_transactions {
+        let (trade_account, trade_security) =
+            (account_id(t.account_id), security_id(t.security_id));
+        match existing_trades.get_mut(&investment_key(t, trade_account, trade_security)) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => new_trades.push(new_investment_transaction(t, trade_account, trade_security)),
+        }
+    }
+    store.create_investment_transactions(&new_trades)?;
+
+    Ok(report)
+}
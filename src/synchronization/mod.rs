@@ -1,3 +1,12 @@
 pub mod model;
 pub mod import;
-pub mod export;
\ No newline at end of file
+pub mod merge;
+pub mod export;
+pub mod prices;
+pub mod csv;
+pub mod markup;
+pub mod ofx;
+pub mod statement;
+pub mod qif;
+pub mod camt;
+pub mod mt940;
\ No newline at end of file
@@ -1,22 +1,317 @@
+use std::collections::HashSet;
+
+use chrono::{Local, NaiveDate};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    models::{
+        Account, AccountGroup, AccountTransfer, Category, CreditCard, InvestmentTransaction, Loan,
+        LoanPayment, MoneyTransaction, Security, SecurityPrice,
+    },
+    utils::dates,
+};
+
+/// Version of the JSON format written by this build. Files written before
+/// the format was versioned count as version 1.
+pub const FORMAT_VERSION: u32 = 5;
 
-use crate::models::{Account, Category, MoneyTransaction};
+/// Steps turning a file of one format version into the next one, the first
+/// upgrading version 1 to 2.
+const UPGRADES: &[fn(&mut Value)] = &[
+    upgrade_v1_to_v2,
+    upgrade_v2_to_v3,
+    upgrade_v3_to_v4,
+    upgrade_v4_to_v5,
+];
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SyncModel {
+    pub format_version: u32,
+    /// Version of the app that wrote the file, for reference only.
+    pub app_version: String,
+    /// Groups the accounts belong to, matched by name on import.
+    pub groups: Vec<AccountGroup>,
     pub accounts: Vec<Account>,
     pub categories: Vec<Category>,
     pub transactions: Vec<MoneyTransaction>,
-    pub currency: String, 
+    pub transfers: Vec<AccountTransfer>,
+    pub loans: Vec<Loan>,
+    /// Principal and interest of the transfers paying the loans.
+    pub loan_payments: Vec<LoanPayment>,
+    pub credit_cards: Vec<CreditCard>,
+    /// Securities traded or priced, matched by symbol on import.
+    pub securities: Vec<Security>,
+    pub security_prices: Vec<SecurityPrice>,
+    pub investment_transactions: Vec<InvestmentTransaction>,
+    pub currency: String,
 }
 
 impl Default for SyncModel {
     fn default() -> Self {
         Self {
+            format_version: FORMAT_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            groups: vec![],
             accounts: vec![],
             categories: vec![],
             transactions: vec![],
+            transfers: vec![],
+            loans: vec![],
+            loan_payments: vec![],
+            credit_cards: vec![],
+            securities: vec![],
+            security_prices: vec![],
+            investment_transactions: vec![],
             currency: "".to_string(),
         }
     }
 }
+
+impl SyncModel {
+    /// Reads an exported file of any known format version, upgrading it to
+    /// the current one.
+    pub fn from_json(mut value: Value) -> Result<Self, String> {
+        let version = match value.get("format_version") {
+            None => 1,
+            Some(version) => version
+                .as_u64()
+                .and_then(|version| u32::try_from(version).ok())
+                .ok_or("Invalid format version.")?,
+        };
+        if version == 0 || version > FORMAT_VERSION {
+            return Err(format!(
+                "Unsupported format version {}, this app reads up to version {}.",
+                version, FORMAT_VERSION
+            ));
+        }
+        for upgrade in &UPGRADES[version as usize - 1..] {
+            upgrade(&mut value);
+        }
+        serde_json::from_value(value).map_err(|e| e.to_string())
+    }
+
+    /// Counts the records of the file and checks that they are consistent.
+    pub fn preview(&self) -> ImportPreview {
+        let mut problems = vec![];
+
+        let mut account_ids = HashSet::new();
+        for a in &self.accounts {
+            if !account_ids.insert(a.id) {
+                problems.push(format!("Account id {} is used more than once.", a.id));
+            }
+        }
+        let group_ids: HashSet<i32> = self.groups.iter().map(|g| g.id).collect();
+        for a in &self.accounts {
+            if let Some(group_id) = a.group_id.filter(|id| !group_ids.contains(id)) {
+                problems.push(format!(
+                    "Account {} refers to the missing group {}.",
+                    a.id, group_id
+                ));
+            }
+        }
+        let mut category_ids = HashSet::new();
+        for c in &self.categories {
+            if !category_ids.insert(c.id) {
+                problems.push(format!("Category id {} is used more than once.", c.id));
+            }
+        }
+
+        for t in &self.transactions {
+            if !account_ids.contains(&t.bank_account) {
+                problems.push(format!(
+                    "Transaction {} refers to the missing account {}.",
+                    t.id, t.bank_account
+                ));
+            }
+            if !category_ids.contains(&t.transaction_category) {
+                problems.push(format!(
+                    "Transaction {} refers to the missing category {}.",
+                    t.id, t.transaction_category
+                ));
+            }
+            if !(0..=2).contains(&t.status) {
+                problems.push(format!(
+                    "Transaction {} has the unknown status {}.",
+                    t.id, t.status
+                ));
+            }
+        }
+        for t in &self.transfers {
+            for account_id in [t.from_account, t.to_account] {
+                if !account_ids.contains(&account_id) {
+                    problems.push(format!(
+                        "Transfer {} refers to the missing account {}.",
+                        t.id, account_id
+                    ));
+                }
+            }
+            if t.from_account == t.to_account {
+                problems.push(format!(
+                    "Transfer {} moves money to the account it comes from.",
+                    t.id
+                ));
+            }
+            for status in [t.status, t.to_status] {
+                if !(0..=2).contains(&status) {
+                    problems.push(format!(
+                        "Transfer {} has the unknown status {}.",
+                        t.id, status
+                    ));
+                }
+            }
+        }
+
+        let mut loan_ids = HashSet::new();
+        for l in &self.loans {
+            if !loan_ids.insert(l.id) {
+                problems.push(format!("Loan id {} is used more than once.", l.id));
+            }
+            if !account_ids.contains(&l.account_id) {
+                problems.push(format!(
+                    "Loan {} refers to the missing account {}.",
+                    l.id, l.account_id
+                ));
+            }
+        }
+        let transfer_ids: HashSet<i32> = self.transfers.iter().map(|t| t.id).collect();
+        for p in &self.loan_payments {
+            if !loan_ids.contains(&p.loan_id) {
+                problems.push(format!(
+                    "Loan payment {} refers to the missing loan {}.",
+                    p.id, p.loan_id
+                ));
+            }
+            if !transfer_ids.contains(&p.transfer_id) {
+                problems.push(format!(
+                    "Loan payment {} refers to the missing transfer {}.",
+                    p.id, p.transfer_id
+                ));
+            }
+        }
+        for c in &self.credit_cards {
+            if !account_ids.contains(&c.account_id) {
+                problems.push(format!(
+                    "Credit card {} refers to the missing account {}.",
+                    c.id, c.account_id
+                ));
+            }
+        }
+        let security_ids: HashSet<i32> = self.securities.iter().map(|s| s.id).collect();
+        for p in &self.security_prices {
+            if !security_ids.contains(&p.security_id) {
+                problems.push(format!(
+                    "Price {} refers to the missing security {}.",
+                    p.id, p.security_id
+                ));
+            }
+        }
+        for t in &self.investment_transactions {
+            if !account_ids.contains(&t.account_id) {
+                problems.push(format!(
+                    "Investment transaction {} refers to the missing account {}.",
+                    t.id, t.account_id
+                ));
+            }
+            if !security_ids.contains(&t.security_id) {
+                problems.push(format!(
+                    "Investment transaction {} refers to the missing security {}.",
+                    t.id, t.security_id
+                ));
+            }
+        }
+
+        let dates = self
+            .transactions
+            .iter()
+            .map(|t| dates::local_date(&Local, &t.transaction_date))
+            .chain(
+                self.transfers
+                    .iter()
+                    .map(|t| dates::local_date(&Local, &t.transfer_date)),
+            );
+        let date_range = dates.fold(None, |range: Option<(NaiveDate, NaiveDate)>, date| {
+            Some(match range {
+                None => (date, date),
+                Some((first, last)) => (first.min(date), last.max(date)),
+            })
+        });
+
+        ImportPreview {
+            accounts: self.accounts.len(),
+            categories: self.categories.len(),
+            transactions: self.transactions.len(),
+            transfers: self.transfers.len(),
+            date_range,
+            problems,
+        }
+    }
+}
+
+/// What an import file holds, shown before anything is written. An import
+/// with problems is refused.
+#[derive(Clone, Debug, Default)]
+pub struct ImportPreview {
+    pub accounts: usize,
+    pub categories: usize,
+    pub transactions: usize,
+    pub transfers: usize,
+    /// Dates of the first and last transaction or transfer.
+    pub date_range: Option<(NaiveDate, NaiveDate)>,
+    pub problems: Vec<String>,
+}
+
+/// Version 2 added transfers and the versions of the format and the app.
+fn upgrade_v1_to_v2(value: &mut Value) {
+    if let Some(object) = value.as_object_mut() {
+        object.insert("format_version".to_string(), Value::from(2));
+        object.insert("app_version".to_string(), Value::from(""));
+        object.insert("transfers".to_string(), Value::Array(vec![]));
+    }
+}
+
+/// Version 3 keeps the status of transfers in each of their accounts, both
+/// start from the single status of before.
+fn upgrade_v2_to_v3(value: &mut Value) {
+    if let Some(object) = value.as_object_mut() {
+        object.insert("format_version".to_string(), Value::from(3));
+        if let Some(transfers) = object.get_mut("transfers").and_then(Value::as_array_mut) {
+            for transfer in transfers.iter_mut().filter_map(Value::as_object_mut) {
+                let status = transfer.get("status").cloned().unwrap_or(Value::from(0));
+                transfer.insert("to_status".to_string(), status);
+            }
+        }
+    }
+}
+
+/// Version 4 added the groups of the accounts. Accounts of older files are
+/// left out of any group, as the groups their ids refer to are unknown.
+fn upgrade_v3_to_v4(value: &mut Value) {
+    if let Some(object) = value.as_object_mut() {
+        object.insert("format_version".to_string(), Value::from(4));
+        object.insert("groups".to_string(), Value::Array(vec![]));
+        if let Some(accounts) = object.get_mut("accounts").and_then(Value::as_array_mut) {
+            for account in accounts.iter_mut().filter_map(Value::as_object_mut) {
+                account.insert("group_id".to_string(), Value::Null);
+            }
+        }
+    }
+}
+
+/// Version 5 added loans with their payments, credit cards, and securities
+/// with their prices and trades.
+fn upgrade_v4_to_v5(value: &mut Value) {
+    if let Some(object) = value.as_object_mut() {
+        object.insert("format_version".to_string(), Value::from(5));
+        for key in [
+            "loans",
+            "loan_payments",
+            "credit_cards",
+            "securities",
+            "security_prices",
+            "investment_transactions",
+        ] {
+            object.insert(key.to_string(), Value::Array(vec![]));
+        }
+    }
+}
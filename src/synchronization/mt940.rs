@@ -0,0 +1,410 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::fl;
+
+use super::{
+    csv::parse_amount,
+    statement::{LedgerBalance, Statement, StatementEntry},
+};
+
+/// Reference banks write when a movement has none.
+const NO_REFERENCE: &str = "NONREF";
+
+/// Whether the text is a SWIFT MT940 statement: fields such as `:20:` at
+/// the start of lines, with an opening balance.
+pub fn is_mt940(text: &str) -> bool {
+    let mut lines = text.lines().map(str::trim_start);
+    lines.clone().any(|line| line.starts_with(":20:"))
+        && lines.any(|line| line.starts_with(":60F:") || line.starts_with(":60M:"))
+}
+
+/// Entry being read, with what identifies it when the bank gives it no
+/// reference.
+struct Mt940Entry {
+    entry: StatementEntry,
+    /// Reference of the account owner, even when it is `NONREF`.
+    reference: String,
+    /// Text of the `:86:` field following the entry.
+    information: String,
+}
+
+/// Statement being read, with the balances it is checked against.
+#[derive(Default)]
+struct Mt940Statement {
+    reference: String,
+    statement: Statement,
+    entries: Vec<Mt940Entry>,
+    opening: Option<f32>,
+    closing: Option<f32>,
+}
+
+impl Mt940Statement {
+    /// The opening balance and the entries have to add up to the closing
+    /// balance, else the file is incomplete.
+    fn finish(mut self) -> Result<Statement, String> {
+        let cents = |amount: f32| (amount as f64 * 100.).round() as i64;
+        if let (Some(opening), Some(closing)) = (self.opening, self.closing) {
+            let total: i64 = self.entries.iter().map(|e| cents(e.entry.amount)).sum();
+            if cents(opening) + total != cents(closing) {
+                return Err(fl!(
+                    "statement-balance-mismatch",
+                    statement = self.reference,
+                    opening = format!("{:.2}", opening),
+                    total = format!("{:.2}", total as f64 / 100.),
+                    closing = format!("{:.2}", closing)
+                ));
+            }
+        }
+        // Entries without a reference are told apart by what the bank wrote
+        // about them, counting the ones that look the same so that each
+        // keeps its own id when the statement is read again.
+        let mut repeated: HashMap<String, usize> = HashMap::new();
+        for Mt940Entry {
+            mut entry,
+            reference,
+            information,
+        } in self.entries
+        {
+            if entry.external_id.is_none() {
+                let identity = format!(
+                    "{}/{}/{}/{}",
+                    entry.date,
+                    cents(entry.amount),
+                    reference,
+                    information
+                );
+                let count = repeated.entry(identity.clone()).or_default();
+                *count += 1;
+                entry.external_id = Some(format!("{}/{}", identity, count));
+            }
+            self.statement.entries.push(entry);
+        }
+        Ok(self.statement)
+    }
+}
+
+/// Reads the statements of an MT940 file. Each one starts at its `:20:`
+/// field and has its balances checked against its entries.
+pub fn parse(text: &str) -> Result<Vec<Statement>, String> {
+    let mut statements = vec![];
+    let mut statement: Option<Mt940Statement> = None;
+    let mut number = 0;
+    let mut previous = String::new();
+    for (tag, value) in fields(text) {
+        let follows_entry = previous == "61";
+        previous = tag.clone();
+        if tag == "20" {
+            if let Some(statement) = statement.take() {
+                statements.push(statement.finish()?);
+            }
+            statement = Some(Mt940Statement {
+                reference: value.trim().to_string(),
+                ..Default::default()
+            });
+            continue;
+        }
+        let Some(current) = statement.as_mut() else {
+            continue;
+        };
+        match tag.as_str() {
+            "25" => current.statement.account = Some(value.trim().to_string()),
+            "60F" | "60M" => {
+                let (amount, _, currency) =
+                    parse_balance(&value).ok_or_else(|| invalid_field(&tag, &value))?;
+                current.opening = Some(amount);
+                current.statement.currency = Some(currency);
+            }
+            "62F" | "62M" => {
+                let (amount, date, _) =
+                    parse_balance(&value).ok_or_else(|| invalid_field(&tag, &value))?;
+                current.closing = Some(amount);
+                current.statement.ledger_balance = Some(LedgerBalance { amount, date });
+            }
+            "61" => {
+                number += 1;
+                let (entry, reference) = parse_entry(&value).ok_or_else(|| {
+                    fl!(
+                        "statement-invalid-entry",
+                        entry = number,
+                        field = ":61:".to_string()
+                    )
+                })?;
+                current.entries.push(Mt940Entry {
+                    entry,
+                    reference,
+                    information: String::new(),
+                });
+            }
+            // Information for the account owner belongs to the entry just
+            // read, the one at the end of the statement is left out.
+            "86" if follows_entry => {
+                if let Some(last) = current.entries.last_mut() {
+                    let (description, payee) = parse_information(&value);
+                    if !description.is_empty() {
+                        last.entry.description = description;
+                    }
+                    last.entry.payee = payee;
+                    last.information = value.replace('\n', "").trim().to_string();
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(statement) = statement {
+        statements.push(statement.finish()?);
+    }
+
+    if statements.is_empty() {
+        return Err(fl!("statement-empty"));
+    }
+    Ok(statements)
+}
+
+/// Splits the file into its fields, joining the lines a value continues
+/// on. The SWIFT block headers around the message are left out.
+fn fields(text: &str) -> Vec<(String, String)> {
+    let mut fields: Vec<(String, String)> = vec![];
+    for line in text.lines() {
+        let line = line.trim_end();
+        if line.starts_with('{') || line == "-" || line == "-}" {
+            continue;
+        }
+        let tag = line
+            .strip_prefix(':')
+            .and_then(|rest| rest.split_once(':'))
+            .filter(|(tag, _)| {
+                (2..=3).contains(&tag.len()) && tag.starts_with(|c: char| c.is_ascii_digit())
+            });
+        if let Some((tag, value)) = tag {
+            fields.push((tag.to_string(), value.to_string()));
+        } else if let Some((_, value)) = fields.last_mut() {
+            value.push('\n');
+            value.push_str(line);
+        }
+    }
+    fields
+}
+
+fn invalid_field(tag: &str, value: &str) -> String {
+    fl!(
+        "statement-invalid-field",
+        field = format!(":{}:", tag),
+        value = value.trim().to_string()
+    )
+}
+
+/// Reads a balance such as `C250131EUR1234,56`: its sign, day, currency and
+/// amount.
+fn parse_balance(value: &str) -> Option<(f32, NaiveDate, String)> {
+    let value = value.trim();
+    let sign = match value.get(..1)? {
+        "C" => 1.,
+        "D" => -1.,
+        _ => return None,
+    };
+    let date = parse_date(value.get(1..7)?)?;
+    let currency = value.get(7..10)?.to_string();
+    let amount = parse_amount(value.get(10..)?, true)?;
+    Some((sign * amount, date, currency))
+}
+
+/// Reads a statement line such as
+/// `2501310131D12,50NTRFNONREF//B123456` followed by optional details on
+/// the next line, with the reference of the account owner.
+fn parse_entry(value: &str) -> Option<(StatementEntry, String)> {
+    let (line, details) = value.split_once('\n').unwrap_or((value, ""));
+    let value_date = parse_date(line.get(..6)?)?;
+    let mut rest = &line[6..];
+    // The day the bank booked the movement, without the year.
+    let mut date = value_date;
+    if let Some(booking) = rest
+        .get(..4)
+        .filter(|booking| booking.chars().all(|c| c.is_ascii_digit()))
+    {
+        let month: u32 = booking[..2].parse().ok()?;
+        let day: u32 = booking[2..].parse().ok()?;
+        let year = match (value_date.month(), month) {
+            (1, 12) => value_date.year() - 1,
+            (12, 1) => value_date.year() + 1,
+            _ => value_date.year(),
+        };
+        date = NaiveDate::from_ymd_opt(year, month, day)?;
+        rest = &rest[4..];
+    }
+    // Reversals move money the other way of what they reverse.
+    let (sign, marks) = if rest.starts_with("RC") {
+        (-1., 2)
+    } else if rest.starts_with("RD") {
+        (1., 2)
+    } else if rest.starts_with('C') {
+        (1., 1)
+    } else if rest.starts_with('D') {
+        (-1., 1)
+    } else {
+        return None;
+    };
+    rest = &rest[marks..];
+    // The third letter of the currency code, given by some banks.
+    if rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        rest = &rest[1..];
+    }
+    let amount_length = rest
+        .find(|c: char| !c.is_ascii_digit() && c != ',')
+        .unwrap_or(rest.len());
+    let amount = parse_amount(&rest[..amount_length], true)?;
+    // Four characters give the kind of movement, such as NTRF.
+    let references = rest.get(amount_length + 4..).unwrap_or_default();
+    let (owner_reference, bank_reference) = references.split_once("//").unwrap_or((references, ""));
+    let reference =
+        |text: &str| Some(text.trim().to_string()).filter(|r| !r.is_empty() && r != NO_REFERENCE);
+    let details = details.replace('\n', "");
+    let entry = StatementEntry {
+        date,
+        amount: sign * amount,
+        description: details.trim().to_string(),
+        payee: None,
+        external_id: reference(bank_reference).or_else(|| reference(owner_reference)),
+    };
+    Some((entry, owner_reference.trim().to_string()))
+}
+
+/// Reads the information of a `:86:` field: its purpose and the name of the
+/// counterparty. Many banks structure it in subfields such as `?20`, where
+/// `?20` to `?29` and `?60` to `?63` hold the purpose and `?32` and `?33`
+/// the name; otherwise the whole text is the purpose.
+fn parse_information(value: &str) -> (String, Option<String>) {
+    let Some(start) = value.find('?') else {
+        return (value.replace('\n', " ").trim().to_string(), None);
+    };
+    // Subfields are cut at the end of lines, not between words.
+    let value = value.replace('\n', "");
+    let mut purpose = String::new();
+    let mut name = String::new();
+    for subfield in value[start + 1..].split('?') {
+        let (Some(code), Some(text)) = (subfield.get(..2), subfield.get(2..)) else {
+            continue;
+        };
+        match code.parse::<u32>() {
+            Ok(20..=29 | 60..=63) => purpose.push_str(text),
+            Ok(32..=33) => name.push_str(text),
+            _ => {}
+        }
+    }
+    let name = Some(name.trim().to_string()).filter(|name| !name.is_empty());
+    let purpose = purpose.trim().to_string();
+    if purpose.is_empty() {
+        (name.clone().unwrap_or_default(), name)
+    } else {
+        (purpose, name)
+    }
+}
+
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(text, "%y%m%d").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn reads_balanced_statement() {
+        let text = "\
+:20:STMT1
+:25:DE89370400440532013000
+:28C:1/1
+:60F:C250102EUR100,00
+:61:2501020102D12,50NTRFNONREF//B1
+:86:?20Coffee?32Cafe Rossi
+:61:2501030103C50,00NTRFINV42
+:86:Salary
+:62F:C250103EUR137,50
+-";
+        let statements = parse(text).unwrap();
+        assert_eq!(statements.len(), 1);
+        let statement = &statements[0];
+        assert_eq!(statement.account.as_deref(), Some("DE89370400440532013000"));
+        assert_eq!(statement.currency.as_deref(), Some("EUR"));
+        assert_eq!(
+            statement.ledger_balance,
+            Some(LedgerBalance {
+                amount: 137.5,
+                date: date(2025, 1, 3)
+            })
+        );
+        assert_eq!(
+            statement.entries,
+            vec![
+                StatementEntry {
+                    date: date(2025, 1, 2),
+                    amount: -12.5,
+                    description: "Coffee".to_string(),
+                    payee: Some("Cafe Rossi".to_string()),
+                    external_id: Some("B1".to_string()),
+                },
+                StatementEntry {
+                    date: date(2025, 1, 3),
+                    amount: 50.,
+                    description: "Salary".to_string(),
+                    payee: None,
+                    external_id: Some("INV42".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn refuses_unbalanced_statement() {
+        let text = "\
+:20:STMT1
+:60F:C250102EUR100,00
+:61:2501020102D12,50NTRFNONREF//B1
+:62F:C250102EUR90,00
+-";
+        assert!(parse(text).is_err());
+    }
+
+    #[test]
+    fn booking_date_rolls_over_the_year() {
+        let text = "\
+:20:STMT1
+:60F:C241231EUR100,00
+:61:2501011231D10,00NTRFREF1
+:61:2412310101C20,00NTRFREF2
+:62F:C250101EUR110,00
+-";
+        let statements = parse(text).unwrap();
+        let dates: Vec<NaiveDate> = statements[0].entries.iter().map(|e| e.date).collect();
+        assert_eq!(dates, vec![date(2024, 12, 31), date(2025, 1, 1)]);
+    }
+
+    #[test]
+    fn entries_without_reference_get_stable_ids() {
+        let text = "\
+:20:STMT1
+:60F:C250102EUR100,00
+:61:2501020102D3,00NTRFNONREF
+:86:Coffee
+:61:2501020102D3,00NTRFNONREF
+:86:Coffee
+:61:2501020102D3,00NTRFNONREF
+:86:Newspaper
+:62F:C250102EUR91,00
+-";
+        let entries = parse(text).unwrap().remove(0).entries;
+        let ids: HashSet<_> = entries.iter().map(|e| e.external_id.clone()).collect();
+        assert_eq!(ids.len(), 3);
+        assert!(!ids.contains(&None));
+
+        let again = parse(text).unwrap().remove(0).entries;
+        assert_eq!(entries, again);
+    }
+}
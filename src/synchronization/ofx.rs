@@ -0,0 +1,351 @@
+use chrono::NaiveDate;
+
+use crate::fl;
+
+use super::{
+    csv::parse_amount,
+    markup::{self, Event},
+    statement::{LedgerBalance, Statement, StatementEntry},
+};
+
+/// Aggregates whose values are read. Values are given to the innermost of
+/// them that is open, which also copes with empty SGML elements that are
+/// never closed.
+const AGGREGATES: [&str; 10] = [
+    "STMTRS",
+    "CCSTMTRS",
+    "BANKACCTFROM",
+    "CCACCTFROM",
+    "BANKACCTTO",
+    "CCACCTTO",
+    "STMTTRN",
+    "PAYEE",
+    "LEDGERBAL",
+    "AVAILBAL",
+];
+
+/// Whether the text is an OFX or QFX file, either the SGML of version 1 or
+/// the XML of version 2.
+pub fn is_ofx(text: &str) -> bool {
+    let start = text.trim_start();
+    start.starts_with("OFXHEADER") || text.contains("<OFX>") || text.contains("<?OFX")
+}
+
+/// One STMTTRN of the file.
+#[derive(Default)]
+struct OfxTransaction {
+    posted: Option<String>,
+    amount: Option<String>,
+    fitid: Option<String>,
+    name: Option<String>,
+    memo: Option<String>,
+}
+
+impl OfxTransaction {
+    fn into_entry(self, number: usize) -> Result<StatementEntry, String> {
+        let invalid = |field: &str| {
+            fl!(
+                "statement-invalid-entry",
+                entry = number,
+                field = field.to_string()
+            )
+        };
+        let date = self
+            .posted
+            .as_deref()
+            .and_then(parse_date)
+            .ok_or_else(|| invalid("DTPOSTED"))?;
+        let amount = self
+            .amount
+            .as_deref()
+            .and_then(parse_ofx_amount)
+            .ok_or_else(|| invalid("TRNAMT"))?;
+        let payee = self.name.filter(|name| !name.is_empty());
+        Ok(StatementEntry {
+            date,
+            amount,
+            description: self
+                .memo
+                .filter(|memo| !memo.is_empty())
+                .or_else(|| payee.clone())
+                .unwrap_or_default(),
+            payee,
+            external_id: self.fitid.filter(|id| !id.is_empty()),
+        })
+    }
+}
+
+/// Reads the bank and credit card statements of an OFX file.
+pub fn parse(text: &str) -> Result<Vec<Statement>, String> {
+    let mut statements = vec![];
+    let mut path: Vec<String> = vec![];
+    let mut statement: Option<Statement> = None;
+    let mut transaction: Option<OfxTransaction> = None;
+    let mut number = 0;
+    let mut balance: (Option<f32>, Option<NaiveDate>) = (None, None);
+
+    for event in markup::events(text) {
+        match event {
+            Event::Start(name) => {
+                match name.as_str() {
+                    "STMTRS" | "CCSTMTRS" => statement = Some(Statement::default()),
+                    "STMTTRN" => transaction = Some(OfxTransaction::default()),
+                    "LEDGERBAL" => balance = (None, None),
+                    _ => {}
+                }
+                path.push(name);
+            }
+            Event::End(name) => {
+                if let Some(position) = path.iter().rposition(|open| *open == name) {
+                    path.truncate(position);
+                }
+                match name.as_str() {
+                    "STMTTRN" => {
+                        number += 1;
+                        if let (Some(statement), Some(transaction)) =
+                            (statement.as_mut(), transaction.take())
+                        {
+                            statement.entries.push(transaction.into_entry(number)?);
+                        }
+                    }
+                    "LEDGERBAL" => {
+                        if let (Some(statement), (Some(amount), Some(date))) =
+                            (statement.as_mut(), balance)
+                        {
+                            statement.ledger_balance = Some(LedgerBalance { amount, date });
+                        }
+                    }
+                    "STMTRS" | "CCSTMTRS" => statements.extend(statement.take()),
+                    _ => {}
+                }
+            }
+            Event::Value { name, text, .. } => {
+                let context = path
+                    .iter()
+                    .rev()
+                    .find(|open| AGGREGATES.contains(&open.as_str()))
+                    .map(String::as_str);
+                match (context, name.as_str()) {
+                    (Some("STMTTRN"), field) => {
+                        if let Some(transaction) = transaction.as_mut() {
+                            match field {
+                                "DTPOSTED" => transaction.posted = Some(text),
+                                "TRNAMT" => transaction.amount = Some(text),
+                                "FITID" => transaction.fitid = Some(text),
+                                "NAME" => transaction.name = Some(text),
+                                "MEMO" => transaction.memo = Some(text),
+                                _ => {}
+                            }
+                        }
+                    }
+                    (Some("PAYEE"), "NAME") => {
+                        if let Some(transaction) = transaction.as_mut() {
+                            transaction.name = Some(text);
+                        }
+                    }
+                    (Some("BANKACCTFROM" | "CCACCTFROM"), "ACCTID") => {
+                        if let Some(statement) = statement.as_mut() {
+                            statement.account = Some(text);
+                        }
+                    }
+                    (Some("STMTRS" | "CCSTMTRS"), "CURDEF") => {
+                        if let Some(statement) = statement.as_mut() {
+                            statement.currency = Some(text);
+                        }
+                    }
+                    (Some("LEDGERBAL"), "BALAMT") => balance.0 = parse_ofx_amount(&text),
+                    (Some("LEDGERBAL"), "DTASOF") => balance.1 = parse_date(&text),
+                    _ => {}
+                }
+            }
+        }
+    }
+    // Files cut short still give the statement read so far.
+    statements.extend(statement);
+
+    if statements.is_empty() {
+        return Err(fl!("statement-empty"));
+    }
+    Ok(statements)
+}
+
+/// Reads the day of an OFX date such as `20250131`, `20250131120000.000`
+/// or `20250131120000[-5:EST]`.
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(text.get(..8)?, "%Y%m%d").ok()
+}
+
+/// Amounts have a decimal point, but some banks write a comma instead.
+fn parse_ofx_amount(text: &str) -> Option<f32> {
+    parse_amount(text, !text.contains('.') && text.contains(','))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn reads_sgml_with_unclosed_tags() {
+        let text = "\
+OFXHEADER:100
+DATA:OFXSGML
+VERSION:102
+
+<OFX>
+<BANKMSGSRSV1>
+<STMTTRNRS>
+<STMTRS>
+<CURDEF>EUR
+<BANKACCTFROM>
+<BANKID>12345
+<ACCTID>IT60X0542811101000000123456
+<ACCTTYPE>CHECKING
+</BANKACCTFROM>
+<BANKTRANLIST>
+<DTSTART>20250101
+<DTEND>20250131
+<STMTTRN>
+<TRNTYPE>DEBIT
+<DTPOSTED>20250102120000[-5:EST]
+<TRNAMT>-12,50
+<FITID>T1
+<NAME>Cafe Rossi
+<MEMO>
+</STMTTRN>
+<STMTTRN>
+<TRNTYPE>CREDIT
+<DTPOSTED>20250103
+<TRNAMT>1,234.50
+<FITID>T2
+<NAME>ACME &amp; Sons
+<MEMO>Salary January
+</STMTTRN>
+</BANKTRANLIST>
+<LEDGERBAL>
+<BALAMT>1322.00
+<DTASOF>20250131
+</LEDGERBAL>
+</STMTRS>
+</STMTTRNRS>
+</BANKMSGSRSV1>
+</OFX>
+";
+        assert!(is_ofx(text));
+        let statements = parse(text).unwrap();
+        assert_eq!(statements.len(), 1);
+        let statement = &statements[0];
+        assert_eq!(
+            statement.account.as_deref(),
+            Some("IT60X0542811101000000123456")
+        );
+        assert_eq!(statement.currency.as_deref(), Some("EUR"));
+        assert_eq!(
+            statement.ledger_balance,
+            Some(LedgerBalance {
+                amount: 1322.,
+                date: date(2025, 1, 31)
+            })
+        );
+        assert_eq!(
+            statement.entries,
+            vec![
+                StatementEntry {
+                    date: date(2025, 1, 2),
+                    amount: -12.5,
+                    description: "Cafe Rossi".to_string(),
+                    payee: Some("Cafe Rossi".to_string()),
+                    external_id: Some("T1".to_string()),
+                },
+                StatementEntry {
+                    date: date(2025, 1, 3),
+                    amount: 1234.5,
+                    description: "Salary January".to_string(),
+                    payee: Some("ACME & Sons".to_string()),
+                    external_id: Some("T2".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_xml_credit_card_statement() {
+        let text = r#"<?xml version="1.0" encoding="UTF-8"?>
+<?OFX OFXHEADER="200" VERSION="220"?>
+<OFX>
+  <CREDITCARDMSGSRSV1>
+    <CCSTMTTRNRS>
+      <CCSTMTRS>
+        <CURDEF>USD</CURDEF>
+        <CCACCTFROM><ACCTID>4111111111111111</ACCTID></CCACCTFROM>
+        <BANKTRANLIST>
+          <STMTTRN>
+            <DTPOSTED>20250215</DTPOSTED>
+            <TRNAMT>-40.00</TRNAMT>
+            <FITID>CC1</FITID>
+            <PAYEE><NAME>Bookshop</NAME><CITY>Boston</CITY></PAYEE>
+            <MEMO></MEMO>
+          </STMTTRN>
+        </BANKTRANLIST>
+      </CCSTMTRS>
+    </CCSTMTTRNRS>
+  </CREDITCARDMSGSRSV1>
+</OFX>"#;
+        assert!(is_ofx(text));
+        let statements = parse(text).unwrap();
+        assert_eq!(statements[0].account.as_deref(), Some("4111111111111111"));
+        assert_eq!(statements[0].currency.as_deref(), Some("USD"));
+        assert_eq!(
+            statements[0].entries,
+            vec![StatementEntry {
+                date: date(2025, 2, 15),
+                amount: -40.,
+                description: "Bookshop".to_string(),
+                payee: Some("Bookshop".to_string()),
+                external_id: Some("CC1".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn keeps_the_entries_of_a_truncated_file() {
+        let text = "\
+<OFX><BANKMSGSRSV1><STMTTRNRS><STMTRS><CURDEF>EUR
+<STMTTRN><DTPOSTED>20250301<TRNAMT>5.00<FITID>A
+</STMTTRN>
+<STMTTRN><DTPOSTED>2025";
+        let statements = parse(text).unwrap();
+        assert_eq!(statements[0].entries.len(), 1);
+        assert_eq!(statements[0].entries[0].external_id.as_deref(), Some("A"));
+    }
+
+    #[test]
+    fn refuses_entries_without_a_date_or_amount() {
+        let text = "<OFX><STMTRS><STMTTRN><TRNAMT>abc<DTPOSTED>20250301</STMTTRN></STMTRS></OFX>";
+        assert!(parse(text).is_err());
+        assert!(parse("<OFX></OFX>").is_err());
+    }
+
+    #[test]
+    fn amount_decimal_comma_guess() {
+        assert_eq!(parse_ofx_amount("-12,50"), Some(-12.5));
+        assert_eq!(parse_ofx_amount("1,234.50"), Some(1234.5));
+        assert_eq!(parse_ofx_amount("-0.99"), Some(-0.99));
+        assert_eq!(parse_ofx_amount("100"), Some(100.));
+        assert_eq!(parse_ofx_amount(""), None);
+    }
+
+    #[test]
+    fn dates_keep_only_the_day() {
+        assert_eq!(parse_date("20250131"), Some(date(2025, 1, 31)));
+        assert_eq!(parse_date("20250131120000.000"), Some(date(2025, 1, 31)));
+        assert_eq!(
+            parse_date("20250131120000[-5:EST]"),
+            Some(date(2025, 1, 31))
+        );
+        assert_eq!(parse_date("2025"), None);
+    }
+}
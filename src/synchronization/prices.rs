@@ -0,0 +1,64 @@
+use std::fs;
+
+use chrono::{Local, NaiveDate, NaiveDateTime};
+use cosmic::dialog::ashpd::url::Url;
+
+use crate::{models::NewSecurityPrice, utils::dates, STORE};
+
+const DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%d/%m/%Y", "%d.%m.%Y"];
+
+/// Imports a price history from a CSV file with `symbol,date,price` rows.
+/// Semicolons are accepted as separators as well, and lines that cannot be
+/// parsed (like a header) are skipped. Returns the number of imported quotes.
+pub fn import_prices_from_csv(url: &Url) -> Result<usize, String> {
+    let path = url
+        .to_file_path()
+        .map_err(|_| "Failed to read the selected file".to_string())?;
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+
+    let rows: Vec<(String, NaiveDateTime, f32)> = content.lines().filter_map(parse_line).collect();
+    if rows.is_empty() {
+        return Err("No prices found in file".to_string());
+    }
+
+    let mut store = STORE.lock().unwrap();
+    let mut new_prices = Vec::with_capacity(rows.len());
+    for (symbol, price_date, price) in rows {
+        let security = store
+            .get_or_create_security(&symbol)
+            .map_err(|e| e.to_string())?;
+        new_prices.push(NewSecurityPrice {
+            security_id: security.id,
+            price_date,
+            price,
+        });
+    }
+    store
+        .create_security_prices(&new_prices)
+        .map_err(|e| e.to_string())?;
+
+    log::info!("{} prices imported", new_prices.len());
+    Ok(new_prices.len())
+}
+
+fn parse_line(line: &str) -> Option<(String, NaiveDateTime, f32)> {
+    let separator = if line.contains(';') { ';' } else { ',' };
+    let fields: Vec<&str> = line
+        .split(separator)
+        .map(|f| f.trim().trim_matches('"'))
+        .collect();
+    if fields.len() < 3 || fields[0].is_empty() {
+        return None;
+    }
+
+    let date = DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(fields[1], format).ok())?;
+    let price = fields[2].replace(',', ".").parse::<f32>().ok()?;
+
+    Some((
+        fields[0].to_uppercase(),
+        dates::day_start_utc(&Local, date),
+        price,
+    ))
+}
@@ -0,0 +1,871 @@
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+};
+
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone};
+
+use crate::{
+    fl,
+    models::{
+        Account, AccountTransfer, AccountType, Category, MoneyTransaction, TransactionStatus,
+    },
+    utils::dates,
+};
+
+use super::{csv::parse_amount, model::SyncModel};
+
+/// Date formats of QIF files, month first as Quicken writes them. Two-digit
+/// years are tried first, as `%Y` would read them as years of the first
+/// century.
+const QIF_DATE_FORMATS: [&str; 7] = [
+    "%m/%d/%y", "%m/%d/%Y", "%d/%m/%y", "%d/%m/%Y", "%d.%m.%y", "%d.%m.%Y", "%Y-%m-%d",
+];
+
+/// Format of the dates written on export.
+const EXPORT_DATE_FORMAT: &str = "%m/%d/%Y";
+
+/// Where the money of a transaction or split goes: a category, or another
+/// account written as `[Name]`.
+#[derive(Clone, Debug, PartialEq)]
+enum Target {
+    Category(String),
+    Account(String),
+}
+
+impl Target {
+    /// Reads an `L` or `S` line, dropping the class written after a slash.
+    fn parse(text: &str) -> Option<Self> {
+        let text = text.split('/').next().unwrap_or_default().trim();
+        if let Some(account) = text.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+            return Some(Target::Account(account.trim().to_string()));
+        }
+        (!text.is_empty()).then(|| Target::Category(text.to_string()))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Split {
+    target: Option<Target>,
+    memo: String,
+    amount: f32,
+}
+
+#[derive(Clone, Debug)]
+struct Entry {
+    date: NaiveDate,
+    amount: f32,
+    payee: Option<String>,
+    memo: String,
+    target: Option<Target>,
+    status: TransactionStatus,
+    splits: Vec<Split>,
+}
+
+#[derive(Clone, Debug)]
+struct QifAccount {
+    name: String,
+    account_type: AccountType,
+    description: String,
+    opening_balance: Option<f32>,
+    entries: Vec<Entry>,
+}
+
+/// Lines of a record up to its `^`, as code letter, value and line number.
+type Record = Vec<(char, String, usize)>;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Accounts,
+    Categories,
+    Transactions(AccountType),
+    /// Investments, memorized transactions and other lists not imported.
+    Skipped,
+}
+
+/// Reads a QIF file into the shape of an export, ready to be merged.
+/// Accounts and categories already in the ledger keep their details, the
+/// missing ones are created. Transactions without a category go to the
+/// "Other" category, splits become one transaction each, and transfers
+/// listed in both accounts are read once. Subcategories keep their full
+/// `Parent:Child` name, as categories are not nested. Entries of a file
+/// without account headers go to `default_account`.
+pub fn read_qif(
+    text: &str,
+    default_account: &str,
+    accounts: &[Account],
+    categories: &[Category],
+) -> Result<SyncModel, String> {
+    let mut section = Section::Skipped;
+    let mut record: Record = vec![];
+    let mut records: Vec<(Section, Record)> = vec![];
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim_end();
+        if let Some(header) = line.strip_prefix('!') {
+            let header = header.trim().to_lowercase();
+            section = match header.as_str() {
+                "account" => Section::Accounts,
+                "type:cat" => Section::Categories,
+                "type:bank" => Section::Transactions(AccountType::Checking),
+                "type:cash" => Section::Transactions(AccountType::Cash),
+                "type:ccard" => Section::Transactions(AccountType::Credit),
+                // Loans need details QIF lacks, so liabilities become assets.
+                "type:oth a" | "type:oth l" => Section::Transactions(AccountType::Asset),
+                _ if header.starts_with("option:") || header.starts_with("clear:") => section,
+                _ => Section::Skipped,
+            };
+            // Every section header starts a new list of records.
+            if !header.starts_with("option:") && !header.starts_with("clear:") {
+                records.push((section, vec![]));
+            }
+            continue;
+        }
+        let mut chars = line.chars();
+        match chars.next() {
+            None => {}
+            Some('^') => records.push((section, std::mem::take(&mut record))),
+            Some(code) => record.push((code, chars.as_str().trim().to_string(), index + 1)),
+        }
+    }
+    if !record.is_empty() {
+        records.push((section, record));
+    }
+
+    let date_format = detect_date_format(&records)?;
+
+    let mut qif_accounts: Vec<QifAccount> = vec![];
+    let mut current: Option<usize> = None;
+    let mut income_categories: HashMap<String, (bool, String)> = HashMap::new();
+    for (section, record) in records {
+        match section {
+            // An empty record marks the start of a section.
+            _ if record.is_empty() => {
+                if let (Section::Transactions(account_type), None) = (section, current) {
+                    qif_accounts.push(QifAccount {
+                        name: default_account.to_string(),
+                        account_type,
+                        description: String::new(),
+                        opening_balance: None,
+                        entries: vec![],
+                    });
+                    current = Some(qif_accounts.len() - 1);
+                }
+            }
+            Section::Accounts => {
+                let field = |code: char| {
+                    record
+                        .iter()
+                        .find(|(c, ..)| *c == code)
+                        .map(|(_, value, _)| value.clone())
+                };
+                let Some(name) = field('N').filter(|name| !name.is_empty()) else {
+                    continue;
+                };
+                let account_type = match field('T').unwrap_or_default().to_lowercase().as_str() {
+                    "cash" => AccountType::Cash,
+                    "ccard" => AccountType::Credit,
+                    "oth a" | "oth l" => AccountType::Asset,
+                    "invst" | "port" | "401(k)/403(b)" => AccountType::Investment,
+                    _ => AccountType::Checking,
+                };
+                let position = match qif_accounts.iter().position(|a| a.name == name) {
+                    Some(position) => position,
+                    None => {
+                        qif_accounts.push(QifAccount {
+                            name,
+                            account_type,
+                            description: field('D').unwrap_or_default(),
+                            opening_balance: None,
+                            entries: vec![],
+                        });
+                        qif_accounts.len() - 1
+                    }
+                };
+                current = Some(position);
+            }
+            Section::Categories => {
+                let mut name = None;
+                let mut description = String::new();
+                let mut is_income = false;
+                for (code, value, _) in &record {
+                    match code {
+                        'N' => name = Target::parse(value),
+                        'D' => description = value.clone(),
+                        'I' => is_income = true,
+                        'E' => is_income = false,
+                        _ => {}
+                    }
+                }
+                if let Some(Target::Category(name)) = name {
+                    income_categories.insert(name, (is_income, description));
+                }
+            }
+            Section::Transactions(_) => {
+                let Some(account) = current.and_then(|i| qif_accounts.get_mut(i)) else {
+                    continue;
+                };
+                let entry = read_entry(&record, date_format)?;
+                // Quicken writes the opening balance as a transfer from the
+                // account to itself.
+                if entry.target == Some(Target::Account(account.name.clone())) {
+                    account.opening_balance = Some(entry.amount);
+                } else {
+                    account.entries.push(entry);
+                }
+            }
+            Section::Skipped => {}
+        }
+    }
+
+    Ok(build_model(
+        qif_accounts,
+        &income_categories,
+        accounts,
+        categories,
+    ))
+}
+
+fn read_entry(record: &Record, date_format: &str) -> Result<Entry, String> {
+    let invalid =
+        |value: &str, line: usize| fl!("qif-invalid-line", line = line, value = value.to_string());
+    let mut date = None;
+    let mut amount = None;
+    let mut entry = Entry {
+        date: NaiveDate::default(),
+        amount: 0.,
+        payee: None,
+        memo: String::new(),
+        target: None,
+        status: TransactionStatus::Uncleared,
+        splits: vec![],
+    };
+    for (code, value, line) in record {
+        match code {
+            'D' => {
+                date = Some(
+                    NaiveDate::parse_from_str(&normalize_date(value), date_format)
+                        .map_err(|_| invalid(value, *line))?,
+                )
+            }
+            'T' | 'U' if amount.is_none() => {
+                amount = Some(parse_qif_amount(value).ok_or_else(|| invalid(value, *line))?)
+            }
+            'P' => entry.payee = Some(value.clone()).filter(|payee| !payee.is_empty()),
+            'M' => entry.memo = value.clone(),
+            'L' => entry.target = Target::parse(value),
+            'C' => {
+                entry.status = match value.as_str() {
+                    "*" | "c" => TransactionStatus::Cleared,
+                    "X" | "R" => TransactionStatus::Reconciled,
+                    _ => TransactionStatus::Uncleared,
+                }
+            }
+            'S' => entry.splits.push(Split {
+                target: Target::parse(value),
+                memo: String::new(),
+                amount: 0.,
+            }),
+            'E' => {
+                if let Some(split) = entry.splits.last_mut() {
+                    split.memo = value.clone();
+                }
+            }
+            '$' => {
+                if let Some(split) = entry.splits.last_mut() {
+                    split.amount = parse_qif_amount(value).ok_or_else(|| invalid(value, *line))?;
+                }
+            }
+            _ => {}
+        }
+    }
+    let first_line = record.first().map_or(0, |(_, _, line)| *line);
+    entry.date = date.ok_or_else(|| invalid("D", first_line))?;
+    entry.amount = amount.ok_or_else(|| invalid("T", first_line))?;
+    Ok(entry)
+}
+
+/// Turns the accounts read from the file into an export with ids of its
+/// own, reusing the details of the accounts and categories already there.
+fn build_model(
+    qif_accounts: Vec<QifAccount>,
+    income_categories: &HashMap<String, (bool, String)>,
+    accounts: &[Account],
+    categories: &[Category],
+) -> SyncModel {
+    let mut model = SyncModel::default();
+
+    let mut account_ids: HashMap<String, i32> = HashMap::new();
+    let mut account_id = |model: &mut SyncModel, name: &str, template: Option<&QifAccount>| {
+        if let Some(id) = account_ids.get(name) {
+            return *id;
+        }
+        let id = model.accounts.len() as i32 + 1;
+        let account = match accounts.iter().find(|a| a.name == name) {
+            Some(existing) => Account {
+                id,
+                ..existing.clone()
+            },
+            None => Account {
+                id,
+                name: name.to_string(),
+                initial_balance: template.and_then(|a| a.opening_balance).unwrap_or(0.),
+                account_description: template.map(|a| a.description.clone()).unwrap_or_default(),
+                account_type: template
+                    .map_or(AccountType::Checking, |a| a.account_type)
+                    .into(),
+                group_id: None,
+                exclude_from_total: false,
+                exclude_from_statistics: false,
+            },
+        };
+        model.accounts.push(account);
+        account_ids.insert(name.to_string(), id);
+        id
+    };
+
+    let mut category_ids: HashMap<(String, bool), i32> = HashMap::new();
+    let mut category_id = |model: &mut SyncModel, name: &str, amount: f32| {
+        let (is_income, description) = income_categories
+            .get(name)
+            .cloned()
+            .unwrap_or((amount > 0., String::new()));
+        let key = (name.to_string(), is_income);
+        if let Some(id) = category_ids.get(&key) {
+            return *id;
+        }
+        let id = model.categories.len() as i32 + 1;
+        let category = match categories
+            .iter()
+            .find(|c| c.name == name && c.is_income == is_income)
+        {
+            Some(existing) => Category {
+                id,
+                ..existing.clone()
+            },
+            None => Category {
+                id,
+                name: name.to_string(),
+                category_description: description,
+                is_income,
+            },
+        };
+        model.categories.push(category);
+        category_ids.insert(key, id);
+        id
+    };
+
+    for account in &qif_accounts {
+        account_id(&mut model, &account.name, Some(account));
+    }
+
+    // Transfers are listed in both accounts: each side counts how many
+    // times it listed the same transfer, and only the side ahead of the
+    // other adds one. The other side then gives its status to the one it
+    // matches.
+    let mut transfer_sides: HashMap<(i32, i32, NaiveDate, u32), (usize, usize, Vec<usize>)> =
+        HashMap::new();
+    let other = fl!("other");
+    for account in &qif_accounts {
+        let bank_account = account_id(&mut model, &account.name, Some(account));
+        for entry in &account.entries {
+            let lines: Vec<(Option<&Target>, &str, f32)> = if entry.splits.is_empty() {
+                vec![(entry.target.as_ref(), entry.memo.as_str(), entry.amount)]
+            } else {
+                entry
+                    .splits
+                    .iter()
+                    .map(|split| {
+                        let memo = if split.memo.is_empty() {
+                            &entry.memo
+                        } else {
+                            &split.memo
+                        };
+                        (split.target.as_ref(), memo.as_str(), split.amount)
+                    })
+                    .collect()
+            };
+            for (target, memo, amount) in lines {
+                match target {
+                    Some(Target::Account(name)) if *name != account.name => {
+                        let target_account = account_id(&mut model, name, None);
+                        let (from_account, to_account) = if amount < 0. {
+                            (bank_account, target_account)
+                        } else {
+                            (target_account, bank_account)
+                        };
+                        let sides = transfer_sides
+                            .entry((from_account, to_account, entry.date, amount.abs().to_bits()))
+                            .or_default();
+                        let (listed, ahead) = if from_account == bank_account {
+                            sides.0 += 1;
+                            (sides.0, sides.0 > sides.1)
+                        } else {
+                            sides.1 += 1;
+                            (sides.1, sides.1 > sides.0)
+                        };
+                        if ahead {
+                            sides.2.push(model.transfers.len());
+                            model.transfers.push(AccountTransfer {
+                                id: model.transfers.len() as i32 + 1,
+                                from_account,
+                                to_account,
+                                transfer_date: dates::day_start_utc(&Local, entry.date),
+                                amount: amount.abs(),
+                                description: Some(memo.to_string()).filter(|m| !m.is_empty()),
+                                status: entry.status.into(),
+                                to_status: entry.status.into(),
+                            });
+                        } else if let Some(&index) = sides.2.get(listed - 1) {
+                            model.transfers[index].set_status_for(bank_account, entry.status);
+                        }
+                    }
+                    target => {
+                        let category = match target {
+                            Some(Target::Category(name)) => name.as_str(),
+                            _ => other.as_str(),
+                        };
+                        let transaction_category = category_id(&mut model, category, amount);
+                        model.transactions.push(MoneyTransaction {
+                            id: model.transactions.len() as i32 + 1,
+                            bank_account,
+                            transaction_category,
+                            description: memo.to_string(),
+                            amount: amount.abs(),
+                            transaction_date: dates::day_start_utc(&Local, entry.date),
+                            is_expense: amount < 0.,
+                            status: entry.status.into(),
+                            payee: entry.payee.clone(),
+                            external_id: None,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    model
+}
+
+/// Dates such as ` 1/ 5'98` become `1/5/98`.
+fn normalize_date(text: &str) -> String {
+    text.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| if c == '\'' { '/' } else { c })
+        .collect()
+}
+
+/// The first format reading every date of the file.
+fn detect_date_format(records: &[(Section, Record)]) -> Result<&'static str, String> {
+    let dates: Vec<(String, usize)> = records
+        .iter()
+        .filter(|(section, _)| matches!(section, Section::Transactions(_)))
+        .flat_map(|(_, record)| record.iter())
+        .filter(|(code, ..)| *code == 'D')
+        .map(|(_, value, line)| (normalize_date(value), *line))
+        .collect();
+    QIF_DATE_FORMATS
+        .iter()
+        .copied()
+        .find(|format| {
+            dates
+                .iter()
+                .all(|(date, _)| NaiveDate::parse_from_str(date, format).is_ok())
+        })
+        .ok_or_else(|| {
+            let (date, line) = dates
+                .iter()
+                .find(|(date, _)| NaiveDate::parse_from_str(date, QIF_DATE_FORMATS[0]).is_err())
+                .cloned()
+                .unwrap_or_default();
+            fl!("qif-invalid-line", line = line, value = date)
+        })
+}
+
+/// Amounts have a decimal point and comma separated thousands, but some
+/// programs write them the European way.
+fn parse_qif_amount(text: &str) -> Option<f32> {
+    let decimal_comma = match (text.rfind(','), text.rfind('.')) {
+        (Some(comma), Some(point)) => comma > point,
+        (Some(comma), None) => text.len() - comma - 1 != 3,
+        _ => false,
+    };
+    parse_amount(text, decimal_comma)
+}
+
+fn qif_type(account_type: AccountType) -> &'static str {
+    match account_type {
+        AccountType::Cash => "Cash",
+        AccountType::Credit => "CCard",
+        AccountType::Loan => "Oth L",
+        AccountType::Investment | AccountType::Asset => "Oth A",
+        AccountType::Checking | AccountType::Savings => "Bank",
+    }
+}
+
+/// QIF values end at the line break.
+fn one_line(text: &str) -> String {
+    text.replace(['\r', '\n'], " ")
+}
+
+/// Writes the list of accounts and categories that starts a QIF export.
+pub fn write_lists(
+    writer: &mut impl Write,
+    accounts: &[Account],
+    categories: &[Category],
+) -> io::Result<()> {
+    writeln!(writer, "!Option:AutoSwitch")?;
+    writeln!(writer, "!Account")?;
+    for account in accounts {
+        writeln!(writer, "N{}", one_line(&account.name))?;
+        writeln!(writer, "T{}", qif_type(account.account_type.into()))?;
+        if !account.account_description.is_empty() {
+            writeln!(writer, "D{}", one_line(&account.account_description))?;
+        }
+        writeln!(writer, "^")?;
+    }
+    writeln!(writer, "!Clear:AutoSwitch")?;
+    writeln!(writer, "!Type:Cat")?;
+    for category in categories {
+        writeln!(writer, "N{}", one_line(&category.name))?;
+        if !category.category_description.is_empty() {
+            writeln!(writer, "D{}", one_line(&category.category_description))?;
+        }
+        writeln!(writer, "{}", if category.is_income { "I" } else { "E" })?;
+        writeln!(writer, "^")?;
+    }
+    Ok(())
+}
+
+/// Writes the transactions of an account, with its transfers on either
+/// side written as `[Account]` categories.
+pub fn write_account(
+    writer: &mut impl Write,
+    account: &Account,
+    transactions: &[MoneyTransaction],
+    transfers: &[AccountTransfer],
+    account_names: &HashMap<i32, String>,
+    category_names: &HashMap<i32, String>,
+) -> io::Result<()> {
+    let qif_type = qif_type(account.account_type.into());
+    writeln!(writer, "!Account")?;
+    writeln!(writer, "N{}", one_line(&account.name))?;
+    writeln!(writer, "T{}", qif_type)?;
+    writeln!(writer, "^")?;
+    writeln!(writer, "!Type:{}", qif_type)?;
+    if account.initial_balance != 0. {
+        writeln!(
+            writer,
+            "D{}",
+            qif_date(&first_movement(transactions, transfers))
+        )?;
+        writeln!(writer, "T{:.2}", account.initial_balance)?;
+        writeln!(writer, "POpening Balance")?;
+        writeln!(writer, "L[{}]", one_line(&account.name))?;
+        writeln!(writer, "^")?;
+    }
+    for t in transactions {
+        writeln!(writer, "D{}", qif_date(&t.transaction_date))?;
+        let amount = if t.is_expense { -t.amount } else { t.amount };
+        writeln!(writer, "T{:.2}", amount)?;
+        write_status(writer, t.status())?;
+        if let Some(payee) = &t.payee {
+            writeln!(writer, "P{}", one_line(payee))?;
+        }
+        if !t.description.is_empty() {
+            writeln!(writer, "M{}", one_line(&t.description))?;
+        }
+        if let Some(category) = category_names.get(&t.transaction_category) {
+            writeln!(writer, "L{}", one_line(category))?;
+        }
+        writeln!(writer, "^")?;
+    }
+    for t in transfers {
+        let (amount, other) = if t.from_account == account.id {
+            (-t.amount, t.to_account)
+        } else {
+            (t.amount, t.from_account)
+        };
+        writeln!(writer, "D{}", qif_date(&t.transfer_date))?;
+        writeln!(writer, "T{:.2}", amount)?;
+        write_status(writer, t.status_for(account.id))?;
+        if let Some(description) = t.description.as_ref().filter(|d| !d.is_empty()) {
+            writeln!(writer, "M{}", one_line(description))?;
+        }
+        let other = account_names.get(&other).cloned().unwrap_or_default();
+        writeln!(writer, "L[{}]", one_line(&other))?;
+        writeln!(writer, "^")?;
+    }
+    Ok(())
+}
+
+fn write_status(writer: &mut impl Write, status: TransactionStatus) -> io::Result<()> {
+    match status {
+        TransactionStatus::Uncleared => Ok(()),
+        TransactionStatus::Cleared => writeln!(writer, "C*"),
+        TransactionStatus::Reconciled => writeln!(writer, "CX"),
+    }
+}
+
+fn qif_date(date: &NaiveDateTime) -> String {
+    Local
+        .from_utc_datetime(date)
+        .format(EXPORT_DATE_FORMAT)
+        .to_string()
+}
+
+/// Date of the opening balance: the first movement of the account, or
+/// today when it has none.
+fn first_movement(
+    transactions: &[MoneyTransaction],
+    transfers: &[AccountTransfer],
+) -> NaiveDateTime {
+    transactions
+        .iter()
+        .map(|t| t.transaction_date)
+        .chain(transfers.iter().map(|t| t.transfer_date))
+        .min()
+        .unwrap_or_else(|| dates::day_start_utc(&Local, dates::local_today()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDateTime {
+        dates::day_start_utc(&Local, NaiveDate::from_ymd_opt(year, month, day).unwrap())
+    }
+
+    fn names<'a>(model: &'a SyncModel) -> Vec<(&'a str, bool)> {
+        model
+            .categories
+            .iter()
+            .map(|c| (c.name.as_str(), c.is_income))
+            .collect()
+    }
+
+    #[test]
+    fn reads_splits_and_transfers() {
+        let text = "\
+!Account
+NChecking
+TBank
+^
+NSavings
+TBank
+^
+!Type:Cat
+NSalary
+DMonthly pay
+I
+^
+!Account
+NChecking
+TBank
+^
+!Type:Bank
+D01/02'25
+T1,500.00
+POpening Balance
+L[Checking]
+^
+D01/05'25
+T-150.00
+C*
+PSupermarket
+MWeekly shop
+LFood:Groceries
+SFood:Groceries
+EFruit
+$-100.00
+SHousehold
+$-25.00
+S[Savings]
+ESave the change
+$-25.00
+^
+D01/06'25
+T2,000.00
+PACME
+LSalary/Work
+CX
+^
+!Account
+NSavings
+TBank
+^
+!Type:Bank
+D 1/ 5'25
+T25.00
+L[Checking]
+CX
+^
+";
+        let model = read_qif(text, "Default", &[], &[]).unwrap();
+
+        let accounts: Vec<(&str, f32)> = model
+            .accounts
+            .iter()
+            .map(|a| (a.name.as_str(), a.initial_balance))
+            .collect();
+        assert_eq!(accounts, vec![("Checking", 1500.), ("Savings", 0.)]);
+        assert_eq!(
+            names(&model),
+            vec![
+                ("Food:Groceries", false),
+                ("Household", false),
+                ("Salary", true)
+            ]
+        );
+        assert_eq!(model.categories[2].category_description, "Monthly pay");
+
+        let transactions: Vec<(i32, f32, bool, &str, TransactionStatus)> = model
+            .transactions
+            .iter()
+            .map(|t| {
+                (
+                    t.transaction_category,
+                    t.amount,
+                    t.is_expense,
+                    t.description.as_str(),
+                    t.status(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            transactions,
+            vec![
+                (1, 100., true, "Fruit", TransactionStatus::Cleared),
+                (2, 25., true, "Weekly shop", TransactionStatus::Cleared),
+                (3, 2000., false, "", TransactionStatus::Reconciled),
+            ]
+        );
+        assert!(model.transactions.iter().all(|t| t.bank_account == 1));
+        assert_eq!(model.transactions[0].transaction_date, date(2025, 1, 5));
+        assert_eq!(model.transactions[2].payee.as_deref(), Some("ACME"));
+
+        // the transfer is listed by both accounts but read once
+        assert_eq!(model.transfers.len(), 1);
+        let transfer = &model.transfers[0];
+        assert_eq!((transfer.from_account, transfer.to_account), (1, 2));
+        assert_eq!(transfer.amount, 25.);
+        assert_eq!(transfer.transfer_date, date(2025, 1, 5));
+        assert_eq!(transfer.description.as_deref(), Some("Save the change"));
+        assert_eq!(transfer.status_for(1), TransactionStatus::Cleared);
+        assert_eq!(transfer.status_for(2), TransactionStatus::Reconciled);
+    }
+
+    #[test]
+    fn entries_without_an_account_go_to_the_default_one() {
+        let text = "\
+!Type:CCard
+D03/01/2025
+T-5
+LFood
+^
+";
+        let model = read_qif(text, "Wallet", &[], &[]).unwrap();
+
+        assert_eq!(model.accounts.len(), 1);
+        assert_eq!(model.accounts[0].name, "Wallet");
+        assert_eq!(model.accounts[0].kind(), AccountType::Credit);
+        assert_eq!(model.transactions.len(), 1);
+        assert_eq!(model.transactions[0].transaction_date, date(2025, 3, 1));
+    }
+
+    #[test]
+    fn refuses_unreadable_dates() {
+        assert!(read_qif("!Type:Bank\nD31/31/25\nT1\n^\n", "Wallet", &[], &[]).is_err());
+    }
+
+    #[test]
+    fn amounts_in_either_notation() {
+        assert_eq!(parse_qif_amount("-1,500.00"), Some(-1500.));
+        assert_eq!(parse_qif_amount("1.234,56"), Some(1234.56));
+        assert_eq!(parse_qif_amount("1,234"), Some(1234.));
+        assert_eq!(parse_qif_amount("12,5"), Some(12.5));
+    }
+
+    #[test]
+    fn export_reads_back() {
+        let account = |id: i32, name: &str, initial_balance: f32| Account {
+            id,
+            name: name.to_string(),
+            initial_balance,
+            account_description: String::new(),
+            account_type: AccountType::Checking.into(),
+            group_id: None,
+            exclude_from_total: false,
+            exclude_from_statistics: false,
+        };
+        let accounts = vec![account(1, "Checking", 100.), account(2, "Savings", 0.)];
+        let categories = vec![Category {
+            id: 7,
+            name: "Food".to_string(),
+            category_description: String::new(),
+            is_income: false,
+        }];
+        let transactions = vec![MoneyTransaction {
+            id: 1,
+            bank_account: 1,
+            transaction_category: 7,
+            description: "Lunch".to_string(),
+            amount: 12.5,
+            transaction_date: date(2025, 1, 5),
+            is_expense: true,
+            status: TransactionStatus::Cleared.into(),
+            payee: Some("Shop".to_string()),
+            external_id: None,
+        }];
+        let transfers = vec![AccountTransfer {
+            id: 1,
+            from_account: 1,
+            to_account: 2,
+            transfer_date: date(2025, 1, 6),
+            amount: 50.,
+            description: None,
+            status: TransactionStatus::Reconciled.into(),
+            to_status: TransactionStatus::Uncleared.into(),
+        }];
+        let account_names: HashMap<i32, String> =
+            accounts.iter().map(|a| (a.id, a.name.clone())).collect();
+        let category_names: HashMap<i32, String> =
+            categories.iter().map(|c| (c.id, c.name.clone())).collect();
+
+        let mut text: Vec<u8> = vec![];
+        write_lists(&mut text, &accounts, &categories).unwrap();
+        write_account(
+            &mut text,
+            &accounts[0],
+            &transactions,
+            &transfers,
+            &account_names,
+            &category_names,
+        )
+        .unwrap();
+        write_account(
+            &mut text,
+            &accounts[1],
+            &[],
+            &transfers,
+            &account_names,
+            &category_names,
+        )
+        .unwrap();
+        let model = read_qif(&String::from_utf8(text).unwrap(), "Default", &[], &[]).unwrap();
+
+        assert_eq!(model.accounts[0].initial_balance, 100.);
+        assert_eq!(names(&model), vec![("Food", false)]);
+        assert_eq!(
+            model.transactions,
+            vec![MoneyTransaction {
+                transaction_category: 1,
+                ..transactions[0].clone()
+            }]
+        );
+        assert_eq!(model.transfers.len(), 1);
+        let transfer = &model.transfers[0];
+        assert_eq!((transfer.from_account, transfer.to_account), (1, 2));
+        assert_eq!(transfer.transfer_date, date(2025, 1, 6));
+        assert_eq!(transfer.status_for(1), TransactionStatus::Reconciled);
+        assert_eq!(transfer.status_for(2), TransactionStatus::Uncleared);
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::HashSet;
+
+use chrono::{Local, NaiveDate};
+
+use crate::{
+    errors::DataStoreError,
+    fl,
+    models::{Account, NewMoneyTransaction, StatementAccount, TransactionStatus},
+    store::Store,
+    utils::dates,
+};
+
+use super::{camt, csv::TextEncoding, merge::RecordCounts, mt940, ofx};
+
+/// Movement of a bank statement. Negative amounts are money going out.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatementEntry {
+    pub date: NaiveDate,
+    pub amount: f32,
+    pub description: String,
+    pub payee: Option<String>,
+    /// Id the bank gives the movement, the same every time it is exported.
+    pub external_id: Option<String>,
+}
+
+/// Balance of the account at the bank on a day.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LedgerBalance {
+    pub amount: f32,
+    pub date: NaiveDate,
+}
+
+/// Movements of one account read from a statement file, whatever its
+/// format.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Statement {
+    /// Number, IBAN or other id of the account at the bank.
+    pub account: Option<String>,
+    pub currency: Option<String>,
+    pub entries: Vec<StatementEntry>,
+    pub ledger_balance: Option<LedgerBalance>,
+}
+
+impl Statement {
+    pub fn date_range(&self) -> Option<(NaiveDate, NaiveDate)> {
+        let first = self.entries.iter().map(|e| e.date).min()?;
+        let last = self.entries.iter().map(|e| e.date).max()?;
+        Some((first, last))
+    }
+
+    /// Entries whose external id is not among the given ones, nor repeated
+    /// earlier in the statement.
+    pub fn new_entries<'a>(&'a self, known_ids: &HashSet<String>) -> Vec<&'a StatementEntry> {
+        let mut seen = HashSet::new();
+        self.entries
+            .iter()
+            .filter(|e| match &e.external_id {
+                Some(id) => !known_ids.contains(id) && seen.insert(id.as_str()),
+                None => true,
+            })
+            .collect()
+    }
+}
+
+/// Reads the statements in a file, detecting its format.
+pub fn read_statements(bytes: &[u8]) -> Result<Vec<Statement>, String> {
+    let text = TextEncoding::detect(bytes).decode(bytes);
+    if ofx::is_ofx(&text) {
+        ofx::parse(&text)
+    } else if camt::is_camt(&text) {
+        camt::parse(&text)
+    } else if mt940::is_mt940(&text) {
+        mt940::parse(&text)
+    } else {
+        Err(fl!("statement-unknown-format"))
+    }
+}
+
+/// The app account a statement goes to: the one its account was imported
+/// into before, or else the only one whose name or description holds the
+/// account number.
+pub fn match_account(
+    statement: &Statement,
+    accounts: &[Account],
+    known: &[StatementAccount],
+) -> Option<i32> {
+    let number = statement.account.as_deref()?;
+    if let Some(known) = known.iter().find(|k| k.external_account == number) {
+        return Some(known.account_id);
+    }
+    let normalize = |text: &str| -> String {
+        text.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_uppercase)
+            .collect()
+    };
+    let number = normalize(number);
+    if number.is_empty() {
+        return None;
+    }
+    let mut matching = accounts.iter().filter(|a| {
+        normalize(&a.name).contains(&number) || normalize(&a.account_description).contains(&number)
+    });
+    match (matching.next(), matching.next()) {
+        (Some(account), None) => Some(account.id),
+        _ => None,
+    }
+}
+
+/// Adds the new entries of a statement to an account, leaving out the ones
+/// imported before, and remembers the account for the next statements.
+pub fn import_statement(
+    store: &mut Store,
+    statement: &Statement,
+    target_account: i32,
+    expense_category: i32,
+    income_category: i32,
+) -> Result<RecordCounts, DataStoreError> {
+    let known_ids = store.get_external_ids(target_account)?;
+    let new_transactions: Vec<NewMoneyTransaction> = statement
+        .new_entries(&known_ids)
+        .into_iter()
+        .map(|entry| NewMoneyTransaction {
+            bank_account: target_account,
+            transaction_category: if entry.amount < 0. {
+                expense_category
+            } else {
+                income_category
+            },
+            description: entry.description.clone(),
+            amount: entry.amount.abs(),
+            transaction_date: dates::day_start_utc(&Local, entry.date),
+            is_expense: entry.amount < 0.,
+            status: TransactionStatus::Cleared.into(),
+            payee: entry.payee.clone(),
+            external_id: entry.external_id.clone(),
+        })
+        .collect();
+    let counts = RecordCounts {
+        added: new_transactions.len(),
+        updated: 0,
+        skipped: statement.entries.len() - new_transactions.len(),
+    };
+    store.create_money_transactions_with_rules(new_transactions)?;
+    if let Some(number) = &statement.account {
+        store.set_statement_account(number, target_account)?;
+    }
+    Ok(counts)
+}
@@ -0,0 +1,242 @@
+use chrono::{Duration, Months, NaiveDateTime};
+
+use crate::models::Loan;
+
+/// Upper bound on the number of periods simulated when looking for a payoff date.
+const MAX_PERIODS: i32 = 1200;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum PaymentFrequency {
+    Weekly,
+    Biweekly,
+    #[default]
+    Monthly,
+    Quarterly,
+    Yearly,
+}
+
+impl PaymentFrequency {
+    pub fn all() -> &'static [Self] {
+        &[
+            Self::Weekly,
+            Self::Biweekly,
+            Self::Monthly,
+            Self::Quarterly,
+            Self::Yearly,
+        ]
+    }
+
+    /// Number of payments in one year, as stored in `loan.payment_frequency`.
+    pub fn payments_per_year(&self) -> i32 {
+        match self {
+            Self::Weekly => 52,
+            Self::Biweekly => 26,
+            Self::Monthly => 12,
+            Self::Quarterly => 4,
+            Self::Yearly => 1,
+        }
+    }
+
+    pub fn from_payments_per_year(value: i32) -> Self {
+        match value {
+            52 => Self::Weekly,
+            26 => Self::Biweekly,
+            4 => Self::Quarterly,
+            1 => Self::Yearly,
+            _ => Self::Monthly,
+        }
+    }
+
+    /// Date of the `n`-th payment period after `start`.
+    pub fn advance(&self, start: NaiveDateTime, n: i32) -> NaiveDateTime {
+        let n = n.max(0);
+        match self {
+            Self::Weekly => start + Duration::weeks(n as i64),
+            Self::Biweekly => start + Duration::weeks(2 * n as i64),
+            Self::Monthly => start + Months::new(n as u32),
+            Self::Quarterly => start + Months::new(3 * n as u32),
+            Self::Yearly => start + Months::new(12 * n as u32),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ScheduleEntry {
+    pub number: i32,
+    pub date: NaiveDateTime,
+    pub payment: f32,
+    pub principal: f32,
+    pub interest: f32,
+    pub balance: f32,
+}
+
+pub fn periodic_rate(annual_rate: f32, payments_per_year: i32) -> f64 {
+    annual_rate as f64 / 100. / payments_per_year.max(1) as f64
+}
+
+/// Fixed installment that repays `principal` in `term` payments (French amortization).
+pub fn payment_amount(principal: f32, annual_rate: f32, term: i32, payments_per_year: i32) -> f32 {
+    if term <= 0 {
+        return principal;
+    }
+    let rate = periodic_rate(annual_rate, payments_per_year);
+    let principal = principal as f64;
+    if rate == 0. {
+        return (principal / term as f64) as f32;
+    }
+    (principal * rate / (1. - (1. + rate).powi(-term))) as f32
+}
+
+pub fn loan_payment_amount(loan: &Loan) -> f32 {
+    payment_amount(
+        loan.principal,
+        loan.interest_rate,
+        loan.term,
+        loan.payment_frequency,
+    )
+}
+
+/// Full amortization schedule of a loan, from the first to the last payment.
+pub fn schedule(loan: &Loan) -> Vec<ScheduleEntry> {
+    let frequency = PaymentFrequency::from_payments_per_year(loan.payment_frequency);
+    let rate = periodic_rate(loan.interest_rate, loan.payment_frequency);
+    let payment = loan_payment_amount(loan) as f64;
+    let mut balance = loan.principal as f64;
+    let mut entries = Vec::with_capacity(loan.term.max(0) as usize);
+
+    for number in 1..=loan.term {
+        let interest = balance * rate;
+        let mut principal = payment - interest;
+        // the last installment absorbs rounding differences
+        if number == loan.term || principal > balance {
+            principal = balance;
+        }
+        balance -= principal;
+        entries.push(ScheduleEntry {
+            number,
+            date: frequency.advance(loan.start_date, number),
+            payment: (principal + interest) as f32,
+            principal: principal as f32,
+            interest: interest as f32,
+            balance: balance.max(0.) as f32,
+        });
+    }
+
+    entries
+}
+
+/// Splits a payment of `amount` against an outstanding `balance` into
+/// its principal and interest parts, returned in this order.
+pub fn split_payment(loan: &Loan, balance: f32, amount: f32) -> (f32, f32) {
+    let rate = periodic_rate(loan.interest_rate, loan.payment_frequency);
+    let interest = ((balance.max(0.) as f64) * rate) as f32;
+    let interest = interest.min(amount.max(0.));
+    (amount - interest, interest)
+}
+
+/// Date of the payment that brings `balance` to zero, assuming the regular
+/// installment is paid from the next scheduled period onwards. Returns `None`
+/// when the loan is already repaid or the installment does not even cover the
+/// interest.
+pub fn payoff_date(loan: &Loan, balance: f32, payments_made: i32) -> Option<NaiveDateTime> {
+    let frequency = PaymentFrequency::from_payments_per_year(loan.payment_frequency);
+    if balance <= 0. {
+        return None;
+    }
+
+    let rate = periodic_rate(loan.interest_rate, loan.payment_frequency);
+    let payment = loan_payment_amount(loan) as f64;
+    let mut balance = balance as f64;
+    let mut periods = 0;
+
+    while balance > 0.005 {
+        let interest = balance * rate;
+        if payment <= interest || periods >= MAX_PERIODS {
+            return None;
+        }
+        balance -= payment - interest;
+        periods += 1;
+    }
+
+    Some(frequency.advance(loan.start_date, payments_made + periods))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+    }
+
+    fn loan(principal: f32, interest_rate: f32, term: i32) -> Loan {
+        Loan {
+            id: 1,
+            account_id: 1,
+            principal,
+            interest_rate,
+            term,
+            payment_frequency: 12,
+            start_date: date(2024, 1, 31),
+        }
+    }
+
+    #[test]
+    fn annuity_payment() {
+        assert!((payment_amount(10000., 12., 12, 12) - 888.49).abs() < 0.01);
+        assert_eq!(payment_amount(1200., 0., 12, 12), 100.);
+        assert_eq!(payment_amount(1200., 5., 0, 12), 1200.);
+    }
+
+    #[test]
+    fn last_payment_repays_the_remainder() {
+        let entries = schedule(&loan(1000., 12., 3));
+
+        assert_eq!(entries.len(), 3);
+        assert!((entries[0].interest - 10.).abs() < 0.001);
+        assert!((entries[0].payment - 340.02).abs() < 0.01);
+        let last = entries.last().unwrap();
+        assert_eq!(last.balance, 0.);
+        assert!((last.principal - entries[1].balance).abs() < 0.001);
+        let repaid: f32 = entries.iter().map(|e| e.principal).sum();
+        assert!((repaid - 1000.).abs() < 0.001);
+    }
+
+    #[test]
+    fn schedule_dates_stay_at_month_end() {
+        let dates: Vec<NaiveDateTime> = schedule(&loan(300., 0., 3))
+            .iter()
+            .map(|e| e.date)
+            .collect();
+
+        assert_eq!(
+            dates,
+            vec![date(2024, 2, 29), date(2024, 3, 31), date(2024, 4, 30)]
+        );
+    }
+
+    #[test]
+    fn split_caps_interest_at_the_payment() {
+        let loan = loan(1000., 12., 3);
+
+        let (principal, interest) = split_payment(&loan, 1000., 100.);
+        assert!((principal - 90.).abs() < 0.001);
+        assert!((interest - 10.).abs() < 0.001);
+        assert_eq!(split_payment(&loan, 1000., 4.), (0., 4.));
+    }
+
+    #[test]
+    fn payoff() {
+        let loan = loan(1000., 12., 3);
+
+        assert_eq!(payoff_date(&loan, 1000., 0), Some(date(2024, 4, 30)));
+        assert_eq!(payoff_date(&loan, 0., 3), None);
+        // the installment does not cover the interest of this balance
+        assert_eq!(payoff_date(&loan, 100000., 0), None);
+    }
+}
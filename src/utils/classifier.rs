@@ -0,0 +1,205 @@
+use std::collections::{HashMap, HashSet};
+
+/// Per-category counts collected while training.
+#[derive(Default)]
+struct CategoryStats {
+    transactions: u32,
+    tokens: HashMap<String, u32>,
+    total_tokens: u32,
+}
+
+/// Naive Bayes classifier suggesting the category of a transaction from
+/// the words of its description and the size of its amount, trained on the
+/// transactions already in the database.
+#[derive(Default)]
+pub struct CategoryClassifier {
+    categories: HashMap<i32, CategoryStats>,
+    vocabulary: HashSet<String>,
+    transactions: u32,
+}
+
+impl CategoryClassifier {
+    /// Trains a classifier on (category, description, amount) examples.
+    pub fn train<'a>(examples: impl IntoIterator<Item = (i32, &'a str, f32)>) -> Self {
+        let mut classifier = Self::default();
+        for (category_id, description, amount) in examples {
+            let words = words(description);
+            if words.is_empty() {
+                continue;
+            }
+            let stats = classifier.categories.entry(category_id).or_default();
+            stats.transactions += 1;
+            for token in words.into_iter().chain([amount_token(amount)]) {
+                *stats.tokens.entry(token.clone()).or_default() += 1;
+                stats.total_tokens += 1;
+                classifier.vocabulary.insert(token);
+            }
+            classifier.transactions += 1;
+        }
+        classifier
+    }
+
+    /// The most likely category among `candidates` with its probability,
+    /// from 0 to 1. Nothing is suggested until the description has a word
+    /// seen in training.
+    pub fn suggest(
+        &self,
+        description: &str,
+        amount: f32,
+        candidates: &[i32],
+    ) -> Option<(i32, f32)> {
+        let words: Vec<String> = words(description)
+            .into_iter()
+            .filter(|word| self.vocabulary.contains(word))
+            .collect();
+        if words.is_empty() {
+            return None;
+        }
+        let mut tokens = words;
+        if amount > 0. {
+            tokens.push(amount_token(amount));
+        }
+
+        let vocabulary = self.vocabulary.len() as f32;
+        let category_count = candidates.len() as f32;
+        let scores: Vec<(i32, f32)> = candidates
+            .iter()
+            .map(|category_id| {
+                let empty = CategoryStats::default();
+                let stats = self.categories.get(category_id).unwrap_or(&empty);
+                let prior = ((stats.transactions as f32 + 1.)
+                    / (self.transactions as f32 + category_count))
+                    .ln();
+                let likelihood: f32 = tokens
+                    .iter()
+                    .map(|token| {
+                        let count = stats.tokens.get(token).copied().unwrap_or_default();
+                        ((count as f32 + 1.) / (stats.total_tokens as f32 + vocabulary)).ln()
+                    })
+                    .sum();
+                (*category_id, prior + likelihood)
+            })
+            .collect();
+
+        // Turn log scores into probabilities, shifting by the best score to
+        // keep the exponentials in range.
+        let best = scores.iter().copied().max_by(|a, b| a.1.total_cmp(&b.1))?;
+        let total: f32 = scores.iter().map(|(_, score)| (score - best.1).exp()).sum();
+        Some((best.0, 1. / total))
+    }
+}
+
+/// Lowercase words of a description, leaving out numbers such as
+/// references and dates that rarely repeat.
+fn words(description: &str) -> Vec<String> {
+    description
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() > 1 && !word.chars().all(|c| c.is_numeric()))
+        .map(|word| word.to_lowercase())
+        .collect()
+}
+
+/// Token for the order of magnitude of an amount, so that a 3 coffee and a
+/// 60 fuel fill-up land in different buckets.
+fn amount_token(amount: f32) -> String {
+    format!("#amount:{}", amount.abs().max(0.01).log2().floor() as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COFFEE: i32 = 1;
+    const FUEL: i32 = 2;
+    const SHOPPING: i32 = 3;
+
+    fn classifier() -> CategoryClassifier {
+        CategoryClassifier::train([
+            (COFFEE, "Coffee Rossi", 3.5),
+            (COFFEE, "Coffee Rossi", 3.),
+            (COFFEE, "Coffee Rossi", 2.5),
+            (FUEL, "Fuel Shell", 60.),
+            (FUEL, "Fuel Shell", 55.),
+            (FUEL, "Fuel Shell", 40.),
+            // left out, as it has no words
+            (SHOPPING, "12345", 10.),
+        ])
+    }
+
+    #[test]
+    fn ranks_the_category_sharing_the_words() {
+        let classifier = classifier();
+        let candidates = [COFFEE, FUEL, SHOPPING];
+
+        let (category, confidence) = classifier.suggest("coffee", 3., &candidates).unwrap();
+        assert_eq!(category, COFFEE);
+        assert!(confidence > 0.5 && confidence <= 1.);
+        let (category, _) = classifier.suggest("SHELL 0042", 50., &candidates).unwrap();
+        assert_eq!(category, FUEL);
+    }
+
+    #[test]
+    fn confidence_grows_with_matching_words() {
+        let classifier = classifier();
+        let candidates = [COFFEE, FUEL];
+
+        let (_, one_word) = classifier.suggest("coffee", 3., &candidates).unwrap();
+        let (_, two_words) = classifier.suggest("coffee rossi", 3., &candidates).unwrap();
+        assert!(two_words > one_word);
+    }
+
+    #[test]
+    fn amount_breaks_ties_between_the_same_words() {
+        let classifier = CategoryClassifier::train([
+            (COFFEE, "Shop", 3.),
+            (COFFEE, "Shop", 2.5),
+            (FUEL, "Shop", 50.),
+            (FUEL, "Shop", 60.),
+        ]);
+        let candidates = [COFFEE, FUEL];
+
+        assert_eq!(
+            classifier.suggest("shop", 2.8, &candidates).unwrap().0,
+            COFFEE
+        );
+        assert_eq!(
+            classifier.suggest("shop", 45., &candidates).unwrap().0,
+            FUEL
+        );
+        // without the amount the two are as likely
+        let (_, confidence) = classifier.suggest("shop", 0., &candidates).unwrap();
+        assert!((confidence - 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn suggests_only_candidates() {
+        let classifier = classifier();
+
+        let (category, _) = classifier.suggest("coffee", 3., &[FUEL, SHOPPING]).unwrap();
+        assert_ne!(category, COFFEE);
+        assert_eq!(classifier.suggest("coffee", 3., &[]), None);
+    }
+
+    #[test]
+    fn needs_a_known_word() {
+        let classifier = classifier();
+        let candidates = [COFFEE, FUEL];
+
+        assert_eq!(classifier.suggest("bakery", 3., &candidates), None);
+        assert_eq!(classifier.suggest("12345", 10., &candidates), None);
+        assert_eq!(
+            CategoryClassifier::default().suggest("coffee", 3., &candidates),
+            None
+        );
+    }
+
+    #[test]
+    fn words_leave_out_numbers_and_single_letters() {
+        assert_eq!(
+            words("REF 12345 Coffee-Rossi a 2x"),
+            vec!["ref", "coffee", "rossi", "2x"]
+        );
+        assert_eq!(amount_token(3.), amount_token(-2.5));
+        assert_ne!(amount_token(3.), amount_token(60.));
+    }
+}
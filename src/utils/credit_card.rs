@@ -0,0 +1,154 @@
+use chrono::{Datelike, Months, NaiveDate, NaiveDateTime};
+
+use crate::{models::CreditCard, utils::dates};
+
+/// One closed billing period of a credit card.
+#[derive(Clone, Debug)]
+pub struct StatementCycle {
+    /// End of the previous statement, excluded from this cycle.
+    pub start: NaiveDateTime,
+    /// Last instant included in the statement.
+    pub closing_date: NaiveDateTime,
+    pub due_date: NaiveDateTime,
+}
+
+/// Date in the month of `date` with the given day, clamped to the last day
+/// of shorter months (a closing day of 31 closes on the 30th in April).
+fn day_in_month(date: NaiveDate, day: i32) -> NaiveDate {
+    let first = date.with_day(1).unwrap_or(date);
+    let last = (first + Months::new(1)).pred_opt().unwrap_or(first);
+    first
+        .with_day(day.clamp(1, last.day() as i32) as u32)
+        .unwrap_or(last)
+}
+
+/// Closing date of the `offset`-th month before the month of `date`.
+fn closing_in_month(card: &CreditCard, date: NaiveDate, offset: u32) -> NaiveDate {
+    day_in_month(date - Months::new(offset), card.closing_day)
+}
+
+/// Payment due date for a statement closing on `closing`: the first due day
+/// after the closing date.
+pub fn due_date(card: &CreditCard, closing: NaiveDate) -> NaiveDate {
+    let due = day_in_month(closing, card.due_day);
+    if due > closing {
+        due
+    } else {
+        day_in_month(closing + Months::new(1), card.due_day)
+    }
+}
+
+/// The last `count` statements closed on or before `today`, newest first.
+pub fn closed_cycles(card: &CreditCard, today: NaiveDate, count: usize) -> Vec<StatementCycle> {
+    // this month's statement is closed only once its closing day is over
+    let first_offset = if closing_in_month(card, today, 0) < today {
+        0
+    } else {
+        1
+    };
+
+    (0..count as u32)
+        .map(|n| {
+            let closing = closing_in_month(card, today, first_offset + n);
+            let previous = closing_in_month(card, today, first_offset + n + 1);
+            StatementCycle {
+                start: dates::local_day_end(previous),
+                closing_date: dates::local_day_end(closing),
+                due_date: dates::local_day_end(due_date(card, closing)),
+            }
+        })
+        .collect()
+}
+
+/// Closing date of the cycle currently open on `today`.
+pub fn next_closing_date(card: &CreditCard, today: NaiveDate) -> NaiveDate {
+    let closing = closing_in_month(card, today, 0);
+    if closing >= today {
+        closing
+    } else {
+        day_in_month(today + Months::new(1), card.closing_day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn card(closing_day: i32, due_day: i32) -> CreditCard {
+        CreditCard {
+            id: 1,
+            account_id: 1,
+            closing_day,
+            due_day,
+        }
+    }
+
+    #[test]
+    fn day_31_is_clamped_to_the_month() {
+        assert_eq!(day_in_month(date(2025, 2, 10), 31), date(2025, 2, 28));
+        assert_eq!(day_in_month(date(2024, 2, 10), 31), date(2024, 2, 29));
+        assert_eq!(day_in_month(date(2025, 4, 1), 31), date(2025, 4, 30));
+        assert_eq!(day_in_month(date(2025, 4, 1), 0), date(2025, 4, 1));
+    }
+
+    #[test]
+    fn next_closing_at_month_end() {
+        let card = card(31, 15);
+
+        assert_eq!(
+            next_closing_date(&card, date(2025, 2, 10)),
+            date(2025, 2, 28)
+        );
+        assert_eq!(
+            next_closing_date(&card, date(2025, 2, 28)),
+            date(2025, 2, 28)
+        );
+        assert_eq!(
+            next_closing_date(&card, date(2025, 3, 1)),
+            date(2025, 3, 31)
+        );
+    }
+
+    #[test]
+    fn due_date_follows_the_closing_date() {
+        assert_eq!(
+            due_date(&card(31, 15), date(2025, 1, 31)),
+            date(2025, 2, 15)
+        );
+        assert_eq!(due_date(&card(5, 31), date(2025, 2, 5)), date(2025, 2, 28));
+        assert_eq!(
+            due_date(&card(28, 31), date(2025, 2, 28)),
+            date(2025, 3, 31)
+        );
+    }
+
+    #[test]
+    fn cycles_close_once_the_closing_day_is_over() {
+        let card = card(31, 15);
+
+        let cycles = closed_cycles(&card, date(2025, 2, 28), 1);
+        assert_eq!(
+            cycles[0].closing_date,
+            dates::local_day_end(date(2025, 1, 31))
+        );
+
+        let cycles = closed_cycles(&card, date(2025, 3, 10), 2);
+        assert_eq!(cycles.len(), 2);
+        assert_eq!(cycles[0].start, dates::local_day_end(date(2025, 1, 31)));
+        assert_eq!(
+            cycles[0].closing_date,
+            dates::local_day_end(date(2025, 2, 28))
+        );
+        assert_eq!(cycles[0].due_date, dates::local_day_end(date(2025, 3, 15)));
+        assert_eq!(cycles[1].start, dates::local_day_end(date(2024, 12, 31)));
+        assert_eq!(
+            cycles[1].closing_date,
+            dates::local_day_end(date(2025, 1, 31))
+        );
+        assert_eq!(cycles[1].due_date, dates::local_day_end(date(2025, 2, 15)));
+    }
+}
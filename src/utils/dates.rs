@@ -1,4 +1,10 @@
-use chrono::{Duration, NaiveDate};
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+
+use crate::models::FilterPeriod;
+
+// Dates are stored in the database as naive UTC date-times, while the user
+// thinks in local calendar days: every query on a day or month range goes
+// through the helpers below to turn local day boundaries into UTC instants.
 
 pub fn get_month_date_range(year: i32, month: u32) -> (NaiveDate, NaiveDate) {
     let month_start =
@@ -17,3 +23,309 @@ pub fn get_month_date_range(year: i32, month: u32) -> (NaiveDate, NaiveDate) {
 
     (month_start, month_end)
 }
+
+/// UTC instant at which the given local day starts in `tz`. When midnight
+/// does not exist because of a DST change, the day starts at the first
+/// valid local time after it.
+pub fn day_start_utc<Tz: TimeZone>(tz: &Tz, date: NaiveDate) -> NaiveDateTime {
+    let midnight = date.and_time(NaiveTime::MIN);
+    if let Some(start) = tz.from_local_datetime(&midnight).earliest() {
+        return start.naive_utc();
+    }
+    // Midnight falls in a DST gap: the day starts at the instant the clocks
+    // jump past it. Offsets are shorter than a day, so that instant is within
+    // a day of midnight read as UTC; bisect it to the second.
+    let local = |seconds: i64| {
+        tz.from_utc_datetime(&(midnight + Duration::seconds(seconds)))
+            .naive_local()
+    };
+    let (mut before, mut after) = (-86_400, 86_400);
+    while after - before > 1 {
+        let middle = (before + after) / 2;
+        if local(middle) < midnight {
+            before = middle;
+        } else {
+            after = middle;
+        }
+    }
+    midnight + Duration::seconds(after)
+}
+
+/// Half-open UTC range `[start, end)` covering the local days from `first`
+/// to `last` included.
+pub fn day_range_utc<Tz: TimeZone>(
+    tz: &Tz,
+    first: NaiveDate,
+    last: NaiveDate,
+) -> (NaiveDateTime, NaiveDateTime) {
+    (
+        day_start_utc(tz, first),
+        day_start_utc(tz, last + Duration::days(1)),
+    )
+}
+
+/// Local calendar day of a stored UTC date-time.
+pub fn local_date<Tz: TimeZone>(tz: &Tz, utc: &NaiveDateTime) -> NaiveDate {
+    tz.from_utc_datetime(utc).naive_local().date()
+}
+
+/// [`day_range_utc`] in the system time zone.
+pub fn local_day_range(first: NaiveDate, last: NaiveDate) -> (NaiveDateTime, NaiveDateTime) {
+    day_range_utc(&Local, first, last)
+}
+
+/// Last instant of a local day in `tz`, as stored UTC.
+pub fn day_end_utc<Tz: TimeZone>(tz: &Tz, date: NaiveDate) -> NaiveDateTime {
+    day_start_utc(tz, date + Duration::days(1)) - Duration::seconds(1)
+}
+
+/// [`day_end_utc`] in the system time zone.
+pub fn local_day_end(date: NaiveDate) -> NaiveDateTime {
+    day_end_utc(&Local, date)
+}
+
+/// Current local calendar day.
+pub fn local_today() -> NaiveDate {
+    local_date(&Local, &Utc::now().naive_utc())
+}
+
+/// Days covered by a relative period, as seen from `today`.
+pub fn period_range(period: FilterPeriod, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+    match period {
+        FilterPeriod::ThisMonth => get_month_date_range(today.year(), today.month()),
+        FilterPeriod::LastMonth => {
+            let (month_start, _) = get_month_date_range(today.year(), today.month());
+            let previous = month_start - Duration::days(1);
+            get_month_date_range(previous.year(), previous.month())
+        }
+        FilterPeriod::ThisQuarter => {
+            let first_month = (today.month() - 1) / 3 * 3 + 1;
+            let (quarter_start, _) = get_month_date_range(today.year(), first_month);
+            let (_, quarter_end) = get_month_date_range(today.year(), first_month + 2);
+            (quarter_start, quarter_end)
+        }
+        FilterPeriod::ThisYear => (
+            NaiveDate::from_ymd_opt(today.year(), 1, 1).unwrap_or(today),
+            NaiveDate::from_ymd_opt(today.year(), 12, 31).unwrap_or(today),
+        ),
+        FilterPeriod::Last30Days => (today - Duration::days(29), today),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{FixedOffset, LocalResult};
+
+    use super::*;
+
+    /// Zone at UTC+1 whose clocks change at local midnight in 2025: they
+    /// jump to UTC+2 when March 30 starts, skipping its first hour, and go
+    /// back when October 26 reaches 01:00, repeating its first hour.
+    #[derive(Clone, Copy, Debug)]
+    struct MidnightDst;
+
+    impl MidnightDst {
+        const STANDARD: i32 = 3600;
+        const SUMMER: i32 = 7200;
+
+        fn summer_time(utc: &NaiveDateTime) -> bool {
+            *utc >= datetime(2025, 3, 29, 23, 0, 0) && *utc < datetime(2025, 10, 25, 23, 0, 0)
+        }
+    }
+
+    impl TimeZone for MidnightDst {
+        type Offset = FixedOffset;
+
+        fn from_offset(_offset: &FixedOffset) -> Self {
+            MidnightDst
+        }
+
+        fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+            self.offset_from_local_datetime(&local.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+            let offsets: Vec<FixedOffset> = [Self::SUMMER, Self::STANDARD]
+                .into_iter()
+                .filter_map(FixedOffset::east_opt)
+                .filter(|offset| {
+                    let utc = *local - Duration::seconds(offset.local_minus_utc() as i64);
+                    self.offset_from_utc_datetime(&utc) == *offset
+                })
+                .collect();
+            match offsets[..] {
+                [] => LocalResult::None,
+                [offset] => LocalResult::Single(offset),
+                [earliest, latest, ..] => LocalResult::Ambiguous(earliest, latest),
+            }
+        }
+
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+            self.offset_from_utc_datetime(&utc.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+            let seconds = if Self::summer_time(utc) {
+                Self::SUMMER
+            } else {
+                Self::STANDARD
+            };
+            FixedOffset::east_opt(seconds).unwrap()
+        }
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn datetime(year: i32, month: u32, day: u32, h: u32, m: u32, s: u32) -> NaiveDateTime {
+        date(year, month, day).and_hms_opt(h, m, s).unwrap()
+    }
+
+    fn hours(offset: i32) -> FixedOffset {
+        FixedOffset::east_opt(offset * 3600).unwrap()
+    }
+
+    #[test]
+    fn day_start_with_fixed_offsets() {
+        let india = FixedOffset::east_opt(5 * 3600 + 1800).unwrap();
+        assert_eq!(
+            day_start_utc(&india, date(2025, 1, 1)),
+            datetime(2024, 12, 31, 18, 30, 0)
+        );
+        assert_eq!(
+            day_start_utc(&hours(-8), date(2025, 1, 1)),
+            datetime(2025, 1, 1, 8, 0, 0)
+        );
+    }
+
+    #[test]
+    fn day_range_across_the_new_year() {
+        assert_eq!(
+            day_range_utc(&hours(-8), date(2024, 12, 31), date(2024, 12, 31)),
+            (
+                datetime(2024, 12, 31, 8, 0, 0),
+                datetime(2025, 1, 1, 8, 0, 0)
+            )
+        );
+        assert_eq!(
+            day_range_utc(&hours(1), date(2024, 12, 31), date(2025, 1, 1)),
+            (
+                datetime(2024, 12, 30, 23, 0, 0),
+                datetime(2025, 1, 1, 23, 0, 0)
+            )
+        );
+    }
+
+    #[test]
+    fn day_range_at_the_end_of_february() {
+        // Leap year: February 29 belongs to the range.
+        assert_eq!(
+            day_range_utc(&hours(1), date(2024, 2, 28), date(2024, 2, 29)),
+            (
+                datetime(2024, 2, 27, 23, 0, 0),
+                datetime(2024, 2, 29, 23, 0, 0)
+            )
+        );
+        assert_eq!(
+            day_range_utc(&hours(1), date(2025, 2, 28), date(2025, 2, 28)),
+            (
+                datetime(2025, 2, 27, 23, 0, 0),
+                datetime(2025, 2, 28, 23, 0, 0)
+            )
+        );
+    }
+
+    #[test]
+    fn day_end_is_the_last_second_of_the_day() {
+        assert_eq!(
+            day_end_utc(&hours(-8), date(2024, 12, 31)),
+            datetime(2025, 1, 1, 7, 59, 59)
+        );
+        assert_eq!(
+            day_end_utc(&hours(1), date(2024, 2, 28)),
+            datetime(2024, 2, 28, 22, 59, 59)
+        );
+        assert_eq!(
+            day_end_utc(&hours(1), date(2025, 2, 28)),
+            datetime(2025, 2, 28, 22, 59, 59)
+        );
+        assert_eq!(
+            local_day_end(date(2025, 2, 28)),
+            day_end_utc(&Local, date(2025, 2, 28))
+        );
+    }
+
+    #[test]
+    fn local_date_at_day_edges() {
+        assert_eq!(
+            local_date(&hours(-8), &datetime(2025, 1, 1, 7, 59, 59)),
+            date(2024, 12, 31)
+        );
+        assert_eq!(
+            local_date(&hours(-8), &datetime(2025, 1, 1, 8, 0, 0)),
+            date(2025, 1, 1)
+        );
+        assert_eq!(
+            local_date(&hours(1), &datetime(2024, 2, 28, 23, 30, 0)),
+            date(2024, 2, 29)
+        );
+        assert_eq!(
+            local_date(&hours(1), &datetime(2025, 2, 28, 23, 30, 0)),
+            date(2025, 3, 1)
+        );
+    }
+
+    #[test]
+    fn day_start_in_the_spring_forward_gap() {
+        // Midnight of March 30 does not exist: the day starts at 01:00 UTC+2.
+        assert_eq!(
+            day_start_utc(&MidnightDst, date(2025, 3, 30)),
+            datetime(2025, 3, 29, 23, 0, 0)
+        );
+        assert_eq!(
+            day_range_utc(&MidnightDst, date(2025, 3, 30), date(2025, 3, 30)),
+            (
+                datetime(2025, 3, 29, 23, 0, 0),
+                datetime(2025, 3, 30, 22, 0, 0)
+            )
+        );
+        assert_eq!(
+            day_end_utc(&MidnightDst, date(2025, 3, 29)),
+            datetime(2025, 3, 29, 22, 59, 59)
+        );
+        assert_eq!(
+            local_date(&MidnightDst, &datetime(2025, 3, 29, 23, 0, 0)),
+            date(2025, 3, 30)
+        );
+    }
+
+    #[test]
+    fn day_start_in_the_fall_back_overlap() {
+        // Midnight of October 26 happens twice: the day starts at the first.
+        assert_eq!(
+            day_start_utc(&MidnightDst, date(2025, 10, 26)),
+            datetime(2025, 10, 25, 22, 0, 0)
+        );
+        assert_eq!(
+            day_range_utc(&MidnightDst, date(2025, 10, 26), date(2025, 10, 26)),
+            (
+                datetime(2025, 10, 25, 22, 0, 0),
+                datetime(2025, 10, 26, 23, 0, 0)
+            )
+        );
+        assert_eq!(
+            day_end_utc(&MidnightDst, date(2025, 10, 25)),
+            datetime(2025, 10, 25, 21, 59, 59)
+        );
+        // Both readings of 00:30 are on October 26.
+        assert_eq!(
+            local_date(&MidnightDst, &datetime(2025, 10, 25, 22, 30, 0)),
+            date(2025, 10, 26)
+        );
+        assert_eq!(
+            local_date(&MidnightDst, &datetime(2025, 10, 25, 23, 30, 0)),
+            date(2025, 10, 26)
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::Duration;
+
+use crate::models::MoneyTransaction;
+
+/// Amounts closer than this fraction of the larger one count as the same.
+const AMOUNT_TOLERANCE: f32 = 0.01;
+/// Minimum similarity of descriptions and payees, from 0 to 1.
+const MIN_TEXT_SIMILARITY: f32 = 0.6;
+
+/// Pairs of transactions that are likely the same movement entered twice:
+/// same account and direction, close amounts, dates at most `window_days`
+/// apart and similar descriptions. Each pair is given as (older, newer) ids,
+/// and pairs in `dismissed` are left out.
+pub fn find_duplicates(
+    transactions: &[MoneyTransaction],
+    window_days: u32,
+    dismissed: &HashSet<(i32, i32)>,
+) -> Vec<(i32, i32)> {
+    let mut by_account: HashMap<i32, Vec<&MoneyTransaction>> = HashMap::new();
+    for t in transactions {
+        by_account.entry(t.bank_account).or_default().push(t);
+    }
+
+    let window = Duration::days(window_days as i64);
+    let mut pairs = vec![];
+    for account_transactions in by_account.values_mut() {
+        account_transactions.sort_by_key(|t| (t.transaction_date, t.id));
+        for (i, first) in account_transactions.iter().enumerate() {
+            for second in &account_transactions[i + 1..] {
+                if second.transaction_date - first.transaction_date > window {
+                    break;
+                }
+                let pair = (first.id, second.id);
+                if is_duplicate(first, second) && !dismissed.contains(&pair) {
+                    pairs.push(pair);
+                }
+            }
+        }
+    }
+    pairs.sort();
+    pairs
+}
+
+fn is_duplicate(first: &MoneyTransaction, second: &MoneyTransaction) -> bool {
+    let largest = first.amount.abs().max(second.amount.abs());
+    first.is_expense == second.is_expense
+        && (first.amount - second.amount).abs() <= (largest * AMOUNT_TOLERANCE).max(0.01)
+        && text_similarity(&transaction_text(first), &transaction_text(second))
+            >= MIN_TEXT_SIMILARITY
+}
+
+fn transaction_text(t: &MoneyTransaction) -> String {
+    format!(
+        "{} {}",
+        t.payee.as_deref().unwrap_or_default(),
+        t.description
+    )
+    .to_lowercase()
+    .chars()
+    .filter(|c| c.is_alphanumeric())
+    .collect()
+}
+
+/// Dice coefficient of the character bigrams of two strings. Two empty
+/// texts are considered equal, so entries without notes still match.
+fn text_similarity(a: &str, b: &str) -> f32 {
+    if a == b {
+        return 1.;
+    }
+    let bigrams = |s: &str| -> Vec<(char, char)> {
+        let chars: Vec<char> = s.chars().collect();
+        chars.windows(2).map(|w| (w[0], w[1])).collect()
+    };
+    let a_bigrams = bigrams(a);
+    let mut b_bigrams = bigrams(b);
+    if a_bigrams.is_empty() || b_bigrams.is_empty() {
+        return 0.;
+    }
+
+    let total = (a_bigrams.len() + b_bigrams.len()) as f32;
+    let mut shared = 0;
+    for bigram in a_bigrams {
+        if let Some(position) = b_bigrams.iter().position(|b| *b == bigram) {
+            b_bigrams.swap_remove(position);
+            shared += 1;
+        }
+    }
+    2. * shared as f32 / total
+}
@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+
+use crate::models::{InvestmentKind, InvestmentTransaction, Security, SecurityPrice};
+
+#[derive(Clone, Debug)]
+pub struct Holding {
+    pub security_id: i32,
+    pub symbol: String,
+    pub quantity: f32,
+    pub cost_basis: f32,
+    pub price: Option<f32>,
+    pub price_date: Option<NaiveDateTime>,
+}
+
+impl Holding {
+    /// Market value at the latest known price, or at cost when no price is available.
+    pub fn market_value(&self) -> f32 {
+        match self.price {
+            Some(price) => self.quantity * price,
+            None => self.cost_basis,
+        }
+    }
+
+    pub fn unrealized_gain(&self) -> f32 {
+        self.market_value() - self.cost_basis
+    }
+}
+
+/// Cash effect of an investment operation on the account balance.
+pub fn cash_effect(transaction: &InvestmentTransaction) -> f32 {
+    match transaction.kind() {
+        InvestmentKind::Buy => -transaction.amount,
+        InvestmentKind::Sell | InvestmentKind::Dividend => transaction.amount,
+    }
+}
+
+/// Latest price of every security, by security id.
+pub fn latest_prices(prices: &[SecurityPrice]) -> HashMap<i32, (NaiveDateTime, f32)> {
+    let mut latest: HashMap<i32, (NaiveDateTime, f32)> = HashMap::new();
+    for p in prices {
+        match latest.get(&p.security_id) {
+            Some((date, _)) if *date >= p.price_date => {}
+            _ => {
+                latest.insert(p.security_id, (p.price_date, p.price));
+            }
+        }
+    }
+    latest
+}
+
+/// Open positions of an account, with the cost basis computed using the
+/// average cost method.
+pub fn holdings(
+    transactions: &[InvestmentTransaction],
+    securities: &[Security],
+    prices: &HashMap<i32, (NaiveDateTime, f32)>,
+) -> Vec<Holding> {
+    let mut sorted: Vec<&InvestmentTransaction> = transactions.iter().collect();
+    sorted.sort_by_key(|t| (t.transaction_date, t.id));
+
+    let mut positions: Vec<Holding> = vec![];
+    for t in sorted {
+        let position = match positions
+            .iter()
+            .position(|h| h.security_id == t.security_id)
+        {
+            Some(index) => &mut positions[index],
+            None => {
+                positions.push(Holding {
+                    security_id: t.security_id,
+                    symbol: securities
+                        .iter()
+                        .find(|s| s.id == t.security_id)
+                        .map(|s| s.symbol.clone())
+                        .unwrap_or_default(),
+                    quantity: 0.,
+                    cost_basis: 0.,
+                    price: None,
+                    price_date: None,
+                });
+                positions.last_mut().unwrap()
+            }
+        };
+
+        match t.kind() {
+            InvestmentKind::Buy => {
+                position.quantity += t.quantity;
+                position.cost_basis += t.amount;
+            }
+            InvestmentKind::Sell => {
+                if position.quantity > 0. {
+                    let sold = t.quantity.min(position.quantity);
+                    position.cost_basis -= position.cost_basis * sold / position.quantity;
+                }
+                position.quantity -= t.quantity;
+            }
+            InvestmentKind::Dividend => {}
+        }
+        // fall back to the last traded price until a quote is recorded
+        if t.kind() != InvestmentKind::Dividend && t.price > 0. {
+            position.price = Some(t.price);
+            position.price_date = Some(t.transaction_date);
+        }
+    }
+
+    for position in positions.iter_mut() {
+        if let Some((date, price)) = prices.get(&position.security_id) {
+            if position.price_date.is_none_or(|traded| *date >= traded) {
+                position.price = Some(*price);
+                position.price_date = Some(*date);
+            }
+        }
+    }
+
+    positions
+        .into_iter()
+        .filter(|h| h.quantity.abs() > f32::EPSILON)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, month, day)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+    }
+
+    fn trade(
+        id: i32,
+        kind: InvestmentKind,
+        quantity: f32,
+        price: f32,
+        transaction_date: NaiveDateTime,
+    ) -> InvestmentTransaction {
+        InvestmentTransaction {
+            id,
+            account_id: 1,
+            security_id: 1,
+            kind: kind.into(),
+            quantity,
+            price,
+            amount: quantity * price,
+            transaction_date,
+        }
+    }
+
+    fn securities() -> Vec<Security> {
+        vec![Security {
+            id: 1,
+            symbol: "ACME".to_string(),
+            security_name: "Acme Corp".to_string(),
+        }]
+    }
+
+    fn price(id: i32, price_date: NaiveDateTime, price: f32) -> SecurityPrice {
+        SecurityPrice {
+            id,
+            security_id: 1,
+            price_date,
+            price,
+        }
+    }
+
+    #[test]
+    fn average_cost_after_partial_sell() {
+        let transactions = vec![
+            trade(3, InvestmentKind::Sell, 5., 30., date(3, 1)),
+            trade(1, InvestmentKind::Buy, 10., 10., date(1, 1)),
+            trade(2, InvestmentKind::Buy, 10., 20., date(2, 1)),
+        ];
+
+        let holdings = holdings(&transactions, &securities(), &HashMap::new());
+
+        assert_eq!(holdings.len(), 1);
+        let holding = &holdings[0];
+        assert_eq!(holding.symbol, "ACME");
+        assert_eq!(holding.quantity, 15.);
+        assert_eq!(holding.cost_basis, 225.);
+        // valued at the last traded price
+        assert_eq!(holding.price, Some(30.));
+        assert_eq!(holding.market_value(), 450.);
+        assert_eq!(holding.unrealized_gain(), 225.);
+    }
+
+    #[test]
+    fn closed_positions_are_dropped() {
+        let transactions = vec![
+            trade(1, InvestmentKind::Buy, 10., 10., date(1, 1)),
+            trade(2, InvestmentKind::Sell, 10., 12., date(2, 1)),
+        ];
+
+        assert!(holdings(&transactions, &securities(), &HashMap::new()).is_empty());
+    }
+
+    #[test]
+    fn quotes_newer_than_the_last_trade_win() {
+        let transactions = vec![trade(1, InvestmentKind::Buy, 10., 10., date(2, 1))];
+
+        let older = latest_prices(&[price(1, date(1, 15), 8.)]);
+        assert_eq!(
+            holdings(&transactions, &securities(), &older)[0].price,
+            Some(10.)
+        );
+
+        let newer = latest_prices(&[price(1, date(3, 1), 12.), price(2, date(2, 15), 11.)]);
+        let holding = &holdings(&transactions, &securities(), &newer)[0];
+        assert_eq!(holding.price, Some(12.));
+        assert_eq!(holding.price_date, Some(date(3, 1)));
+    }
+
+    #[test]
+    fn cash_moves_out_on_buys_only() {
+        let buy = trade(1, InvestmentKind::Buy, 2., 50., date(1, 1));
+        let sell = trade(2, InvestmentKind::Sell, 2., 50., date(1, 2));
+        let dividend = InvestmentTransaction {
+            amount: 7.5,
+            ..trade(3, InvestmentKind::Dividend, 0., 0., date(1, 3))
+        };
+
+        assert_eq!(cash_effect(&buy), -100.);
+        assert_eq!(cash_effect(&sell), 100.);
+        assert_eq!(cash_effect(&dividend), 7.5);
+    }
+}
@@ -1 +1,7 @@
-pub mod dates;
\ No newline at end of file
+pub mod amortization;
+pub mod classifier;
+pub mod credit_card;
+pub mod dates;
+pub mod duplicates;
+pub mod investments;
+pub mod rules;
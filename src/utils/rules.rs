@@ -0,0 +1,247 @@
+use regex::{Regex, RegexBuilder};
+
+use crate::models::{NewMoneyTransaction, Rule};
+
+/// How a rule matches the description of a transaction.
+enum TextMatch {
+    Any,
+    Contains(String), // Lowercase, matched ignoring case
+    Pattern(Regex),
+}
+
+/// Rules ready to be matched against transactions, in the order they are
+/// checked. Rules with an invalid regular expression never match.
+pub struct RuleSet {
+    rules: Vec<(Rule, Option<TextMatch>)>,
+}
+
+impl RuleSet {
+    pub fn new(rules: Vec<Rule>) -> Self {
+        Self {
+            rules: rules
+                .into_iter()
+                .map(|rule| {
+                    let text_match = text_match(&rule);
+                    if text_match.is_none() {
+                        log::warn!("rule '{}' has an invalid pattern", rule.rule_name);
+                    }
+                    (rule, text_match)
+                })
+                .collect(),
+        }
+    }
+
+    /// The first rule matching a transaction with the given account, amount
+    /// and description.
+    pub fn find(&self, bank_account: i32, amount: f32, description: &str) -> Option<&Rule> {
+        self.rules
+            .iter()
+            .find(|(rule, text_match)| {
+                text_match.as_ref().is_some_and(|text_match| {
+                    matches(rule, text_match, bank_account, amount, description)
+                })
+            })
+            .map(|(rule, _)| rule)
+    }
+
+    /// Applies the first matching rule to a new transaction, returning the
+    /// tag it sets, if any.
+    pub fn apply(&self, transaction: &mut NewMoneyTransaction) -> Option<i32> {
+        let rule = self.find(
+            transaction.bank_account,
+            transaction.amount,
+            &transaction.description,
+        )?;
+        if let Some(category_id) = rule.set_category {
+            transaction.transaction_category = category_id;
+        }
+        if let Some(payee) = &rule.set_payee {
+            transaction.payee = Some(payee.clone());
+        }
+        if let Some(note) = &rule.set_note {
+            transaction.description = note.clone();
+        }
+        rule.set_tag
+    }
+}
+
+/// Checks the text a rule looks for, which must be a valid regular
+/// expression when `is_regex` is set.
+pub fn validate_pattern(text: &str, is_regex: bool) -> Result<(), String> {
+    if is_regex {
+        build_regex(text).map(|_| ()).map_err(|e| e.to_string())
+    } else {
+        Ok(())
+    }
+}
+
+fn text_match(rule: &Rule) -> Option<TextMatch> {
+    match rule.match_text.as_deref().filter(|text| !text.is_empty()) {
+        None => Some(TextMatch::Any),
+        Some(text) if rule.match_regex => build_regex(text).ok().map(TextMatch::Pattern),
+        Some(text) => Some(TextMatch::Contains(text.to_lowercase())),
+    }
+}
+
+fn build_regex(text: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(text).case_insensitive(true).build()
+}
+
+fn matches(
+    rule: &Rule,
+    text_match: &TextMatch,
+    bank_account: i32,
+    amount: f32,
+    description: &str,
+) -> bool {
+    if rule.match_account.is_some_and(|a| a != bank_account) {
+        return false;
+    }
+    if rule.match_min_amount.is_some_and(|min| amount < min) {
+        return false;
+    }
+    if rule.match_max_amount.is_some_and(|max| amount > max) {
+        return false;
+    }
+    match text_match {
+        TextMatch::Any => true,
+        TextMatch::Contains(text) => description.to_lowercase().contains(text.as_str()),
+        TextMatch::Pattern(regex) => regex.is_match(description),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn rule(id: i32) -> Rule {
+        Rule {
+            id,
+            rule_name: format!("Rule {}", id),
+            match_text: None,
+            match_regex: false,
+            match_min_amount: None,
+            match_max_amount: None,
+            match_account: None,
+            set_category: None,
+            set_payee: None,
+            set_tag: None,
+            set_note: None,
+        }
+    }
+
+    fn text_rule(id: i32, text: &str, match_regex: bool) -> Rule {
+        Rule {
+            match_text: Some(text.to_string()),
+            match_regex,
+            ..rule(id)
+        }
+    }
+
+    fn found(rules: &RuleSet, bank_account: i32, amount: f32, description: &str) -> Option<i32> {
+        rules
+            .find(bank_account, amount, description)
+            .map(|rule| rule.id)
+    }
+
+    #[test]
+    fn contains_ignores_case_and_is_literal() {
+        let rules = RuleSet::new(vec![
+            text_rule(1, "COFFEE", false),
+            text_rule(2, "^amzn", false),
+        ]);
+
+        assert_eq!(found(&rules, 1, 3., "Morning coffee at Rossi"), Some(1));
+        assert_eq!(found(&rules, 1, 3., "AMZN Mktp DE"), None);
+        assert_eq!(found(&rules, 1, 3., "Refund ^AMZN"), Some(2));
+    }
+
+    #[test]
+    fn regex_ignores_case() {
+        let rules = RuleSet::new(vec![text_rule(1, r"^amzn\b", true)]);
+
+        assert_eq!(found(&rules, 1, 20., "AMZN Mktp DE"), Some(1));
+        assert_eq!(found(&rules, 1, 20., "Refund AMZN"), None);
+        assert_eq!(found(&rules, 1, 20., "AMZNPrime"), None);
+    }
+
+    #[test]
+    fn invalid_patterns_never_match() {
+        let rules = RuleSet::new(vec![text_rule(1, "(unclosed", true), rule(2)]);
+
+        assert_eq!(found(&rules, 1, 5., "(unclosed"), Some(2));
+        assert!(validate_pattern("(unclosed", true).is_err());
+        assert!(validate_pattern("(unclosed", false).is_ok());
+        assert!(validate_pattern(r"^\d+$", true).is_ok());
+    }
+
+    #[test]
+    fn amount_bounds_are_inclusive() {
+        let rules = RuleSet::new(vec![Rule {
+            match_min_amount: Some(10.),
+            match_max_amount: Some(20.),
+            ..rule(1)
+        }]);
+
+        assert_eq!(found(&rules, 1, 10., "x"), Some(1));
+        assert_eq!(found(&rules, 1, 20., "x"), Some(1));
+        assert_eq!(found(&rules, 1, 9.99, "x"), None);
+        assert_eq!(found(&rules, 1, 20.01, "x"), None);
+    }
+
+    #[test]
+    fn account_and_order() {
+        let rules = RuleSet::new(vec![
+            Rule {
+                match_account: Some(2),
+                ..text_rule(1, "rent", false)
+            },
+            text_rule(2, "rent", false),
+        ]);
+
+        assert_eq!(found(&rules, 2, 800., "Rent March"), Some(1));
+        assert_eq!(found(&rules, 1, 800., "Rent March"), Some(2));
+        assert_eq!(found(&rules, 1, 800., "Groceries"), None);
+    }
+
+    #[test]
+    fn apply_sets_the_fields_of_the_first_match() {
+        let rules = RuleSet::new(vec![Rule {
+            set_category: Some(7),
+            set_payee: Some("Rossi".to_string()),
+            set_tag: Some(3),
+            ..text_rule(1, "coffee", false)
+        }]);
+        let transaction = NewMoneyTransaction {
+            bank_account: 1,
+            transaction_category: 1,
+            description: "Coffee".to_string(),
+            amount: 3.5,
+            transaction_date: NaiveDate::from_ymd_opt(2025, 1, 2)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap(),
+            is_expense: true,
+            status: 0,
+            payee: None,
+            external_id: None,
+        };
+
+        let mut matched = transaction.clone();
+        assert_eq!(rules.apply(&mut matched), Some(3));
+        assert_eq!(matched.transaction_category, 7);
+        assert_eq!(matched.payee.as_deref(), Some("Rossi"));
+        // the note is kept when the rule sets none
+        assert_eq!(matched.description, "Coffee");
+
+        let mut other = NewMoneyTransaction {
+            description: "Fuel".to_string(),
+            ..transaction
+        };
+        assert_eq!(rules.apply(&mut other), None);
+        assert_eq!(other.transaction_category, 1);
+        assert_eq!(other.payee, None);
+    }
+}
@@ -0,0 +1,24 @@
+use cosmic::{
+    iced::{Length, Padding},
+    widget::{self, Space},
+    Element,
+};
+
+/// Form field with its label above it, filling the width it is given.
+pub fn labeled<'a, M: 'a>(label: String, content: impl Into<Element<'a, M>>) -> Element<'a, M> {
+    widget::column()
+        .push(widget::text::text(label))
+        .push(Space::with_height(3))
+        .push(content)
+        .width(Length::Fill)
+        .into()
+}
+
+/// Card grouping a section of a form.
+pub fn card<'a, M: 'a>(content: impl Into<Element<'a, M>>) -> Element<'a, M> {
+    widget::container(content)
+        .padding(Padding::new(10.))
+        .width(Length::Fill)
+        .class(cosmic::theme::Container::Card)
+        .into()
+}
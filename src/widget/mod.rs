@@ -1 +1,3 @@
-pub mod date_picker;
\ No newline at end of file
+pub mod date_picker;
+pub mod form;
+pub mod transaction_card;
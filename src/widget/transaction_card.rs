@@ -0,0 +1,72 @@
+use crate::{
+    fl,
+    models::{Account, Category, MoneyTransaction},
+};
+use chrono::{Local, TimeZone};
+use cosmic::{
+    iced::{alignment::Vertical, Length, Padding},
+    widget, Element,
+};
+
+/// Read-only card for a transaction listed outside the Transactions page,
+/// showing its date, category, account, amount, payee and note.
+pub fn transaction_card<'a, M: 'a>(
+    t: &'a MoneyTransaction,
+    categories: &[Category],
+    accounts: &[Account],
+    currency_symbol: &str,
+) -> Element<'a, M> {
+    let category = categories
+        .iter()
+        .find(|c| c.id == t.transaction_category)
+        .map(|c| c.name.clone())
+        .unwrap_or_else(|| fl!("not-found"));
+    let account = accounts
+        .iter()
+        .find(|a| a.id == t.bank_account)
+        .map(|a| a.name.clone())
+        .unwrap_or_else(|| fl!("not-found"));
+
+    widget::container(
+        widget::column()
+            .push(
+                widget::row()
+                    .push(
+                        widget::text::text(
+                            Local
+                                .from_utc_datetime(&t.transaction_date)
+                                .format("%d-%m-%Y")
+                                .to_string(),
+                        )
+                        .width(Length::Fill),
+                    )
+                    .push(widget::text::text(category).width(Length::Fill))
+                    .push(widget::text::text(account).width(Length::Fill))
+                    .push(widget::text::text(format!(
+                        "{}{:.2} {}",
+                        if t.is_expense { "-" } else { "+" },
+                        t.amount,
+                        currency_symbol
+                    )))
+                    .align_y(Vertical::Center),
+            )
+            .push_maybe(
+                t.payee
+                    .as_ref()
+                    .map(|payee| widget::text::text(format!("{}: {}", fl!("payee"), payee))),
+            )
+            .push_maybe(if !t.description.is_empty() {
+                Some(widget::text::text(format!(
+                    "{}: {}",
+                    fl!("note"),
+                    t.description
+                )))
+            } else {
+                None
+            }),
+    )
+    .width(Length::Fill)
+    .padding(Padding::new(10.))
+    .class(cosmic::theme::Container::Card)
+    .into()
+}